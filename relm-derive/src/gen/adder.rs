@@ -20,8 +20,11 @@
  */
 
 //! The adder adds the calls to set_property() or emit(Msg) whenever we assign to an attribute of
-//! the model.
+//! the model or call a method on it in a statement, like `self.model.items.push(item);`.
 
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, TokenStreamExt};
 use syn::{
     Expr,
@@ -29,26 +32,32 @@ use syn::{
     ExprAssignOp,
     ExprBlock,
     ExprField,
+    ExprIndex,
+    ExprMethodCall,
+    ExprParen,
     ExprPath,
     Ident,
     Path,
     Stmt,
     parse,
 };
-use syn::Expr::{Assign, AssignOp, Block, Field};
-use syn::fold::{Fold, fold_expr};
+use syn::Expr::{Assign, AssignOp, Block, Field, Index, MethodCall, Paren};
+use syn::fold::{Fold, fold_expr, fold_stmt};
 use syn::Member::Named;
 
-use super::{MsgModelMap, PropertyModelMap};
+use super::{ListModelMap, MsgModelMap, PropertyModelMap, sent_value_key};
+use super::generator::{gen_cached_set_property, gen_set_property, value_ident};
 
 pub struct Adder<'a> {
+    list_map: &'a ListModelMap,
     msg_map: &'a MsgModelMap,
     property_map: &'a PropertyModelMap,
 }
 
 impl<'a> Adder<'a> {
-    pub fn new(property_map: &'a PropertyModelMap, msg_map: &'a MsgModelMap) -> Self {
+    pub fn new(property_map: &'a PropertyModelMap, msg_map: &'a MsgModelMap, list_map: &'a ListModelMap) -> Self {
         Adder {
+            list_map,
             msg_map,
             property_map,
         }
@@ -61,7 +70,7 @@ impl<'a> Adder<'a> {
         let new_statements =
             if let Field(ExprField { ref base, member: Named(ref ident), .. }) = lhs {
                 if is_model_path(base) {
-                    Some(create_stmts(ident, self.property_map, self.msg_map))
+                    Some(create_stmts(ident, self.property_map, self.msg_map, self.list_map))
                 }
                 else {
                     None
@@ -94,6 +103,25 @@ impl<'a> Fold for Adder<'a> {
         let new_expr = fold_expr(self, expr);
         self.fold_assign(lhs_clone, new_expr)
     }
+
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        let stmt = fold_stmt(self, stmt);
+        let field =
+            match stmt {
+                Stmt::Semi(MethodCall(ExprMethodCall { ref receiver, .. }), _) => model_field(receiver).cloned(),
+                _ => None,
+            };
+        if let Some(field) = field {
+            let stmts = create_stmts(&field, self.property_map, self.msg_map, self.list_map);
+            if !stmts.is_empty() {
+                return parse(quote! {{
+                    #stmt
+                    #(#stmts)*
+                }}.into()).expect("statements");
+            }
+        }
+        stmt
+    }
 }
 
 #[derive(Debug, Eq, Hash, PartialEq)]
@@ -106,60 +134,178 @@ pub struct Message {
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub struct Property {
     pub expr: Expr,
+    pub is_bound: bool,
+    pub is_cached: bool,
+    pub is_popover: bool,
     pub is_relm_widget: bool,
     pub name: Ident,
     pub widget_name: Ident,
 }
 
-fn create_stmts(ident: &Ident, property_map: &PropertyModelMap, msg_map: &MsgModelMap) -> Vec<Stmt> {
+fn create_stmts(ident: &Ident, property_map: &PropertyModelMap, msg_map: &MsgModelMap, list_map: &ListModelMap)
+    -> Vec<Stmt>
+{
     let mut stmts = vec![];
     stmts.append(&mut create_stmts_for_props(ident, property_map));
     stmts.append(&mut create_stmts_for_msgs(ident, msg_map));
+    stmts.append(&mut create_stmts_for_lists(ident, list_map));
     stmts
 }
 
+fn create_stmts_for_lists(ident: &Ident, list_map: &ListModelMap) -> Vec<Stmt> {
+    let mut stmts = vec![];
+    if let Some(methods) = list_map.get(ident) {
+        for method in methods {
+            stmts.push(create_stmt_for_list(ident, method));
+        }
+    }
+    stmts
+}
+
+fn create_stmt_for_list(ident: &Ident, method: &Ident) -> Stmt {
+    let stmt = quote_spanned! { ident.span() =>
+        { self.#method(); }
+    };
+    block_stmt(stmt)
+}
+
 fn create_stmts_for_msgs(ident: &Ident, msg_map: &MsgModelMap) -> Vec<Stmt> {
     let mut stmts = vec![];
     if let Some(messages) = msg_map.get(ident) {
         for msg in messages {
-            let widget_name = &msg.widget_name;
-            let mut value = quote! {};
-            value.append_all(&[&msg.expr]);
-            let variant = &msg.name;
-            let stmt = quote_spanned! { ident.span() =>
-                { self.components.#widget_name.stream().emit(#variant(#value)); }
-            };
-            let expr: Expr = parse(stmt.into())
-                .expect("parse() in create_stmts");
-            if let Block(ExprBlock { ref block, .. }) = expr {
-                stmts.push(block.stmts[0].clone());
-            }
+            stmts.push(create_stmt_for_msg(ident, msg));
         }
     }
     stmts
 }
 
+fn create_stmt_for_msg(ident: &Ident, msg: &Message) -> Stmt {
+    let widget_name = &msg.widget_name;
+    let mut value = quote! {};
+    value.append_all(&[&msg.expr]);
+    let variant = &msg.name;
+    let key = sent_value_key(widget_name, variant);
+    let value_ident = value_ident(&msg.expr);
+    // Only send the message when the value changed since the last time it was sent.
+    let stmt = quote_spanned! { ident.span() =>
+        {{
+            let #value_ident = #value;
+            if self.components.__relm_sent_values.changed(#key, &#value_ident) {
+                self.components.#widget_name.stream().emit(#variant(#value_ident));
+            }
+        }}
+    };
+    block_stmt(stmt)
+}
+
 fn create_stmts_for_props(ident: &Ident, property_map: &PropertyModelMap) -> Vec<Stmt> {
     let mut stmts = vec![];
     if let Some(properties) = property_map.get(ident) {
         for property in properties {
-            let widget_name = &property.widget_name;
+            stmts.push(create_stmt_for_prop(ident, property));
+        }
+    }
+    stmts
+}
+
+fn create_stmt_for_prop(ident: &Ident, property: &Property) -> Stmt {
+    let widget_name = &property.widget_name;
+    let set_property =
+        if property.is_relm_widget {
             let prop_name = Ident::new(&format!("set_{}", property.name), property.name.span());
             let mut tokens = quote! {};
             tokens.append_all(&[&property.expr]);
-            let stmt =
-                quote_spanned! { ident.span() =>
-                    { self.widgets.#widget_name.#prop_name(#tokens); }
-                };
-            let expr: Expr = parse(stmt.into()).expect("parse() in create_stmts");
-            if let Block(ExprBlock { ref block, .. }) = expr {
-                stmts.push(block.stmts[0].clone());
+            quote! {
+                self.widgets.#widget_name.#prop_name(#tokens);
+            }
+        }
+        else if property.is_cached {
+            gen_cached_set_property(quote! { self.widgets.#widget_name }, property.is_popover, widget_name,
+                &property.name, &property.expr, quote! { self.components.__relm_sent_values })
+        }
+        else {
+            gen_set_property(quote! { self.widgets.#widget_name }, property.is_popover, &property.name, &property.expr)
+        };
+    let stmt =
+        if property.is_bound {
+            // Lock the stream so that the property change doesn't send the message of the
+            // binding back.
+            quote_spanned! { ident.span() =>
+                {{
+                    let _lock = self.relm.stream().lock();
+                    #set_property
+                }}
+            }
+        }
+        else {
+            quote_spanned! { ident.span() =>
+                { #set_property }
+            }
+        };
+    block_stmt(stmt)
+}
+
+/*
+ * Create the statements updating every property, message and list depending on the model, used
+ * by the update_view() method.
+ * A property depending on many model attributes is only updated once.
+ */
+pub fn create_update_view_stmts(property_map: &PropertyModelMap, msg_map: &MsgModelMap, list_map: &ListModelMap)
+    -> Vec<Stmt>
+{
+    let mut stmts = vec![];
+    let mut properties = HashSet::new();
+    for (ident, props) in property_map {
+        for property in props {
+            if properties.insert(property) {
+                stmts.push(create_stmt_for_prop(ident, property));
+            }
+        }
+    }
+    let mut messages = HashSet::new();
+    for (ident, msgs) in msg_map {
+        for msg in msgs {
+            if messages.insert(msg) {
+                stmts.push(create_stmt_for_msg(ident, msg));
+            }
+        }
+    }
+    let mut methods = HashSet::new();
+    for (ident, list_methods) in list_map {
+        for method in list_methods {
+            if methods.insert(method) {
+                stmts.push(create_stmt_for_list(ident, method));
             }
         }
     }
     stmts
 }
 
+// Get the statement inside a block: the block is needed to parse a statement.
+fn block_stmt(tokens: TokenStream) -> Stmt {
+    let expr: Expr = parse(tokens.into()).expect("parse() in create_stmts");
+    if let Block(ExprBlock { mut block, .. }) = expr {
+        block.stmts.remove(0)
+    }
+    else {
+        unreachable!()
+    }
+}
+
+/*
+ * Get the model attribute on which a method is called, like items in
+ * self.model.items.iter_mut().for_each(...).
+ */
+fn model_field(expr: &Expr) -> Option<&Ident> {
+    match *expr {
+        Field(ExprField { ref base, member: Named(ref ident), .. }) if is_model_path(base) => Some(ident),
+        Field(ExprField { ref base, .. }) => model_field(base),
+        Index(ExprIndex { ref expr, .. }) | Paren(ExprParen { ref expr, .. }) => model_field(expr),
+        MethodCall(ExprMethodCall { ref receiver, .. }) => model_field(receiver),
+        _ => None,
+    }
+}
+
 fn is_model_path(expr: &Expr) -> bool {
     if let Field(ExprField { ref base, ref member, .. }) = *expr {
         if let Expr::Path(ExprPath { path: Path { ref segments, .. }, ..}) = **base {
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Load the ui file given to the `#[widget(ui = "...")]` attribute.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use syn::{Error, LitStr};
+use syn::parse::Result;
+
+use super::parser::EitherWidget::{Gtk, Relm};
+use super::parser::Widget;
+
+#[derive(Debug)]
+pub struct UiFile {
+    ids: HashSet<String>,
+    pub path: String,
+}
+
+impl UiFile {
+    /// Read the ui file, whose path is relative to the root of the crate.
+    pub fn load(literal: &LitStr) -> Result<Self> {
+        let mut path = env::var("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        path.push(literal.value());
+        let content = fs::read_to_string(&path)
+            .map_err(|error| Error::new(literal.span(), format!("cannot read the ui file {}: {}", path.display(), error)))?;
+        Ok(UiFile {
+            ids: object_ids(&content),
+            path: path.to_string_lossy().into_owned(),
+        })
+    }
+
+    /*
+     * Check that every gtk widget of the view is an object of the ui file, so that a typo in a
+     * name is reported at compile time.
+     * The relm widgets are still created by the view and are added to their parent from the ui
+     * file.
+     */
+    pub fn check_widgets(&self, widgets: &[Widget]) -> Result<()> {
+        for widget in widgets {
+            self.check_widget(widget)?;
+        }
+        Ok(())
+    }
+
+    fn check_widget(&self, widget: &Widget) -> Result<()> {
+        match widget.widget {
+            Gtk(ref gtk_widget) => {
+                let id = widget.name.to_string();
+                if !self.ids.contains(&id) {
+                    return Err(Error::new(widget.name.span(), format!(
+                        "no object with id `{}` in the ui file {}; use #[name=\"id\"] to refer to an object", id,
+                        self.path)));
+                }
+                if !widget.init_parameters.is_empty() || !gtk_widget.construct_properties.is_empty() {
+                    return Err(Error::new(widget.name.span(), format!(
+                        "the widget `{}` is created by the ui file, so it cannot have construct parameters", id)));
+                }
+                self.check_widgets(&widget.children)?;
+            },
+            Relm(_) =>
+                if let Some(child) = widget.children.first() {
+                    return Err(Error::new(child.name.span(),
+                        "the children of a relm widget cannot be declared when using a ui file"));
+                },
+        }
+        for nested_view in widget.nested_views.values() {
+            self.check_widget(nested_view)?;
+        }
+        Ok(())
+    }
+}
+
+fn object_ids(content: &str) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    for object in content.split("<object").skip(1) {
+        // Skip the other tags starting with the same name, like <objects>.
+        if !object.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let tag = object.split('>').next().unwrap_or_default();
+        if let Some(id) = attribute(tag, "id") {
+            ids.insert(id.to_string());
+        }
+    }
+    ids
+}
+
+/*
+ * Get the value of the attribute `name` of a tag, whatever its position and whether its value is
+ * in single or double quotes.
+ */
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        rest = rest.trim_start();
+        let equal = rest.find('=')?;
+        let attribute_name = rest[..equal].trim_end();
+        let value = rest[equal + 1..].trim_start();
+        let quote = value.chars().next().filter(|&quote| quote == '"' || quote == '\'')?;
+        let end = value[1..].find(quote)? + 1;
+        if attribute_name == name {
+            return Some(&value[1..end]);
+        }
+        rest = &value[end + 1..];
+    }
+}
@@ -20,32 +20,48 @@
  */
 
 use std::collections::{HashMap, HashSet};
+use std::slice;
 
-use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote, quote_spanned};
 use syn::{
     Expr,
+    ExprArray,
+    ExprLit,
+    ExprTuple,
     Generics,
     Ident,
+    Lit,
+    Pat,
+    PatIdent,
     Path,
     Type,
     TypePath,
+    parse_quote,
     parse,
 };
-use syn::fold::Fold;
+use syn::fold::{Fold, fold_expr};
 use syn::spanned::Spanned;
+use syn::visit::Visit;
 
 use super::parser::{
+    ChildLoop,
+    ChildPropertyName,
     Event,
     GtkWidget,
     RelmWidget,
     Widget,
+    accessor_name,
+    dummy_ident,
 };
 use super::parser::EventValue::{CurrentWidget, ForeignWidget, NoEventValue};
 use super::parser::EventValueReturn::{CallReturn, Return, WithoutReturn};
+use super::parser::ChildPropertyName::{Name, Setter};
 use super::parser::EitherWidget::{Gtk, Relm};
-use super::transformer::Transformer;
-use super::{Driver, MODEL_IDENT};
+use super::signals::check_signal;
+use super::transformer::{Transformer, WidgetReferences};
+use super::{Driver, MODEL_IDENT, is_cached_property, property_value_key, sent_value_key};
+use super::walker::ModelVariableVisitor;
 
 use self::WidgetType::*;
 use self::WithParentheses::{WithParens, WithoutParens};
@@ -64,6 +80,8 @@ enum WidgetType {
 
 pub struct Gen {
     pub view: TokenStream,
+    pub list_methods: Vec<TokenStream>,
+    pub lists: Vec<Ident>,
     pub relm_widgets: HashMap<Ident, Path>,
     pub relm_components: HashMap<Ident, Path>,
     pub streams_to_save: HashSet<Ident>,
@@ -71,7 +89,7 @@ pub struct Gen {
 }
 
 pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
-    let mut generator = Generator::new(driver);
+    let mut generator = Generator::new(driver, widgets);
     let mut widget_tokens = quote! {};
     for (index, widget) in widgets.iter().enumerate() {
         // Only show the first item as the following could be non-widget like a gtk::Gesture.
@@ -110,6 +128,28 @@ pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
         .filter(|ident| generator.streams_to_save.contains(ident))
         .map(|name| quote! { #name.stream() });
 
+    let builder =
+        match driver.ui_file {
+            Some(ref ui_file) => {
+                let path = &ui_file.path;
+                quote! {
+                    let __relm_builder = ::gtk::Builder::from_string(include_str!(#path));
+                }
+            },
+            None => quote! {},
+        };
+
+    let list_names = &generator.list_names;
+    let relm_field =
+        if list_names.is_empty() && !driver.has_bindings {
+            quote! {}
+        }
+        else {
+            quote! {
+                relm: relm.clone(),
+            }
+        };
+
     let root_widget_expr =
         if driver.root_widget_is_relm {
             quote! {
@@ -121,6 +161,9 @@ pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
         };
 
     let view = quote_spanned! { name.span() =>
+        #[allow(unused_mut)]
+        let mut __relm_sent_values = ::relm::SentValues::new();
+        #builder
         #widget_tokens
 
         #(#events)*
@@ -137,14 +180,19 @@ pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
             },
             components: #components_name {
                 #(#component_names,)*
+                #(#list_names,)*
+                __relm_sent_values,
             },
             model: #model_ident,
+            #relm_field
         }
     };
     let container_impl = gen_container_impl(&generator, &widgets[0], driver.generic_types.as_ref().expect("generic types"));
 
     Gen {
         view,
+        list_methods: generator.list_methods,
+        lists: generator.list_names,
         relm_widgets: generator.relm_widgets,
         relm_components: generator.relm_components,
         streams_to_save: generator.streams_to_save,
@@ -156,23 +204,46 @@ struct Generator<'a> {
     container_names: HashMap<Option<String>, (Ident, Path)>,
     driver: Option<&'a mut Driver>,
     events: Vec<TokenStream>,
+    gtk_widget_types: HashMap<Ident, Path>,
+    // The tab labels are created before the root widget is known, but without a parent.
+    in_tab_label: bool,
+    // The gtk widgets are taken from the ui file instead of being created.
+    from_builder: bool,
+    list_methods: Vec<TokenStream>,
+    list_names: Vec<Ident>,
     properties: Vec<TokenStream>,
     relm_components: HashMap<Ident, Path>,
     relm_widgets: HashMap<Ident, Path>,
     streams_to_save: HashSet<Ident>,
+    // The values sent to the relm widgets are not tracked in the rows of a loop, which are
+    // updated by synchronizing the list.
+    track_sent_values: bool,
+    view_widgets: HashMap<Ident, WidgetType>,
     widget_names: Vec<Ident>,
 }
 
 impl<'a> Generator<'a> {
-    fn new(driver: &'a mut Driver) -> Self {
+    fn new(driver: &'a mut Driver, widgets: &[Widget]) -> Self {
+        let mut view_widgets = HashMap::new();
+        for widget in widgets {
+            collect_view_widgets(widget, &mut view_widgets);
+        }
+        let from_builder = driver.ui_file.is_some();
         Generator {
             container_names: HashMap::new(),
             driver: Some(driver),
             events: vec![],
+            from_builder,
+            gtk_widget_types: HashMap::new(),
+            in_tab_label: false,
+            list_methods: vec![],
+            list_names: vec![],
             properties: vec![],
             relm_components: HashMap::new(),
             relm_widgets: HashMap::new(),
             streams_to_save: HashSet::new(),
+            track_sent_values: true,
+            view_widgets,
             widget_names: vec![],
         }
     }
@@ -182,7 +253,44 @@ impl<'a> Generator<'a> {
     {
         let widget_name = &widget.name;
         if let Some(name) = parent {
-            if parent_widget_type == IsGtk {
+            if let Some(positional_add) = gen_positional_add(widget) {
+                let PositionalChild { method, args, .. } = positional_add;
+                let container = gen_container(name, parent_widget_type);
+                quote_spanned! { widget_name.span() =>
+                    #method(&#container, &#widget_name, #(#args),*);
+                }
+            }
+            else if let Some(ref pack_type) = widget.pack_type {
+                let container = gen_container(name, parent_widget_type);
+                quote_spanned! { widget_name.span() =>
+                    ::relm::pack(&#container, &#widget_name, ::gtk::PackType::#pack_type);
+                }
+            }
+            else if parent_widget_type == IsGtk && self.is_tree_view_column(widget, name) {
+                quote_spanned! { widget_name.span() =>
+                    ::gtk::TreeViewExt::append_column(&#name, &#widget_name);
+                }
+            }
+            else if parent_widget_type == IsGtk && is_cell_renderer(widget) {
+                let attributes = widget.properties.iter()
+                    .filter_map(|(key, value)| cell_attribute(value).map(|column| (key, column)))
+                    .map(|(key, column)| {
+                        let attribute = key.to_string().replace('_', "-");
+                        quote_spanned! { widget_name.span() =>
+                            ::gtk::CellLayoutExt::add_attribute(&#name, &#widget_name, #attribute, #column);
+                        }
+                    });
+                quote_spanned! { widget_name.span() =>
+                    ::gtk::CellLayoutExt::pack_start(&#name, &#widget_name, true);
+                    #(#attributes)*
+                }
+            }
+            else if parent_widget_type == IsGtk && self.is_submenu(widget, name) {
+                quote_spanned! { widget_name.span() =>
+                    ::gtk::GtkMenuItemExt::set_submenu(&#name, Some(&#widget_name));
+                }
+            }
+            else if parent_widget_type == IsGtk {
                 quote_spanned! { widget_name.span() =>
                     ::gtk::ContainerExt::add(&#name, &#widget_name);
                 }
@@ -196,7 +304,7 @@ impl<'a> Generator<'a> {
         else {
             let struct_name = &widget.typ;
             let driver = self.driver.as_mut().expect("driver");
-            if driver.root_widget_expr.is_none() {
+            if driver.root_widget_expr.is_none() && !self.in_tab_label {
                 driver.root_widget_type = Some(quote! {
                     #struct_name
                 });
@@ -239,7 +347,7 @@ impl<'a> Generator<'a> {
         }
         else {
             let driver = self.driver.as_mut().expect("driver");
-            if driver.root_widget_expr.is_none() {
+            if driver.root_widget_expr.is_none() && !self.in_tab_label {
                 driver.root_widget_type = Some(quote_spanned! { widget_name.span() =>
                     <#widget_type_ident as ::relm::Widget>::Root
                 });
@@ -262,48 +370,174 @@ impl<'a> Generator<'a> {
         }
     }
 
-    fn collect_event(&mut self, widget_name: TokenStream, name: &Ident, event: &Event) {
+    /// Generate the loops placed before the child at `position`.
+    fn child_loops(&mut self, widget: &Widget, position: usize) -> Vec<TokenStream> {
+        widget.loops.iter()
+            .enumerate()
+            .filter(|&(_, child_loop)| child_loop.position == position)
+            .map(|(index, child_loop)| {
+                let previous = index.checked_sub(1).map(|index| &widget.loops[index].name);
+                self.child_loop(&widget.name, child_loop, previous)
+            })
+            .collect()
+    }
+
+    /*
+     * Generate the list of children of a for loop.
+     * The rows are created by a nested generator so that their widgets are kept in the rows
+     * instead of the widget struct.
+     * The same synchronization code is used in a method called by update() when the model
+     * attributes used by the loop are updated.
+     * The rows are kept after those of the `previous` loop of the same container, whose number
+     * changes.
+     */
+    fn child_loop(&mut self, parent: &Ident, child_loop: &ChildLoop, previous: Option<&Ident>) -> TokenStream {
+        let row_widget = &child_loop.widget;
+        let mut generator = Generator::new(self.driver.take().expect("driver"), slice::from_ref(row_widget));
+        generator.from_builder = false;
+        generator.track_sent_values = false;
+        let row = generator.widget(row_widget, Some(parent), IsGtk, true);
+        self.driver = generator.driver.take();
+
+        let row_name = &row_widget.name;
+        let row_root =
+            match row_widget.widget {
+                Gtk(_) => quote! { #row_name.clone() },
+                Relm(_) => quote! { #row_name.widget().clone() },
+            };
+        let names = &generator.widget_names;
+        let events = &generator.events;
+        let properties = &generator.properties;
+        let updates = gen_row_updates(row_widget);
+
+        let mut remover = Transformer::new(MODEL_IDENT);
+        let expr = remover.fold_expr(child_loop.expr.clone());
+        let pat = &child_loop.pat;
+        let (key, items) =
+            match child_loop.key {
+                Some(ref key) => (quote! { #key }, quote! { #expr }),
+                None => (quote! { _ }, quote! {
+                    ::std::iter::Iterator::enumerate(::std::iter::IntoIterator::into_iter(#expr))
+                }),
+            };
+        let sync = quote_spanned! { row_name.span() =>
+            sync(&#parent, #items,
+                |#key, #pat| {
+                    #row
+                    #(#events)*
+                    #(#properties)*
+                    (::relm::Cast::upcast::<::gtk::Widget>(#row_root), (#(#names,)*))
+                },
+                |__relm_row, #key, #pat| {
+                    let (#(ref #names,)*) = *__relm_row;
+                    #updates
+                });
+        };
+
+        let list_name = &child_loop.name;
+        let method_name = child_loop.sync_method();
+        let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
+        self.list_methods.push(quote! {
+            #[allow(unused_variables, clippy::all)]
+            fn #method_name(&mut self) {
+                let relm = &self.relm;
+                let #model_ident = &self.model;
+                let #parent = self.widgets.#parent.clone();
+                self.components.#list_name.#sync
+            }
+        });
+        self.list_names.push(list_name.clone());
+
+        let new_list =
+            match previous {
+                Some(previous) => quote! { ::relm::ChildList::new_after(&#parent, &#previous) },
+                None => quote! { ::relm::ChildList::new(&#parent) },
+            };
+        quote! {
+            let mut #list_name = #new_list;
+            #list_name.#sync
+        }
+    }
+
+    fn collect_event(&mut self, widget_name: TokenStream, widget_type: Option<&Path>, name: &Ident, event: &Event) {
+        if let Some(error) = widget_type.and_then(|widget_type| check_signal(widget_type, name)) {
+            self.events.push(error);
+            return;
+        }
         let event_ident = Ident::new(&format!("connect_{}", name), name.span());
         let event_params = &event.params;
         let shared_values = gen_shared_values(&event.shared_values);
         let metadata = gen_event_metadata(event);
+        // Errors about the parameters of the handler, like their number, point to them, or to the
+        // signal name when there are none.
+        let connect_span = event.params_span.unwrap_or_else(|| name.span());
+        let mut references = self.widget_references();
         let connect =
-            match event.value {
-                CurrentWidget(WithoutReturn(ref event_value)) => quote_spanned! { widget_name.span() => {
-                    #shared_values
-                    relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #event_value);
-                }},
-                ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => quote! {{
-                    #shared_values
-                    relm::connect!(#widget_name, #event_ident(#(#event_params),*), #foreign_widget_name, #event_value);
-                }},
-                CurrentWidget(Return(ref value)) => {
-                    let event_value = &value.0;
-                    let return_value = &value.1;
-
-                    quote_spanned! { widget_name.span() => {
-                        #shared_values
-                        relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), return (#event_value, #return_value));
-                    }}
-                },
-                ForeignWidget(_, Return(_)) | ForeignWidget(_, CallReturn(_)) => unreachable!(),
-                CurrentWidget(CallReturn(ref func)) => quote_spanned! { widget_name.span() => {
-                    #shared_values
-                    relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #func);
-                }},
-                NoEventValue => panic!("no event value"),
+            if name == "notify" {
+                gen_notify_connect(&widget_name, event, &mut references)
+            }
+            else {
+                match event.value {
+                    CurrentWidget(WithoutReturn(ref event_value)) => {
+                        let event_value = references.fold_expr(event_value.clone());
+                        quote_spanned! { connect_span =>
+                            relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #event_value);
+                        }
+                    },
+                    ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => {
+                        let event_value = references.fold_expr(event_value.clone());
+                        quote! {
+                            relm::connect!(#widget_name, #event_ident(#(#event_params),*), #foreign_widget_name, #event_value);
+                        }
+                    },
+                    CurrentWidget(Return(ref value)) => {
+                        let event_value = references.fold_expr(value.0.clone());
+                        let return_value = references.fold_expr(value.1.clone());
+
+                        if let Some(span) = self_use_outside_model(value.1.to_token_stream()) {
+                            quote_spanned! { span =>
+                                compile_error!("Only `self.model` can be used in the return value of a signal handler");
+                            }
+                        }
+                        else if uses_self(&value.1) {
+                            let return_value = gen_model_return_value(&return_value);
+                            quote_spanned! { connect_span =>
+                                let __relm_handle = relm.clone();
+                                relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), return (#event_value, #return_value));
+                            }
+                        }
+                        else {
+                            quote_spanned! { connect_span =>
+                                relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), return (#event_value, #return_value));
+                            }
+                        }
+                    },
+                    ForeignWidget(_, Return(_)) | ForeignWidget(_, CallReturn(_)) => unreachable!(),
+                    CurrentWidget(CallReturn(ref func)) => {
+                        let func = references.fold_expr(func.clone());
+                        quote_spanned! { connect_span =>
+                            relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #func);
+                        }
+                    },
+                    NoEventValue => panic!("no event value"),
+                }
             };
-        self.events.push(connect);
+        let widget_clones = self.gen_widget_clones(&references.references);
+        self.events.push(quote_spanned! { widget_name.span() => {
+            #shared_values
+            #widget_clones
+            #connect
+        }});
     }
 
     fn collect_events(&mut self, widget: &Widget, gtk_widget: &GtkWidget) {
         let widget_name = &widget.name;
         for (name, event) in &gtk_widget.events {
-            self.collect_event(quote! { #widget_name }, name, event);
+            self.collect_event(quote! { #widget_name }, Some(&widget.typ), name, event);
         }
         for (&(ref child_name, ref name), event) in &widget.child_events {
             let child_ident = Ident::new(&format!("get_{}", child_name), child_name.span());
-            self.collect_event(quote! { #widget_name.#child_ident() }, name, event);
+            self.collect_event(quote! { #widget_name.#child_ident() }, None, name, event);
         }
     }
 
@@ -322,28 +556,60 @@ impl<'a> Generator<'a> {
                         }
                     };
                 let metadata = gen_event_metadata(event);
+                let mut references = self.widget_references();
                 let connect =
                     match event.value {
-                        CurrentWidget(WithoutReturn(ref event_value)) => quote_spanned! { widget_name.span() => {
-                            relm::connect!(#widget_name@#event_ident #params, relm, #metadata #event_value);
-                        }},
-                        ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => quote! {{
-                            relm::connect!(#widget_name@#event_ident #params, #foreign_widget_name,
-                                     #metadata #event_value);
-                        }},
+                        CurrentWidget(WithoutReturn(ref event_value)) => {
+                            let event_value = references.fold_expr(event_value.clone());
+                            quote_spanned! { widget_name.span() =>
+                                relm::connect!(#widget_name@#event_ident #params, relm, #metadata #event_value);
+                            }
+                        },
+                        ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => {
+                            let event_value = references.fold_expr(event_value.clone());
+                            quote! {
+                                relm::connect!(#widget_name@#event_ident #params, #foreign_widget_name,
+                                         #metadata #event_value);
+                            }
+                        },
                         CurrentWidget(Return(_)) | CurrentWidget(CallReturn(_)) | ForeignWidget(_, Return(_)) |
                             ForeignWidget(_, CallReturn(_)) => unreachable!(),
                         NoEventValue => panic!("no event value"),
                     };
-                self.events.push(connect);
+                let widget_clones = self.gen_widget_clones(&references.references);
+                self.events.push(quote_spanned! { widget_name.span() => {
+                    #widget_clones
+                    #connect
+                }});
             }
         }
         for (name, event) in &relm_widget.gtk_events {
-            self.collect_event(quote! { #widget_name.widget() }, name, event);
+            self.collect_event(quote! { #widget_name.widget() }, None, name, event);
         }
         for (&(ref child_name, ref name), event) in &widget.child_events {
             let child_ident = Ident::new(&format!("get_{}", child_name), child_name.span());
-            self.collect_event(quote! { #widget_name.widget().#child_ident() }, &name, event);
+            self.collect_event(quote! { #widget_name.widget().#child_ident() }, None, &name, event);
+        }
+    }
+
+    /*
+     * Generate the clones of the widgets referenced in an event handler, so that they can be moved
+     * in the closure.
+     */
+    fn gen_widget_clones(&self, references: &[Ident]) -> TokenStream {
+        let clones = references.iter()
+            .map(|name| {
+                match self.view_widgets[name] {
+                    IsGtk => quote_spanned! { name.span() =>
+                        let #name = #name.clone();
+                    },
+                    IsRelm => quote_spanned! { name.span() =>
+                        let #name = #name.widget().clone();
+                    },
+                }
+            });
+        quote! {
+            #(#clones)*
         }
     }
 
@@ -351,13 +617,33 @@ impl<'a> Generator<'a> {
         let mut properties = vec![];
         let mut visible_properties = vec![];
         for (key, value) in &widget.properties {
+            if is_cell_renderer(widget) && cell_attribute(value).is_some() {
+                // The attributes of a cell renderer are mapped to columns by add_child_or_show_all().
+                continue;
+            }
             let mut remover = Transformer::new(MODEL_IDENT);
             let new_value = remover.fold_expr(value.clone());
-            let property_func = Ident::new(&format!("set_{}", key), key.span());
-            let property = quote! {
-                #ident.#property_func(#new_value);
-            };
-            if key == "visible" {
+            let property =
+                if self.track_sent_values && records_property_value(widget, key, value) {
+                    // Record the value so that update() doesn't set it again if it didn't change.
+                    let value_ident = value_ident(&new_value);
+                    let changed = gen_property_value_changed(&widget.name, key, &value_ident,
+                        quote! { __relm_sent_values });
+                    let set_property = gen_set_property(ident.clone(), is_popover(widget), key,
+                        &parse_quote! { #value_ident });
+                    quote_spanned! { key.span() =>
+                        match #new_value {
+                            #value_ident => {
+                                let _ = #changed;
+                                #set_property
+                            },
+                        }
+                    }
+                }
+                else {
+                    gen_set_property(ident.clone(), is_popover(widget), key, &new_value)
+                };
+            if is_set_after_children(key) {
                 visible_properties.push(property);
             }
             else {
@@ -365,6 +651,10 @@ impl<'a> Generator<'a> {
             }
         }
         for (key, value) in &widget.nested_views {
+            if key == "tab" {
+                // The tab label is set on the parent by tab_label().
+                continue;
+            }
             let name = &value.name;
             let name =
                 match value.widget {
@@ -391,23 +681,46 @@ impl<'a> Generator<'a> {
         let widget_name = &widget.name;
         self.set_container(widget, widget_name, struct_name, false);
         self.widget_names.push(widget_name.clone());
+        self.gtk_widget_types.insert(widget_name.clone(), struct_name.clone());
 
         if widget.save {
             self.relm_widgets.insert(widget_name.clone(), struct_name.clone());
             self.streams_to_save.insert(widget_name.clone());
         }
 
-        let construct_widget = gen_construct_widget(widget, gtk_widget);
+        let construct_widget =
+            if self.from_builder {
+                gen_builder_object(widget)
+            }
+            else {
+                gen_construct_widget(widget, gtk_widget)
+            };
         self.collect_events(widget, gtk_widget);
+        if !gtk_widget.bindings.is_empty() {
+            self.driver.as_mut().expect("driver").has_bindings = true;
+        }
 
-        let children: Vec<_> = widget.children.iter()
-            .map(|child| self.widget(child, Some(widget_name), IsGtk, true))
-            .collect();
+        let mut children = vec![];
+        for (index, child) in widget.children.iter().enumerate() {
+            children.extend(self.child_loops(widget, index));
+            children.push(self.widget(child, Some(widget_name), IsGtk, true));
+        }
+        children.extend(self.child_loops(widget, widget.children.len()));
 
-        let add_child_or_show_all = self.add_child_or_show_all(widget, parent, parent_widget_type);
+        let add_child_or_show_all =
+            if self.from_builder && parent.is_some() {
+                // The objects of the ui file are already in their parent.
+                quote! {}
+            }
+            else {
+                self.add_child_or_show_all(widget, parent, parent_widget_type)
+            };
+        // Only the root is shown so that the visibility of the objects of the ui file is kept.
+        let show = show && (!self.from_builder || self.is_root(widget_name)) && is_widget(widget);
         let ident = quote! { #widget_name };
         let (properties, visible_properties) = self.gtk_set_prop_calls(widget, ident);
         let child_properties = gen_set_child_prop_calls(widget, parent, parent_widget_type, IsGtk);
+        let tab_label = self.tab_label(widget, parent, parent_widget_type, IsGtk);
         let set_style_classes: Vec<_> = widget.style_classes.iter().map(|style_class|
             quote_spanned! { widget_name.span() => gtk::StyleContextExt::add_class(&#widget_name.get_style_context(), &#style_class); }
         ).collect();
@@ -421,6 +734,12 @@ impl<'a> Generator<'a> {
             else {
                 quote! { }
             };
+        let adjustment_connections = gtk_widget.adjustment_messages.iter().map(|(adjustment, msg)| {
+            let orientation = adjustment_orientation(adjustment);
+            quote_spanned! { adjustment.span() =>
+                ::relm::connect_adjustment_value_changed(&#widget_name, #orientation, relm.stream(), #msg);
+            }
+        });
 
         quote_spanned! { widget_name.span() =>
             let #widget_name: #struct_name = #construct_widget;
@@ -430,10 +749,32 @@ impl<'a> Generator<'a> {
             #(#set_style_classes)*
             #show
             #(#visible_properties)*
+            #(#adjustment_connections)*
             #(#child_properties)*
+            #tab_label
         }
     }
 
+    /// A menu declared in a menu item is its submenu instead of its child.
+    fn is_submenu(&self, widget: &Widget, parent: &Ident) -> bool {
+        let parent_is_menu_item = self.gtk_widget_types.get(parent)
+            .map(|parent_type| type_name(parent_type).ends_with("MenuItem"))
+            .unwrap_or(false);
+        parent_is_menu_item && type_name(&widget.typ) == "Menu"
+    }
+
+    /// A column declared in a tree view is appended to its columns.
+    fn is_tree_view_column(&self, widget: &Widget, parent: &Ident) -> bool {
+        let parent_is_tree_view = self.gtk_widget_types.get(parent)
+            .map(|parent_type| type_name(parent_type) == "TreeView")
+            .unwrap_or(false);
+        parent_is_tree_view && type_name(&widget.typ) == "TreeViewColumn"
+    }
+
+    fn is_root(&self, widget_name: &Ident) -> bool {
+        self.driver.as_ref().expect("driver").root_widget.as_ref() == Some(widget_name)
+    }
+
     fn relm_widget(&mut self, widget: &Widget, relm_widget: &RelmWidget, parent: Option<&Ident>,
         parent_widget_type: WidgetType) -> TokenStream
     {
@@ -461,13 +802,35 @@ impl<'a> Generator<'a> {
         let add_or_create_widget = self.add_or_create_widget(
             parent, parent_widget_type, widget_name, widget_type_ident, &widget.init_parameters, widget.is_container);
         let child_properties = gen_set_child_prop_calls(widget, parent, parent_widget_type, IsRelm);
+        let tab_label = self.tab_label(widget, parent, parent_widget_type, IsRelm);
         let messages = self.messages(widget, relm_widget);
+        // The component is added by add_widget(), so move it to its position afterwards.
+        let move_to_position =
+            match (parent, gen_positional_add(widget), &widget.pack_type) {
+                (Some(parent), Some(PositionalChild { method, args, .. }), _) => {
+                    let container = gen_container(parent, parent_widget_type);
+                    quote_spanned! { widget_name.span() =>
+                        ::gtk::ContainerExt::remove(&#container, #widget_name.widget());
+                        #method(&#container, #widget_name.widget(), #(#args),*);
+                    }
+                },
+                (Some(parent), None, Some(pack_type)) => {
+                    let container = gen_container(parent, parent_widget_type);
+                    quote_spanned! { widget_name.span() =>
+                        ::gtk::ContainerExt::remove(&#container, #widget_name.widget());
+                        ::relm::pack(&#container, #widget_name.widget(), ::gtk::PackType::#pack_type);
+                    }
+                },
+                _ => quote! {},
+            };
 
         quote! {
             #add_or_create_widget
+            #move_to_position
             #messages
             #(#children)*
             #(#child_properties)*
+            #tab_label
         }
     }
 
@@ -478,9 +841,25 @@ impl<'a> Generator<'a> {
         for (variant, value) in &relm_widget.messages {
             let mut remover = Transformer::new(MODEL_IDENT);
             let value = remover.fold_expr(value.clone());
+            let emit =
+                if self.track_sent_values {
+                    // Record the value so that update() doesn't send it again if it didn't change.
+                    let key = sent_value_key(name, variant);
+                    let value_ident = value_ident(&value);
+                    quote! {{
+                        let #value_ident = #value;
+                        let _ = __relm_sent_values.changed(#key, &#value_ident);
+                        #name.stream().emit(#variant(#value_ident));
+                    }}
+                }
+                else {
+                    quote! {
+                        #name.stream().emit(#variant(#value));
+                    }
+                };
             tokens = quote! {
                 #tokens
-                #name.stream().emit(#variant(#value));
+                #emit
             };
         }
         tokens
@@ -508,6 +887,47 @@ impl<'a> Generator<'a> {
         }
     }
 
+    /// Create a transformer replacing the references to the widgets of the view (`self.widgets.name`).
+    /*
+     * Create the widget declared with tab: in a page of a gtk::Notebook and set it as the tab
+     * label of this page.
+     */
+    fn tab_label(&mut self, widget: &Widget, parent: Option<&Ident>, parent_widget_type: WidgetType,
+        widget_type: WidgetType) -> TokenStream
+    {
+        let (tab, parent) =
+            match (widget.nested_views.get(&dummy_ident("tab")), parent) {
+                (Some(tab), Some(parent)) => (tab, parent),
+                _ => return quote! {},
+            };
+        self.in_tab_label = true;
+        let tab_widget = self.widget(tab, None, IsGtk, true);
+        self.in_tab_label = false;
+        let tab_name = &tab.name;
+        let tab_label =
+            match tab.widget {
+                Gtk(_) => quote! { &#tab_name },
+                Relm(_) => quote! { #tab_name.widget() },
+            };
+        let widget_name = &widget.name;
+        let child =
+            if widget_type == IsGtk {
+                quote! { &#widget_name }
+            }
+            else {
+                quote! { #widget_name.widget() }
+            };
+        let container = gen_container(parent, parent_widget_type);
+        quote_spanned! { tab_name.span() =>
+            #tab_widget
+            ::gtk::NotebookExt::set_tab_label(&#container, #child, Some(#tab_label));
+        }
+    }
+
+    fn widget_references(&self) -> WidgetReferences {
+        WidgetReferences::new(self.view_widgets.keys().cloned().collect())
+    }
+
     fn widget(&mut self, widget: &Widget, parent: Option<&Ident>, parent_widget_type: WidgetType, show: bool) -> TokenStream {
         match widget.widget {
             Gtk(ref gtk_widget) => self.gtk_widget(widget, gtk_widget, parent, parent_widget_type, show),
@@ -516,6 +936,66 @@ impl<'a> Generator<'a> {
     }
 }
 
+/*
+ * Collect the widgets of the view: they are local variables of the generated view() method, so
+ * they can be referenced by the event handlers.
+ */
+fn collect_view_widgets(widget: &Widget, view_widgets: &mut HashMap<Ident, WidgetType>) {
+    let widget_type =
+        match widget.widget {
+            Gtk(_) => IsGtk,
+            Relm(_) => IsRelm,
+        };
+    view_widgets.insert(widget.name.clone(), widget_type);
+    for nested_view in widget.nested_views.values() {
+        collect_view_widgets(nested_view, view_widgets);
+    }
+    for child in &widget.children {
+        collect_view_widgets(child, view_widgets);
+    }
+}
+
+/*
+ * Generate the calls to update the properties of the widgets of a row that is kept when the list
+ * is synchronized.
+ */
+fn gen_row_updates(widget: &Widget) -> TokenStream {
+    let name = &widget.name;
+    let ident =
+        match widget.widget {
+            Gtk(_) => quote! { #name },
+            Relm(_) => quote! { #name.widget() },
+        };
+    let mut tokens = quote! {};
+    for (key, value) in &widget.properties {
+        let mut remover = Transformer::new(MODEL_IDENT);
+        let value = remover.fold_expr(value.clone());
+        let property_func = Ident::new(&format!("set_{}", key), key.span());
+        tokens = quote! {
+            #tokens
+            #ident.#property_func(#value);
+        };
+    }
+    if let Relm(ref relm_widget) = widget.widget {
+        for (variant, value) in &relm_widget.messages {
+            let mut remover = Transformer::new(MODEL_IDENT);
+            let value = remover.fold_expr(value.clone());
+            tokens = quote! {
+                #tokens
+                #name.stream().emit(#variant(#value));
+            };
+        }
+    }
+    for child in &widget.children {
+        let child_tokens = gen_row_updates(child);
+        tokens = quote! {
+            #tokens
+            #child_tokens
+        };
+    }
+    tokens
+}
+
 fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream {
     let struct_name = &widget.typ;
 
@@ -550,6 +1030,14 @@ fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream
         });
     }*/
 
+    let base_type =
+        if is_widget(widget) {
+            quote! { ::gtk::Widget }
+        }
+        else {
+            quote! { ::relm::Object }
+        };
+
     if widget.init_parameters.is_empty() {
         quote_spanned! { struct_name.span() =>
             unsafe {
@@ -567,7 +1055,7 @@ fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream
                 let mut parameters = [#(#parameters),*];
                 // TODO: use the safe Object::new().
                 // TODO: switch to builders.
-                ::gtk::Widget::from_glib_none(::relm::g_object_newv(
+                #base_type::from_glib_none(::relm::g_object_newv(
                     ::relm::ToGlib::to_glib(&#struct_name::static_type()),
                     #properties_count, parameters.as_mut_ptr()) as *mut _)
                     .downcast().unwrap()
@@ -589,6 +1077,345 @@ fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream
     }
 }
 
+/*
+ * Like the visibility, the visible child of a gtk::Stack and the current page of a gtk::Notebook
+ * are set after the children are added.
+ * The adjustments are also set after the widget is added to its parent, since a scrollable widget
+ * gets the adjustments of its gtk::ScrolledWindow.
+ */
+fn is_set_after_children(property: &Ident) -> bool {
+    property == "visible" || property == "visible_child_name" || property == "current_page" ||
+        property == "property_page" || property == "css" || adjustment_orientation(property).is_some()
+}
+
+/*
+ * Get the orientation of the adjustment set by the pseudo-property hadjustment_value or
+ * vadjustment_value, or of the adjustment hadjustment or vadjustment.
+ */
+fn adjustment_orientation(property: &Ident) -> Option<TokenStream> {
+    if property == "hadjustment" || property == "hadjustment_value" {
+        Some(quote! { ::gtk::Orientation::Horizontal })
+    }
+    else if property == "vadjustment" || property == "vadjustment_value" {
+        Some(quote! { ::gtk::Orientation::Vertical })
+    }
+    else {
+        None
+    }
+}
+
+/*
+ * Generate the call setting the property `name` of a gtk widget: the style classes, the CSS and
+ * the drag and drop handlers are pseudo-properties handled by relm instead of setters of the
+ * widget, the margin sets the four
+ * margins, the visibility of a popover is changed by relm::set_popover_visible() and the tuple
+ * given to a setter with many arguments is split into these arguments.
+ */
+pub fn gen_set_property(widget: TokenStream, is_popover: bool, name: &Ident, value: &Expr) -> TokenStream {
+    if let Expr::Tuple(ExprTuple { ref elems, .. }) = *value {
+        if is_multi_arg_setter(name) {
+            let property_func = Ident::new(&format!("set_{}", name), name.span());
+            return quote! {
+                #widget.#property_func(#elems);
+            };
+        }
+    }
+    if is_popover && name == "visible" {
+        quote_spanned! { name.span() =>
+            ::relm::set_popover_visible(&#widget, #value);
+        }
+    }
+    else if let Some(orientation) = adjustment_orientation(name) {
+        quote_spanned! { name.span() =>
+            ::relm::set_adjustment_value(&#widget, #orientation, #value);
+        }
+    }
+    else if name == "margin" {
+        quote_spanned! { name.span() =>
+            {
+                let margin = #value;
+                ::gtk::WidgetExt::set_margin_top(&#widget, margin);
+                ::gtk::WidgetExt::set_margin_bottom(&#widget, margin);
+                ::gtk::WidgetExt::set_margin_start(&#widget, margin);
+                ::gtk::WidgetExt::set_margin_end(&#widget, margin);
+            }
+        }
+    }
+    else if name == "style_classes" {
+        let value = ArrayToVec.fold_expr(value.clone());
+        quote_spanned! { name.span() =>
+            ::relm::set_style_classes(&#widget, #value);
+        }
+    }
+    else if name == "css" {
+        quote_spanned! { name.span() =>
+            ::relm::set_css(&#widget, #value);
+        }
+    }
+    else if name == "drop" {
+        quote_spanned! { name.span() =>
+            ::relm::dnd::enable_drop(&#widget, relm.stream(), #value);
+        }
+    }
+    else if name == "drag_source" {
+        quote_spanned! { name.span() =>
+            ::relm::dnd::enable_drag_source(&#widget, #value);
+        }
+    }
+    else {
+        let property_func = Ident::new(&format!("set_{}", accessor_name(name)), name.span());
+        quote! {
+            #widget.#property_func(#value);
+        }
+    }
+}
+
+/*
+ * Only call the setter of the property when its value is different from the last value set,
+ * recorded in `sent_values`.
+ */
+pub fn gen_cached_set_property(widget: TokenStream, is_popover: bool, widget_name: &Ident, name: &Ident,
+    value: &Expr, sent_values: TokenStream) -> TokenStream
+{
+    let value_ident = value_ident(value);
+    let changed = gen_property_value_changed(widget_name, name, &value_ident, sent_values);
+    let set_property = gen_set_property(widget, is_popover, name, &parse_quote! { #value_ident });
+    // The match keeps the temporaries of the value alive while the setter is called.
+    quote_spanned! { name.span() =>
+        match #value {
+            #value_ident => {
+                if #changed {
+                    #set_property
+                }
+            },
+        }
+    }
+}
+
+/*
+ * Get the variable holding the value of a property or a message while it is compared to the last
+ * value sent, with the span of the value, so that the type errors point at the value instead of the
+ * widget attribute.
+ */
+pub fn value_ident(value: &Expr) -> Ident {
+    Ident::new("__relm_value", value.span())
+}
+
+/*
+ * Check if the value is different from the last value recorded for the property: the types that
+ * cannot be compared are always considered changed (see relm::PropertyValue).
+ */
+fn gen_property_value_changed(widget_name: &Ident, name: &Ident, value_ident: &Ident, sent_values: TokenStream)
+    -> TokenStream
+{
+    let key = property_value_key(widget_name, name);
+    quote_spanned! { value_ident.span() => {
+        #[allow(unused_imports)]
+        use ::relm::{
+            BorrowedPropertyValue as _,
+            OptionBorrowedPropertyValue as _,
+            OwnedPropertyValue as _,
+            UncomparablePropertyValue as _,
+        };
+        (&&&::relm::PropertyValue(&#value_ident)).changed(&#sent_values, #key)
+    }}
+}
+
+/*
+ * The value of a property is recorded when the view is created if update() only sets it when it
+ * changed, i.e. when it is cached and depends on the model.
+ */
+fn records_property_value(widget: &Widget, name: &Ident, value: &Expr) -> bool {
+    match widget.widget {
+        Gtk(ref gtk_widget) => {
+            let mut visitor = ModelVariableVisitor::new();
+            visitor.visit_expr(value);
+            is_cached_property(gtk_widget, name, value) && !visitor.idents.is_empty()
+        },
+        Relm(_) => false,
+    }
+}
+
+/*
+ * The properties set with a setter taking the value as is, which can be skipped when the value
+ * didn't change.
+ */
+pub fn is_plain_setter(name: &Ident, value: &Expr) -> bool {
+    let is_multi_arg = is_multi_arg_setter(name) && matches!(*value, Expr::Tuple(_));
+    !is_multi_arg && name != "margin" && name != "style_classes" && name != "css" && name != "drop" &&
+        name != "drag_source"
+}
+
+fn is_multi_arg_setter(property: &Ident) -> bool {
+    property == "size_request" || property == "default_size" || property == "alignment" ||
+        property == "padding" || property == "fixed_size"
+}
+
+/*
+ * The style classes can be arrays of different lengths in the branches of a condition, like
+ * `if self.model.error { ["error"] } else { [] }`, so the arrays are turned into vectors.
+ */
+struct ArrayToVec;
+
+impl Fold for ArrayToVec {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match fold_expr(self, expr) {
+            Expr::Array(ExprArray { elems, .. }) => parse_quote! {
+                ::std::vec![#elems]
+            },
+            expr => expr,
+        }
+    }
+}
+
+/*
+ * The columns of a gtk::TreeView and their cell renderers are not widgets, so they are neither
+ * shown nor added to their parent as a child.
+ */
+fn is_widget(widget: &Widget) -> bool {
+    type_name(&widget.typ) != "TreeViewColumn" && !is_cell_renderer(widget)
+}
+
+fn is_cell_renderer(widget: &Widget) -> bool {
+    type_name(&widget.typ).starts_with("CellRenderer")
+}
+
+/*
+ * A property of a cell renderer whose value is a column index between parentheses, like
+ * `text: (0)`, is an attribute mapped to this column of the model.
+ */
+fn cell_attribute(value: &Expr) -> Option<&Expr> {
+    match *value {
+        Expr::Paren(ref paren) =>
+            match *paren.expr {
+                Expr::Lit(ExprLit { lit: Lit::Int(_), .. }) => Some(&paren.expr),
+                _ => None,
+            },
+        _ => None,
+    }
+}
+
+/*
+ * The visibility of a popover is set with popup() and popdown(), so that it is animated.
+ */
+pub fn is_popover(widget: &Widget) -> bool {
+    match widget.widget {
+        Gtk(_) => {
+            let name = type_name(&widget.typ);
+            name == "Popover" || name == "PopoverMenu"
+        },
+        Relm(_) => false,
+    }
+}
+
+fn type_name(path: &Path) -> String {
+    path.segments.last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default()
+}
+
+fn gen_builder_object(widget: &Widget) -> TokenStream {
+    let struct_name = &widget.typ;
+    let id = widget.name.to_string();
+    quote_spanned! { widget.name.span() =>
+        ::gtk::prelude::BuilderExtManual::get_object::<#struct_name>(&__relm_builder, #id)
+            .unwrap_or_else(|| panic!("The object `{}` of the ui file is not a {}", #id, stringify!(#struct_name)))
+    }
+}
+
+/*
+ * Connect to the notify signal of the property given as the first parameter of notify(), like in
+ * `notify(position, paned) => PaneMoved(paned.get_position())`: the other parameter is the object.
+ */
+fn gen_notify_connect(widget_name: &TokenStream, event: &Event, references: &mut WidgetReferences) -> TokenStream {
+    let property =
+        match event.params.first() {
+            Some(Pat::Ident(PatIdent { ref ident, .. })) => ident.to_string(),
+            _ => return quote_spanned! { widget_name.span() =>
+                compile_error!("Expected the name of a property as the first parameter of notify()");
+            },
+        };
+    let object = event.params.get(1).map(|param| quote! { #param }).unwrap_or_else(|| quote! { _ });
+    let (stream, event_value) =
+        match event.value {
+            CurrentWidget(WithoutReturn(ref event_value)) => (quote! { relm.stream() }, event_value),
+            ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) =>
+                (quote! { #foreign_widget_name.stream() }, event_value),
+            _ => return quote_spanned! { widget_name.span() =>
+                compile_error!("A notify() handler cannot return a value");
+            },
+        };
+    let event_value = references.fold_expr(event_value.clone());
+    quote_spanned! { widget_name.span() =>
+        let stream = #stream.clone();
+        ::relm::connect_notify(&#widget_name, #property, move |#object| {
+            let msg: Option<_> = ::relm::IntoOption::into_option(#event_value);
+            if let Some(msg) = msg {
+                stream.emit(msg);
+            }
+        });
+    }
+}
+
+/*
+ * The return value of a signal handler using the model is computed when the signal is emitted, by
+ * borrowing the component: the default value is returned when it is already borrowed, i.e. when
+ * the signal is emitted during update().
+ */
+fn gen_model_return_value(return_value: &Expr) -> TokenStream {
+    let mut remover = Transformer::new(MODEL_IDENT);
+    let return_value = remover.fold_expr(return_value.clone());
+    let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
+    quote! {
+        __relm_handle.with_state(|__relm_state| {
+            let #model_ident = &__relm_state.model;
+            #return_value
+        }).unwrap_or_default()
+    }
+}
+
+fn uses_self(expr: &Expr) -> bool {
+    expr.to_token_stream().into_iter().any(|token| token_uses_self(&token))
+}
+
+fn token_uses_self(token: &TokenTree) -> bool {
+    match *token {
+        TokenTree::Ident(ref ident) => ident == "self",
+        TokenTree::Group(ref group) => group.stream().into_iter().any(|token| token_uses_self(&token)),
+        _ => false,
+    }
+}
+
+/*
+ * Only the model can be read in the handler, since the other fields of the component, like its
+ * widgets, are not available there.
+ */
+fn self_use_outside_model(tokens: TokenStream) -> Option<Span> {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match *token {
+            TokenTree::Ident(ref ident) if ident == "self" => {
+                let is_model_field =
+                    match (tokens.get(index + 1), tokens.get(index + 2)) {
+                        (Some(TokenTree::Punct(dot)), Some(TokenTree::Ident(field))) =>
+                            dot.as_char() == '.' && field == "model",
+                        _ => false,
+                    };
+                if !is_model_field {
+                    return Some(ident.span());
+                }
+            },
+            TokenTree::Group(ref group) => {
+                if let Some(span) = self_use_outside_model(group.stream()) {
+                    return Some(span);
+                }
+            },
+            _ => (),
+        }
+    }
+    None
+}
+
 fn gen_event_metadata(event: &Event) -> TokenStream {
     if let CurrentWidget(CallReturn(_)) = event.value {
         quote! {
@@ -848,35 +1675,113 @@ fn gen_relm_component_type(is_container: bool, name: &Path) -> Path {
     parse(tokens.into()).expect("gen_relm_component_type is a Path")
 }
 
+/*
+ * Child properties positioning a child with a method of its container instead of add(), like
+ * cell: { left: 0, top: 1, width: 2, height: 1 } for gtk::Grid::attach().
+ * The values of the parameters are passed to the method in this order, and the parameters
+ * without a default value are required: otherwise, they are set as normal child properties.
+ */
+struct PositionalAdd {
+    method: &'static str,
+    name: &'static str,
+    params: &'static [(&'static str, Option<&'static str>)],
+}
+
+const POSITIONAL_ADDS: &[PositionalAdd] = &[
+    PositionalAdd {
+        method: "::gtk::GridExt::attach",
+        name: "cell",
+        params: &[("left", None), ("top", None), ("width", Some("1")), ("height", Some("1"))],
+    },
+];
+
+struct PositionalChild {
+    args: Vec<TokenStream>,
+    method: TokenStream,
+    name: &'static str,
+}
+
+fn gen_positional_add(widget: &Widget) -> Option<PositionalChild> {
+    for positional_add in POSITIONAL_ADDS {
+        let keys: Vec<_> = widget.child_properties.keys()
+            .filter(|(ident, _)| ident == positional_add.name)
+            .map(|(_, key)| key)
+            .collect();
+        let all_params = keys.iter().all(|key| positional_add.params.iter().any(|&(param, _)| is_setter(key, param)));
+        if keys.is_empty() || !all_params {
+            continue;
+        }
+        let mut args = vec![];
+        for &(param, default) in positional_add.params {
+            let value = widget.child_properties.iter()
+                .find(|((ident, key), _)| ident == positional_add.name && is_setter(key, param))
+                .map(|(_, value)| quote! { #value })
+                .or_else(|| default.map(|default| default.parse().expect("default value")));
+            args.push(value?);
+        }
+        return Some(PositionalChild {
+            args,
+            method: positional_add.method.parse().expect("method path"),
+            name: positional_add.name,
+        });
+    }
+    None
+}
+
+fn is_setter(key: &ChildPropertyName, name: &str) -> bool {
+    match *key {
+        Setter(ref ident) => ident == name,
+        Name(_) => false,
+    }
+}
+
+fn gen_container(parent: &Ident, parent_widget_type: WidgetType) -> TokenStream {
+    if parent_widget_type == IsGtk {
+        quote! {
+            #parent
+        }
+    }
+    else {
+        quote! {
+            #parent.container
+        }
+    }
+}
+
 fn gen_set_child_prop_calls(widget: &Widget, parent: Option<&Ident>, parent_widget_type: WidgetType,
     widget_type: WidgetType) -> Vec<TokenStream>
 {
     let widget_name = &widget.name;
     let mut child_properties = vec![];
+    let positional_name = gen_positional_add(widget).map(|positional_add| positional_add.name);
     if let Some(parent) = parent {
         for (&(ref ident, ref key), value) in &widget.child_properties {
-            let property_func = Ident::new(&format!("set_{}_{}", ident, key), key.span());
-            let parent =
-                if parent_widget_type == IsGtk {
+            if positional_name.iter().any(|name| ident == name) {
+                continue;
+            }
+            let parent = gen_container(parent, parent_widget_type);
+            let child =
+                if widget_type == IsGtk {
                     quote! {
-                        #parent
+                        &#widget_name
                     }
                 }
                 else {
                     quote! {
-                        #parent.container
+                        #widget_name.widget()
                     }
                 };
             let call =
-                if widget_type == IsGtk {
-                    quote! {
-                        #parent.#property_func(&#widget_name, #value);
-                    }
-                }
-                else {
-                    quote! {
-                        #parent.#property_func(#widget_name.widget(), #value);
-                    }
+                match *key {
+                    Setter(ref key) => {
+                        let property_func = Ident::new(&format!("set_{}_{}", ident, key), key.span());
+                        quote! {
+                            #parent.#property_func(#child, #value);
+                        }
+                    },
+                    Name(ref name) => quote! {
+                        ::relm::set_child_property(&#parent, #child, #name, &#value);
+                    },
                 };
             child_properties.push(call);
         }
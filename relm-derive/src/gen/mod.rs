@@ -21,14 +21,14 @@
 
 /*
  * TODO: automatically add the model() method with a () return type when it is not found?
- * FIXME: Doing model.text.push_str() will not cause a set_text() to be added.
- * TODO: think about conditions and loops (widget-list).
  */
 
 pub(crate) mod parser;
 
 mod adder;
+mod builder;
 mod generator;
+mod signals;
 mod transformer;
 mod walker;
 
@@ -37,12 +37,18 @@ use std::collections::{HashMap, HashSet};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::{
+    AttributeArgs,
+    Expr,
     Generics,
     Ident,
     ImplItem,
     ImplItemMethod,
     ItemImpl,
+    Lit,
     Macro,
+    Meta,
+    MetaNameValue,
+    NestedMeta,
     Path,
     PatType,
     PathArguments,
@@ -60,14 +66,18 @@ use syn::spanned::Spanned;
 use syn::Type;
 use syn::visit::Visit;
 
-use self::adder::{Adder, Message, Property};
+use self::adder::{Adder, Message, Property, create_update_view_stmts};
+use self::builder::UiFile;
 pub use self::generator::gen_where_clause;
+use self::generator::{is_plain_setter, is_popover};
+use self::parser::ChildPropertyName::{self, Setter};
 use self::parser::EitherWidget::{Gtk, Relm};
-use self::parser::{Widget, WidgetList};
+use self::parser::{GtkWidget, Widget, WidgetList};
 use self::walker::ModelVariableVisitor;
 
 const MODEL_IDENT: &str = "__relm_model";
 
+type ListModelMap = HashMap<Ident, HashSet<Ident>>;
 type MsgModelMap = HashMap<Ident, HashSet<Message>>;
 type PropertyModelMap = HashMap<Ident, HashSet<Property>>;
 
@@ -75,6 +85,8 @@ type PropertyModelMap = HashMap<Ident, HashSet<Property>>;
 pub struct Driver {
     data_method: Option<ImplItem>,
     generic_types: Option<Generics>,
+    has_bindings: bool,
+    list_model_map: Option<ListModelMap>,
     model_type: Option<ImplItem>,
     model_param_type: Option<ImplItem>,
     msg_model_map: Option<MsgModelMap>,
@@ -87,6 +99,7 @@ pub struct Driver {
     root_widget_expr: Option<TokenStream>,
     root_widget_is_relm: bool,
     root_widget_type: Option<TokenStream>,
+    ui_file: Option<UiFile>,
     update_method: Option<ImplItem>,
     view_macro: Option<Macro>,
     widget_model_type: Option<Type>,
@@ -98,6 +111,9 @@ pub struct Driver {
 struct View {
     container_impl: TokenStream,
     item: ImplItem,
+    list_methods: Vec<TokenStream>,
+    list_model_map: ListModelMap,
+    lists: Vec<Ident>,
     msg_model_map: MsgModelMap,
     properties_model_map: PropertyModelMap,
     relm_components: HashMap<Ident, Path>,
@@ -111,6 +127,8 @@ impl Driver {
         Driver {
             data_method: None,
             generic_types: None,
+            has_bindings: false,
+            list_model_map: None,
             model_type: None,
             model_param_type: None,
             msg_model_map: None,
@@ -123,6 +141,7 @@ impl Driver {
             root_widget_expr: None,
             root_widget_is_relm: false,
             root_widget_type: None,
+            ui_file: None,
             update_method: None,
             view_macro: None,
             widget_model_type: None,
@@ -134,24 +153,36 @@ impl Driver {
 
     fn add_set_property_to_method(&self, func: &mut ImplItem) {
         if let Method(ImplItemMethod { ref mut block, .. }) = *func {
+            let list_map = self.list_model_map.as_ref().expect("update method");
             let msg_map = self.msg_model_map.as_ref().expect("update method");
             let property_map = self.properties_model_map.as_ref().expect("update method");
-            let mut adder = Adder::new(property_map, msg_map);
+            let mut adder = Adder::new(property_map, msg_map, list_map);
             *block = adder.fold_block(block.clone());
         }
     }
 
-    fn collect_bindings(&mut self, widget: &Widget, msg_model_map: &mut MsgModelMap, properties_model_map: &mut PropertyModelMap) {
+    fn collect_bindings(&mut self, widget: &Widget, msg_model_map: &mut MsgModelMap, properties_model_map: &mut PropertyModelMap,
+        list_model_map: &mut ListModelMap)
+    {
         get_properties_model_map(&widget, properties_model_map);
         get_msg_model_map(&widget, msg_model_map);
         self.add_widgets(&widget, &properties_model_map);
 
+        if !widget.loops.is_empty() {
+            get_list_model_map(widget, list_model_map);
+            // The container is needed by the methods synchronizing the lists.
+            let widget_type = &widget.typ;
+            self.widgets.insert(widget.name.clone(), quote! {
+                #widget_type
+            });
+        }
+
         for nested_view in widget.nested_views.values() {
-            self.collect_bindings(nested_view, msg_model_map, properties_model_map);
+            self.collect_bindings(nested_view, msg_model_map, properties_model_map, list_model_map);
         }
 
         for child in &widget.children {
-            self.collect_bindings(child, msg_model_map, properties_model_map);
+            self.collect_bindings(child, msg_model_map, properties_model_map, list_model_map);
         }
     }
 
@@ -174,7 +205,9 @@ impl Driver {
         }
     }
 
-    fn create_struct(&self, typ: &Type, relm_widgets: &HashMap<Ident, Path>, relm_components: &HashMap<Ident, Path>, streams_to_save: &HashSet<Ident>, generics: &Generics) -> TokenStream {
+    fn create_struct(&self, typ: &Type, relm_widgets: &HashMap<Ident, Path>, relm_components: &HashMap<Ident, Path>,
+        streams_to_save: &HashSet<Ident>, lists: &[Ident], generics: &Generics) -> TokenStream
+    {
         let where_clause = gen_where_clause(generics);
         let root_widget_name = self.root_widget.as_ref().expect("root widget name");
         let widgets = self.widgets.iter()
@@ -192,6 +225,8 @@ impl Driver {
             quote! {
                 pub struct #components_name {
                     #(pub #idents: #types,)*
+                    #(pub #lists: ::relm::ChildList,)*
+                    pub __relm_sent_values: ::relm::SentValues,
                 }
             }
         };
@@ -240,13 +275,28 @@ impl Driver {
                 }
             }
         };
+        // The relm field is needed to synchronize the lists and to lock the stream when updating the
+        // bound properties.
+        let relm_field =
+            if lists.is_empty() && !self.has_bindings {
+                quote! {}
+            }
+            else {
+                quote! {
+                    relm: ::relm::Relm<#typ>,
+                }
+            };
+        // The bounds of the generic parameters are kept on the struct since the relm field
+        // requires the struct to implement Update.
+        let name = get_name(typ);
         quote_spanned! { typ.span() =>
             #[allow(dead_code, missing_docs)]
-            pub struct #typ #where_clause {
+            pub struct #name #generics #where_clause {
                 streams: #streams_name,
                 components: #components_name,
                 widgets: #widgets_name,
                 model: #widget_model_type,
+                #relm_field
             }
 
             #components
@@ -265,6 +315,7 @@ impl Driver {
             self.generic_types = Some(generics.clone());
             let name = get_name(&self_ty);
             let mut new_items = vec![];
+            let mut init_view = None;
             let mut update_items = vec![];
             for item in items {
                 let mut i = item.clone();
@@ -280,8 +331,9 @@ impl Driver {
                                 add_model_param(&mut i, &mut self.model_param_type);
                                 update_items.push(i);
                             },
-                            "subscriptions" => update_items.push(i),
-                            "init_view" | "on_add" => new_items.push(i),
+                            "on_destroy" | "subscribe" | "subscriptions" => update_items.push(i),
+                            "init_view" => init_view = Some(i),
+                            "on_add" => new_items.push(i),
                             "update" => {
                                 self.widget_msg_type = Some(get_second_param_type(&sig));
                                 self.update_method = Some(i)
@@ -310,12 +362,22 @@ impl Driver {
             if let Some(on_add) = gen_set_child_prop_calls(&view.widget) {
                 new_items.push(on_add);
             }
+            self.list_model_map = Some(view.list_model_map);
+            for method in &view.list_methods {
+                self.other_methods.push(block_to_impl_item(method.clone()));
+            }
             self.msg_model_map = Some(view.msg_model_map);
             self.properties_model_map = Some(view.properties_model_map);
+            if let Some(mut init_view) = init_view {
+                // The model can be updated in init_view(), so the view needs to be updated as well.
+                self.add_set_property_to_method(&mut init_view);
+                new_items.push(init_view);
+            }
             new_items.push(view.item);
             self.widgets.insert(self.root_widget.clone().expect("root widget"),
             self.root_widget_type.clone().expect("root widget type"));
-            let widget_struct = self.create_struct(&self_ty, &view.relm_widgets, &view.relm_components, &view.streams_to_save, &generics);
+            let widget_struct = self.create_struct(&self_ty, &view.relm_widgets, &view.relm_components, &view.streams_to_save,
+                &view.lists, &generics);
             new_items.push(self.get_root_type());
             if let Some(data_method) = self.get_data_method() {
                 new_items.push(data_method);
@@ -390,9 +452,42 @@ impl Driver {
         for method in &mut other_methods {
             self.add_set_property_to_method(method);
         }
+        let update_view = self.get_update_view();
         quote! {
             impl #generics #typ #where_clause {
                 #(#other_methods)*
+                #update_view
+            }
+        }
+    }
+
+    /*
+     * Generate the update_view() method which updates all the widget properties depending on the
+     * model.
+     * This is useful when the model was updated in a way that is not tracked, like through a
+     * mutable reference.
+     */
+    fn get_update_view(&self) -> TokenStream {
+        let list_map = self.list_model_map.as_ref().expect("update method");
+        let msg_map = self.msg_model_map.as_ref().expect("update method");
+        let property_map = self.properties_model_map.as_ref().expect("update method");
+        let stmts = create_update_view_stmts(property_map, msg_map, list_map);
+        quote! {
+            /// Update all the widget properties depending on the model.
+            #[allow(dead_code)]
+            fn update_view(&mut self) {
+                #(#stmts)*
+            }
+
+            /// Replace the whole model, e.g. to restore a snapshot of it, and refresh all the
+            /// bindings of the view, including those whose value did not change.
+            /// The messages bound to the model are sent again to the child widgets, but their model
+            /// parameters are only used when they are created.
+            #[allow(dead_code)]
+            fn replace_model(&mut self, model: <Self as ::relm::Update>::Model) {
+                self.model = model;
+                self.components.__relm_sent_values.clear();
+                self.update_view();
             }
         }
     }
@@ -436,17 +531,22 @@ impl Driver {
             .parse_body()?;
 
         self.widget_parent_id = widgets[0].parent_id.clone();
+        if let Some(ref ui_file) = self.ui_file {
+            ui_file.check_widgets(&widgets)?;
+        }
 
+        let mut list_model_map = HashMap::new();
         let mut msg_model_map = HashMap::new();
         let mut properties_model_map = HashMap::new();
         if let Gtk(ref mut widget) = widgets[0].widget {
             widget.relm_name = Some(typ.clone());
         }
         for widget in &widgets {
-            self.collect_bindings(widget, &mut msg_model_map, &mut properties_model_map);
+            self.collect_bindings(widget, &mut msg_model_map, &mut properties_model_map, &mut list_model_map);
         }
 
-        let generator::Gen { view, relm_widgets, relm_components, streams_to_save, container_impl } = generator::gen(name, &widgets, self);
+        let generator::Gen { view, list_methods, lists, relm_widgets, relm_components, streams_to_save, container_impl } =
+            generator::gen(name, &widgets, self);
         let model_ident = Ident::new(MODEL_IDENT, Span::call_site()); // TODO: maybe need to set Span here.
         let code = quote_spanned! { name.span() =>
             #[allow(unused_variables,clippy::all)] // Necessary to avoid warnings in case the parameters are unused.
@@ -459,6 +559,9 @@ impl Driver {
         Ok(View {
             container_impl,
             item,
+            list_methods,
+            list_model_map,
+            lists,
             msg_model_map,
             properties_model_map,
             relm_components,
@@ -508,26 +611,58 @@ impl Driver {
     }
 }
 
-pub fn gen_widget(input: TokenStream) -> TokenStream {
+pub fn gen_widget(attributes: AttributeArgs, input: TokenStream) -> TokenStream {
     let mut driver = Driver::new();
+    for attribute in attributes {
+        let path =
+            match attribute {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue { ref path, lit: Lit::Str(ref value), .. }))
+                    if path.is_ident("ui") => value.clone(),
+                _ => return syn::Error::new(attribute.span(), "expected `ui = \"path/to/file.ui\"`").to_compile_error(),
+            };
+        match UiFile::load(&path) {
+            Ok(ui_file) => driver.ui_file = Some(ui_file),
+            Err(error) => return error.to_compile_error(),
+        }
+    }
     driver.gen_widget(input)
 }
 
+/*
+ * Add the missing parameters of the model() method and set the ModelParam type from its parameter.
+ * The relm parameter is optional and multiple model parameters are grouped in a tuple, so that
+ * `fn model(title: String, port: u32)` has the model parameter type `(String, u32)`.
+ */
 fn add_model_param(model_fn: &mut ImplItem, model_param_type: &mut Option<ImplItem>) {
     let span = model_fn.span();
     if let Method(ImplItemMethod { ref mut sig, .. }) = *model_fn {
-        let len = sig.inputs.len();
-        if len == 0 || len == 1 {
+        if !sig.inputs.first().map(is_relm_param).unwrap_or(false) {
             let type_tokens = quote_spanned! { span =>
                 &::relm::Relm<Self>
             };
             let ty: Type = parse(type_tokens.into()).expect("Relm type");
             let input: FnArg = parse(quote! { _: #ty }.into()).expect("wild arg");
             sig.inputs.insert(0, input);
-            if len == 0 {
+        }
+        match sig.inputs.len() {
+            1 => {
                 let input: FnArg = parse(quote! { _: () }.into()).expect("wild arg");
                 sig.inputs.push(input);
-            }
+            },
+            2 => (),
+            _ => {
+                let mut patterns = vec![];
+                let mut types = vec![];
+                for input in sig.inputs.iter().skip(1) {
+                    if let Typed(PatType { ref pat, ref ty, .. }) = *input {
+                        patterns.push(pat.clone());
+                        types.push(ty.clone());
+                    }
+                }
+                let relm_input = sig.inputs[0].clone();
+                let input: FnArg = parse(quote! { (#(#patterns),*): (#(#types),*) }.into()).expect("tuple arg");
+                sig.inputs = vec![relm_input, input].into_iter().collect();
+            },
         }
         if let Some(&Typed(PatType { ref ty, .. })) = sig.inputs.iter().nth(1) {
             *model_param_type = Some(block_to_impl_item(quote! {
@@ -537,6 +672,20 @@ fn add_model_param(model_fn: &mut ImplItem, model_param_type: &mut Option<ImplIt
     }
 }
 
+/*
+ * Check if the parameter has the type &Relm<Self>.
+ */
+fn is_relm_param(input: &FnArg) -> bool {
+    if let Typed(PatType { ref ty, .. }) = *input {
+        if let Type::Reference(ref reference) = **ty {
+            if let Type::Path(TypePath { ref path, .. }) = *reference.elem {
+                return path.segments.last().map(|segment| segment.ident == "Relm").unwrap_or(false);
+            }
+        }
+    }
+    false
+}
+
 fn block_to_impl_item(tokens: TokenStream) -> ImplItem {
     let implementation = quote! {
         impl Test {
@@ -550,6 +699,46 @@ fn block_to_impl_item(tokens: TokenStream) -> ImplItem {
     }
 }
 
+/*
+ * Get the key used to store the last value sent in the message `variant` to the relm widget
+ * `widget_name`.
+ */
+fn sent_value_key(widget_name: &Ident, variant: &Ident) -> String {
+    format!("{}::{}", widget_name, variant)
+}
+
+fn property_value_key(widget_name: &Ident, property: &Ident) -> String {
+    format!("{}.{}", widget_name, property)
+}
+
+/*
+ * The setter of a property is not called when its value didn't change, except for the properties
+ * with the #[uncached] attribute and those bound in both directions, since the user can change
+ * them.
+ */
+fn is_cached_property(gtk_widget: &GtkWidget, name: &Ident, value: &Expr) -> bool {
+    !gtk_widget.bindings.contains(name) && !gtk_widget.uncached.contains(name) && is_plain_setter(name, value) &&
+        !needs_coercion(value)
+}
+
+/*
+ * The value of a cached property is stored in a variable before calling the setter, so the
+ * references inside a call or a tuple, like in Some(&self.model.text), would not be coerced to the
+ * type expected by the setter anymore: those properties are not cached.
+ */
+fn needs_coercion(value: &Expr) -> bool {
+    match *value {
+        Expr::Call(ref call) => contains_reference(call.args.iter()),
+        Expr::Paren(ref paren) => needs_coercion(&paren.expr),
+        Expr::Tuple(ref tuple) => contains_reference(tuple.elems.iter()),
+        _ => false,
+    }
+}
+
+fn contains_reference<'a, EXPRS: Iterator<Item=&'a Expr>>(mut exprs: EXPRS) -> bool {
+    exprs.any(|expr| matches!(*expr, Expr::Reference(_)) || needs_coercion(expr))
+}
+
 fn get_name(typ: &Type) -> Ident {
     if let Type::Path(TypePath { ref path, .. }) = *typ {
         let mut parts = vec![];
@@ -567,7 +756,11 @@ fn get_msg_model_map(widget: &Widget, map: &mut MsgModelMap) {
     match widget.widget {
         Gtk(_) => (),
         Relm(ref relm_widget) => {
-            for (name, expr) in &relm_widget.messages {
+            // The model parameter was already given when creating the widget, so the message is
+            // only sent on updates.
+            let param_msg = relm_widget.param_msg.iter()
+                .zip(&widget.init_parameters);
+            for (name, expr) in relm_widget.messages.iter().chain(param_msg) {
                 let mut visitor = ModelVariableVisitor::new();
                 visitor.visit_expr(&expr);
                 let model_variables = visitor.idents;
@@ -595,7 +788,17 @@ fn get_properties_model_map(widget: &Widget, map: &mut PropertyModelMap) {
 }
 
 fn get_map(widget: &Widget, map: &mut PropertyModelMap, is_relm: bool) {
+    let gtk_widget =
+        match widget.widget {
+            Gtk(ref gtk_widget) => Some(gtk_widget),
+            Relm(_) => None,
+        };
     for (name, expr) in &widget.properties {
+        if is_connection_property(name) {
+            continue;
+        }
+        let is_bound = gtk_widget.map(|gtk_widget| gtk_widget.bindings.contains(name)).unwrap_or(false);
+        let is_cached = gtk_widget.map(|gtk_widget| is_cached_property(gtk_widget, name, expr)).unwrap_or(false);
         let mut visitor = ModelVariableVisitor::new();
         visitor.visit_expr(&expr);
         let model_variables = visitor.idents;
@@ -603,6 +806,9 @@ fn get_map(widget: &Widget, map: &mut PropertyModelMap, is_relm: bool) {
             let set = map.entry(var).or_insert_with(HashSet::new);
             set.insert(Property {
                 expr: expr.clone(),
+                is_bound,
+                is_cached,
+                is_popover: is_popover(widget),
                 is_relm_widget: is_relm,
                 name: name.clone(),
                 widget_name: widget.name.clone(),
@@ -611,6 +817,43 @@ fn get_map(widget: &Widget, map: &mut PropertyModelMap, is_relm: bool) {
     }
 }
 
+/*
+ * The drag and drop properties connect handlers, so they are only set when the view is created.
+ */
+fn is_connection_property(name: &Ident) -> bool {
+    name == "drop" || name == "drag_source"
+}
+
+/*
+ * The map maps model variable name to the methods synchronizing the lists using this variable.
+ */
+fn get_list_model_map(widget: &Widget, map: &mut ListModelMap) {
+    for child_loop in &widget.loops {
+        let mut visitor = ModelVariableVisitor::new();
+        visitor.visit_expr(&child_loop.expr);
+        visit_row_widget(&child_loop.widget, &mut visitor);
+        let method_name = child_loop.sync_method();
+        for var in visitor.idents {
+            let set: &mut HashSet<_> = map.entry(var).or_default();
+            set.insert(method_name.clone());
+        }
+    }
+}
+
+fn visit_row_widget(widget: &Widget, visitor: &mut ModelVariableVisitor) {
+    for expr in widget.properties.values() {
+        visitor.visit_expr(expr);
+    }
+    if let Relm(ref relm_widget) = widget.widget {
+        for expr in relm_widget.messages.values() {
+            visitor.visit_expr(expr);
+        }
+    }
+    for child in &widget.children {
+        visit_row_widget(child, visitor);
+    }
+}
+
 fn get_return_type(sig: Signature) -> Type {
     if let ReturnType::Type(_, ty) = sig.output {
         *ty
@@ -635,18 +878,42 @@ fn get_second_param_type(sig: &Signature) -> Type {
 fn gen_set_child_prop_calls(widget: &Widget) -> Option<ImplItem> {
     let mut tokens = quote! {};
     let widget_name = &widget.name;
+    let mut has_setters = false;
     for (&(ref ident, ref key), value) in &widget.child_properties {
-        let property_func = Ident::new(&format!("set_{}_{}", ident, key), key.span());
-        tokens = quote_spanned! { widget_name.span() =>
-            #tokens
-            parent.#property_func(&self.widgets.#widget_name, #value);
-        };
+        tokens =
+            match *key {
+                Setter(ref key) => {
+                    has_setters = true;
+                    let property_func = Ident::new(&format!("set_{}_{}", ident, key), key.span());
+                    quote_spanned! { widget_name.span() =>
+                        #tokens
+                        parent.#property_func(&self.widgets.#widget_name, #value);
+                    }
+                },
+                ChildPropertyName::Name(ref name) => quote_spanned! { widget_name.span() =>
+                    #tokens
+                    ::relm::set_child_property(&parent, &self.widgets.#widget_name, #name, &#value);
+                },
+            };
     }
+    // The child properties set by name can be set in any container.
+    let downcast =
+        if has_setters {
+            quote! {
+                let parent: gtk::Box = ::relm::Cast::downcast(::relm::Cast::upcast::<::gtk::Widget>(parent))
+                    .expect("the parent of a widget with child properties must be a gtk::Box");
+            }
+        }
+        else {
+            quote! {
+                let parent: gtk::Container = ::relm::Cast::downcast(::relm::Cast::upcast::<::gtk::Widget>(parent))
+                    .expect("the parent of a widget with child properties must be a gtk::Container");
+            }
+        };
     if !widget.child_properties.is_empty() {
         Some(block_to_impl_item(quote_spanned! { widget_name.span() =>
             fn on_add<W: ::relm::IsA<::gtk::Widget> + ::relm::IsA<::relm::Object>>(&self, parent: W) {
-                let parent: gtk::Box = ::relm::Cast::downcast(::relm::Cast::upcast::<::gtk::Widget>(parent))
-                    .expect("the parent of a widget with child properties must be a gtk::Box");
+                #downcast
                 #tokens
             }
         }))
@@ -33,6 +33,7 @@ use quote::{quote, ToTokens};
 use syn::{
     Expr,
     ExprMacro,
+    ExprPath,
     Ident,
     LitStr,
     Macro,
@@ -44,6 +45,7 @@ use syn::{
     parenthesized,
     parse,
     parse2,
+    parse_quote,
     token,
     Token,
 };
@@ -52,6 +54,7 @@ use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 
 use self::ChildItem::*;
+use self::ChildPropertyName::*;
 use self::EventValue::*;
 use self::EventValueReturn::*;
 use self::EitherWidget::*;
@@ -74,7 +77,15 @@ macro_rules! catch_return {
 }
 
 type ChildEvents = HashMap<(Ident, Ident), Event>;
-type ChildProperties = HashMap<(Ident, Ident), Expr>;
+type ChildProperties = HashMap<(Ident, ChildPropertyName), Expr>;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChildPropertyName {
+    // Child property set with the setter of the container, like set_child_expand().
+    Setter(Ident),
+    // Child property set by its name, like "tab-expand".
+    Name(String),
+}
 
 #[derive(PartialEq)]
 enum SaveWidget {
@@ -99,6 +110,8 @@ pub enum EventValue {
 #[derive(Debug)]
 pub struct Event {
     pub params: Vec<Pat>,
+    // Span of the parenthesized parameters, if any.
+    pub params_span: Option<Span>,
     pub shared_values: Vec<Ident>,
     pub use_self: bool,
     pub value: EventValue,
@@ -108,6 +121,7 @@ impl Event {
     fn new() -> Self {
         Event {
             params: vec![],
+            params_span: None,
             shared_values: vec![],
             use_self: false,
             value: NoEventValue,
@@ -123,8 +137,10 @@ pub struct Widget {
     pub container_type: Option<Option<String>>, // TODO: Why two Options?
     pub init_parameters: Vec<Expr>,
     pub is_container: bool,
+    pub loops: Vec<ChildLoop>,
     pub name: Ident,
     pub nested_views: HashMap<Ident, Widget>,
+    pub pack_type: Option<Ident>,
     pub parent_id: Option<String>,
     pub properties: HashMap<Ident, Expr>,
     pub save: bool,
@@ -147,8 +163,10 @@ impl Widget {
             container_type: None,
             init_parameters,
             is_container: false,
+            loops: vec![],
             name,
             nested_views,
+            pack_type: None,
             parent_id: None,
             properties,
             save: false,
@@ -175,8 +193,10 @@ impl Widget {
             container_type: None,
             init_parameters,
             is_container: false,
+            loops: vec![],
             name,
             nested_views,
+            pack_type: None,
             parent_id: None,
             properties,
             save: false,
@@ -187,6 +207,31 @@ impl Widget {
     }
 }
 
+/*
+ * Children generated from a collection:
+ * for item in &self.model.items {
+ *     gtk::Label { text: &item.name },
+ * }
+ *
+ * When the pattern is a pair, its first element is the key identifying the row.
+ */
+#[derive(Debug)]
+pub struct ChildLoop {
+    pub expr: Expr,
+    pub key: Option<Pat>,
+    pub name: Ident,
+    pub pat: Pat,
+    pub position: usize,
+    pub widget: Widget,
+}
+
+impl ChildLoop {
+    /// Name of the method synchronizing the list with the model.
+    pub fn sync_method(&self) -> Ident {
+        Ident::new(&format!("{}_sync", self.name), Span::call_site())
+    }
+}
+
 #[derive(Debug)]
 pub enum EitherWidget {
     Gtk(GtkWidget),
@@ -195,17 +240,27 @@ pub enum EitherWidget {
 
 #[derive(Debug)]
 pub struct GtkWidget {
+    // Messages sent with the new value of an adjustment changed by the user, declared with
+    // vadjustment: { on_user_change: Msg }.
+    pub adjustment_messages: HashMap<Ident, Expr>,
+    // Properties bound in both directions with bind self.model.attribute => Msg.
+    pub bindings: HashSet<Ident>,
     pub construct_properties: HashMap<Ident, Expr>,
     pub events: HashMap<Ident, Event>,
     pub relm_name: Option<Type>,
+    // Properties with the #[uncached] attribute.
+    pub uncached: HashSet<Ident>,
 }
 
 impl GtkWidget {
     fn new() -> Self {
         GtkWidget {
+            adjustment_messages: HashMap::new(),
+            bindings: HashSet::new(),
             construct_properties: HashMap::new(),
             events: HashMap::new(),
             relm_name: None,
+            uncached: HashSet::new(),
         }
     }
 }
@@ -215,6 +270,8 @@ pub struct RelmWidget {
     pub events: HashMap<Ident, Vec<Event>>,
     pub gtk_events: HashMap<Ident, Event>,
     pub messages: HashMap<Ident, Expr>,
+    // Message sent with the new model parameter when the model attributes it uses are updated.
+    pub param_msg: Option<Ident>,
 }
 
 impl RelmWidget {
@@ -223,6 +280,7 @@ impl RelmWidget {
             events: HashMap::new(),
             gtk_events: HashMap::new(),
             messages: HashMap::new(),
+            param_msg: None,
         }
     }
 }
@@ -329,26 +387,36 @@ impl Parse for InitPropertiesParser {
 }
 
 enum ChildItem {
+    AdjustmentBinding(Ident, Option<Value>, Option<Expr>),
+    Binding(Ident, Value, Expr),
     ChildEvent(Ident, Ident, Event),
     ItemChildProperties(ChildProperties),
     ItemEvent(Ident, Event),
     ChildWidget(Widget),
+    ConditionalWidgets(Vec<Widget>),
+    Loop(Box<ChildLoop>),
     NestedView(Ident, Widget),
     Property(Ident, Value),
     RelmMsg(Ident, Value),
+    UncachedProperty(Ident, Value),
     RelmMsgEvent(Ident, Event),
 }
 
 impl ChildItem {
     fn unwrap_widget(self) -> Widget {
         match self {
+            AdjustmentBinding(_, _, _) => panic!("Expected widget, found adjustment binding"),
+            Binding(_, _, _) => panic!("Expected widget, found binding"),
             ChildEvent(_, _, _) => panic!("Expected widget, found child event"),
             ItemEvent(_, _) => panic!("Expected widget, found event"),
             ItemChildProperties(_) => panic!("Expected widget, found child properties"),
+            ConditionalWidgets(_) => panic!("Expected widget, found conditional widgets"),
+            Loop(_) => panic!("Expected widget, found for loop"),
             NestedView(_, _) => panic!("Expected widget, found nested view"),
             Property(_, _) => panic!("Expected widget, found property"),
             RelmMsg(_, _) => panic!("Expected widget, found relm msg"),
             RelmMsgEvent(_, _) => panic!("Expected widget, found relm msg event"),
+            UncachedProperty(_, _) => panic!("Expected widget, found property"),
             ChildWidget(widget) => widget,
         }
     }
@@ -535,9 +603,27 @@ impl GtkWidgetParser {
         let mut child_events = HashMap::new();
         let mut child_properties = HashMap::new();
         let mut nested_views = HashMap::new();
+        let mut loops = vec![];
         for item in child_items.into_iter() {
             let item = item.item;
             match item {
+                AdjustmentBinding(ident, value, msg) => {
+                    let property = adjustment_value_property(&ident);
+                    if let Some(msg) = msg {
+                        // The value changed by the user must not be skipped by the cache.
+                        let _ = gtk_widget.bindings.insert(property.clone());
+                        let _ = gtk_widget.adjustment_messages.insert(ident, msg);
+                    }
+                    if let Some(value) = value {
+                        let _ = properties.insert(property, value.value);
+                    }
+                },
+                Binding(ident, value, msg) => {
+                    let (event_name, event) = binding_event(&ident, &msg);
+                    let _ = gtk_widget.events.insert(event_name, event);
+                    let _ = gtk_widget.bindings.insert(ident.clone());
+                    let _ = properties.insert(ident, value.value);
+                },
                 ChildEvent(event_name, child_name, event) => {
                     let _ = child_events.insert((child_name, event_name), event);
                 },
@@ -548,9 +634,18 @@ impl GtkWidgetParser {
                 },
                 ItemEvent(ident, event) => { let _ = gtk_widget.events.insert(ident, event); },
                 ChildWidget(widget) => children.push(widget),
+                ConditionalWidgets(widgets) => children.extend(widgets),
+                Loop(mut child_loop) => {
+                    child_loop.position = children.len();
+                    loops.push(*child_loop);
+                },
                 NestedView(ident, widget) => { let _ = nested_views.insert(ident, widget); },
                 Property(ident, value) => { let _ = properties.insert(ident, value.value); },
                 RelmMsg(_, _) | RelmMsgEvent(_, _) => panic!("Unexpected relm msg in gtk widget"),
+                UncachedProperty(ident, value) => {
+                    let _ = gtk_widget.uncached.insert(ident.clone());
+                    let _ = properties.insert(ident, value.value);
+                },
             }
         }
         match init_properties {
@@ -558,9 +653,13 @@ impl GtkWidgetParser {
             InitParameters(init_params) => init_parameters = init_params,
             NoInitParameter => (),
         }
+        let pack_type = take_pack_type(&mut properties)?;
+        let mut widget = Widget::new_gtk(gtk_widget, typ, init_parameters, children, properties, child_properties,
+            child_events, nested_views);
+        widget.loops = loops;
+        widget.pack_type = pack_type;
         Ok(GtkWidgetParser {
-            gtk_widget: ChildWidget(Widget::new_gtk(gtk_widget, typ, init_parameters, children, properties,
-                            child_properties, child_events, nested_views)),
+            gtk_widget: ChildWidget(widget),
         })
     }
 }
@@ -573,7 +672,10 @@ impl Parse for ChildRelmItem {
     fn parse(input: ParseStream) -> Result<Self> {
         let parser = input.fork();
         let child_item =
-            if RelmPropertyOrEvent::parse(&parser).is_ok() {
+            if input.peek(Token![if]) {
+                ConditionalWidgets(ConditionalWidgetsParser::parse(input)?.widgets)
+            }
+            else if RelmPropertyOrEvent::parse(&parser).is_ok() {
                 RelmPropertyOrEvent::parse(input)?.child_item
             }
             else {
@@ -619,10 +721,14 @@ impl RelmWidgetParser {
                 let mut nested_views = HashMap::new();
                 for item in child_items {
                     match item {
+                        AdjustmentBinding(_, _, _) => panic!("Unexpected adjustment binding in relm widget"),
+                        Binding(_, _, _) => panic!("Unexpected binding in relm widget"),
                         ChildEvent(event_name, child_name, event) => {
                             let _ = child_events.insert((child_name, event_name), event);
                         },
                         ChildWidget(widget) => children.push(widget),
+                        ConditionalWidgets(widgets) => children.extend(widgets),
+                        Loop(_) => panic!("Unexpected for loop in relm widget"),
                         ItemEvent(ident, event) => { let _ = relm_widget.gtk_events.insert(ident, event); },
                         ItemChildProperties(child_props) => {
                             for (key, value) in child_props {
@@ -636,10 +742,14 @@ impl RelmWidgetParser {
                             let events = relm_widget.events.entry(ident).or_insert_with(Vec::new);
                             events.push(event);
                         },
+                        UncachedProperty(_, _) => panic!("Unexpected uncached property in relm widget"),
                     }
                 }
-                ChildWidget(Widget::new_relm(relm_widget, typ, init_parameters, children, properties,
-                    child_properties, child_events, nested_views))
+                let pack_type = take_pack_type(&mut properties)?;
+                let mut widget = Widget::new_relm(relm_widget, typ, init_parameters, children, properties,
+                    child_properties, child_events, nested_views);
+                widget.pack_type = pack_type;
+                ChildWidget(widget)
             }
             else {
                 let init_parameters = init_parameters.unwrap_or_else(Vec::new);
@@ -756,15 +866,40 @@ impl ValueOrChildProperties {
     fn parse(input: ParseStream, ident: &Ident) -> Result<Self> {
         let lookahead = input.lookahead1();
         let child_item =
-            if lookahead.peek(token::Brace) {
+            if lookahead.peek(token::Brace) && (ident == "hadjustment" || ident == "vadjustment") {
+                let AdjustmentBindingParser { on_user_change, value } = input.parse()?;
+                AdjustmentBinding(ident.clone(), value, on_user_change)
+            }
+            else if lookahead.peek(token::Brace) {
                 let properties;
                 let _brace = braced!(properties in input);
                 let properties = ChildPropertiesParser::parse(&properties)?.properties;
                 let properties = properties.into_iter()
-                    .map(|(key, value)| ((ident.clone(), key), value))
+                    .map(|(key, value)| {
+                        // The stack_child properties, like name and title, are set by name instead
+                        // of with the setters of gtk::Stack, which take an Option.
+                        let key =
+                            match key {
+                                Setter(ref key) if ident == "stack_child" =>
+                                    Name(key.to_string().replace('_', "-")),
+                                key => key,
+                            };
+                        ((ident.clone(), key), value)
+                    })
                     .collect();
                 ItemChildProperties(properties)
             }
+            else if ident == "tab" {
+                // A widget used as the tab label of a gtk::Notebook page.
+                NestedView(ident.clone(), ChildWidgetParser::parse(DontSave, input)?.widget.unwrap_widget())
+            }
+            else if is_binding(&input) {
+                Tag::parse(input, "bind")?;
+                let value = Value::parse(input)?;
+                let _token: Token![=>] = input.parse()?;
+                let msg = Expr::parse(input)?;
+                Binding(ident.clone(), value, msg)
+            }
             else {
                 let value = Value::parse(input)?;
                 let mut nested_view = None;
@@ -787,6 +922,94 @@ impl ValueOrChildProperties {
     }
 }
 
+/*
+ * The value of the horizontal or vertical adjustment of a scrollable widget, like a
+ * gtk::ScrolledWindow, is bound to the model with:
+ * vadjustment: { value: bind self.model.scroll, on_user_change: Scrolled }
+ * where both items are optional and the bind keyword is only there to mirror the other bindings.
+ */
+struct AdjustmentBindingParser {
+    on_user_change: Option<Expr>,
+    value: Option<Value>,
+}
+
+impl Parse for AdjustmentBindingParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let _brace = braced!(content in input);
+        let mut on_user_change = None;
+        let mut value = None;
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            let _colon: Token![:] = content.parse()?;
+            if ident == "value" {
+                let _ = Tag::parse(&content, "bind");
+                value = Some(content.parse()?);
+            }
+            else if ident == "on_user_change" {
+                on_user_change = Some(content.parse()?);
+            }
+            else {
+                return Err(Error::new(ident.span(), "expected `value` or `on_user_change` in the adjustment binding"));
+            }
+            if content.is_empty() {
+                break;
+            }
+            let _comma: Token![,] = content.parse()?;
+        }
+        Ok(AdjustmentBindingParser {
+            on_user_change,
+            value,
+        })
+    }
+}
+
+/*
+ * Get the pseudo-property setting the value of the adjustment, like vadjustment_value.
+ */
+fn adjustment_value_property(adjustment: &Ident) -> Ident {
+    Ident::new(&format!("{}_value", adjustment), adjustment.span())
+}
+
+/*
+ * A binding is a property value starting with the bind keyword followed by a model attribute:
+ * active: bind self.model.enabled => EnabledChanged
+ * text: bind &self.model.text => TextChanged
+ */
+fn is_binding(input: &ParseStream) -> bool {
+    let input = input.fork();
+    Tag::parse(&input, "bind").is_ok() && (input.peek(Token![self]) || input.peek(Token![&]))
+}
+
+/*
+ * Create the event sending the message of a binding when the property is changed by the user:
+ * the message is created with the new value of the property.
+ */
+fn binding_event(property: &Ident, msg: &Expr) -> (Ident, Event) {
+    let event_name = Ident::new(&format!("property_{}_notify", property), property.span());
+    let getter = Ident::new(&format!("get_{}", accessor_name(property)), property.span());
+    let mut event = Event::new();
+    event.params.push(parse_quote! { __relm_widget });
+    event.value = CurrentWidget(WithoutReturn(parse_quote! {
+        #msg(::std::convert::Into::into(__relm_widget.#getter()))
+    }));
+    (event_name, event)
+}
+
+/*
+ * Get the name used by the getter and the setter of a property, which is the name of the property
+ * except for the few properties whose accessors are named differently in gtk-rs, like
+ * search_mode_enabled of gtk::SearchBar, set with set_search_mode().
+ */
+pub fn accessor_name(property: &Ident) -> String {
+    if property == "search_mode_enabled" {
+        "search_mode".to_string()
+    }
+    else {
+        property.to_string()
+    }
+}
+
 struct Tag;
 
 impl Tag {
@@ -951,8 +1174,9 @@ impl Parse for Event {
         let lookahead = input.lookahead1();
         let params =
             if lookahead.peek(token::Paren) {
-                let _parens = parenthesized!(content in input);
+                let parens = parenthesized!(content in input);
                 Punctuated::<Pat, Token![,]>::parse_separated_nonempty(&content).ok()
+                    .map(|params| (params, parens.span))
             }
             else {
                 None
@@ -962,8 +1186,9 @@ impl Parse for Event {
         let message_sent = MessageSent::parse(input)?.ident_or_event_value;
 
         let mut event = Event::new();
-        if let Some(params) = params {
+        if let Some((params, span)) = params {
             event.params = params.into_iter().collect();
+            event.params_span = Some(span);
         }
         if let Some(shared_values) = shared_values {
             event.shared_values = shared_values;
@@ -1039,13 +1264,70 @@ fn is_property_or_event(input: &ParseStream) -> bool {
     false
 }
 
+/*
+ * A property with the uncached attribute has its setter called every time the model attributes it
+ * uses are updated, even when its value didn't change:
+ * #[uncached]
+ * text: &self.model.text,
+ */
+fn is_uncached_property(input: &ParseStream) -> bool {
+    let input = input.fork();
+    UncachedPropertyParser::parse_attribute(&input).is_ok() && input.peek(Ident) && input.peek2(Token![:])
+}
+
+struct UncachedPropertyParser {
+    child_item: ChildItem,
+}
+
+impl UncachedPropertyParser {
+    fn parse_attribute(input: ParseStream) -> Result<()> {
+        let _hash: Token![#] = input.parse()?;
+        let content;
+        let _bracket = bracketed!(content in input);
+        Tag::parse(&content, "uncached")?;
+        if content.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(content.error("Expected ]"))
+        }
+    }
+}
+
+impl Parse for UncachedPropertyParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        UncachedPropertyParser::parse_attribute(input)?;
+        let ident: Ident = input.parse()?;
+        let _colon: Token![:] = input.parse()?;
+        let value = Value::parse(input)?;
+        Ok(UncachedPropertyParser {
+            child_item: UncachedProperty(ident, value),
+        })
+    }
+}
+
 struct ChildGtkItem {
     item: ChildItem,
 }
 
 impl Parse for ChildGtkItem {
     fn parse(input: ParseStream) -> Result<Self> {
-        if is_property_or_event(&input) {
+        if input.peek(Token![if]) {
+            Ok(ChildGtkItem {
+                item: ConditionalWidgets(ConditionalWidgetsParser::parse(input)?.widgets),
+            })
+        }
+        else if input.peek(Token![for]) {
+            Ok(ChildGtkItem {
+                item: Loop(Box::new(parse_child_loop(input)?)),
+            })
+        }
+        else if is_uncached_property(&input) {
+            Ok(ChildGtkItem {
+                item: UncachedPropertyParser::parse(input)?.child_item,
+            })
+        }
+        else if is_property_or_event(&input) {
             let item: GtkChildPropertyOrEvent = input.parse()?;
             Ok(ChildGtkItem {
                 item: item.child_item,
@@ -1059,14 +1341,153 @@ impl Parse for ChildGtkItem {
     }
 }
 
+/*
+ * Parse a condition on children:
+ * if condition {
+ *     gtk::Label { },
+ * }
+ * else if condition {
+ *     gtk::Button { },
+ * }
+ * else {
+ *     gtk::Entry { },
+ * }
+ *
+ * Every widget gets its visible property bound to the condition of its branch, so that the
+ * widgets are shown or hidden when the model attributes used in the condition are updated.
+ */
+struct ConditionalWidgetsParser {
+    widgets: Vec<Widget>,
+}
+
+impl ConditionalWidgetsParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _if: Token![if] = input.parse()?;
+        let condition = Expr::parse_without_eager_brace(input)?;
+        let mut widgets = parse_conditional_branch(input)?;
+        for widget in &mut widgets {
+            add_visibility_condition(widget, &condition);
+        }
+        if input.peek(Token![else]) {
+            let _else: Token![else] = input.parse()?;
+            let mut else_widgets =
+                if input.peek(Token![if]) {
+                    ConditionalWidgetsParser::parse(input)?.widgets
+                }
+                else {
+                    parse_conditional_branch(input)?
+                };
+            let negation: Expr = parse_quote! { !(#condition) };
+            for widget in &mut else_widgets {
+                add_visibility_condition(widget, &negation);
+            }
+            widgets.extend(else_widgets);
+        }
+        Ok(ConditionalWidgetsParser {
+            widgets,
+        })
+    }
+}
+
+fn parse_conditional_branch(input: ParseStream) -> Result<Vec<Widget>> {
+    let content;
+    let _brace = braced!(content in input);
+    let mut widgets = vec![];
+    while !content.is_empty() {
+        if content.peek(Token![if]) {
+            widgets.extend(ConditionalWidgetsParser::parse(&content)?.widgets);
+        }
+        else {
+            widgets.push(ChildWidgetParser::parse(DontSave, &content)?.widget.unwrap_widget());
+        }
+        if content.is_empty() {
+            break;
+        }
+        let _comma: Token![,] = content.parse()?;
+    }
+    Ok(widgets)
+}
+
+fn parse_child_loop(input: ParseStream) -> Result<ChildLoop> {
+    let for_token: Token![for] = input.parse()?;
+    let pat: Pat = input.parse()?;
+    let _in: Token![in] = input.parse()?;
+    let expr = Expr::parse_without_eager_brace(input)?;
+    let content;
+    let _brace = braced!(content in input);
+    let widget = ChildWidgetParser::parse(DontSave, &content)?.widget.unwrap_widget();
+    if !content.is_empty() {
+        let _comma: Token![,] = content.parse()?;
+    }
+    if !content.is_empty() {
+        return Err(content.error("the body of a for loop must contain a single widget"));
+    }
+    if has_loops(&widget) {
+        return Err(Error::new(for_token.span(), "nested for loops are not supported"));
+    }
+    let (key, pat) =
+        match pat {
+            Pat::Tuple(ref tuple) if tuple.elems.len() == 2 => (Some(tuple.elems[0].clone()), tuple.elems[1].clone()),
+            pat => (None, pat),
+        };
+    Ok(ChildLoop {
+        expr,
+        key,
+        name: gen_list_name(),
+        pat,
+        position: 0,
+        widget,
+    })
+}
+
+fn has_loops(widget: &Widget) -> bool {
+    !widget.loops.is_empty() || widget.children.iter().any(has_loops)
+}
+
+fn add_visibility_condition(widget: &mut Widget, condition: &Expr) {
+    let visible = dummy_ident("visible");
+    let value =
+        match widget.properties.remove(&visible) {
+            Some(value) => parse_quote! { (#condition) && (#value) },
+            None => condition.clone(),
+        };
+    let _ = widget.properties.insert(visible, value);
+}
+
+/*
+ * Take the packing: start | end property, which adds the widget with pack_start() or pack_end().
+ */
+fn take_pack_type(properties: &mut HashMap<Ident, Expr>) -> Result<Option<Ident>> {
+    let value =
+        match properties.remove(&dummy_ident("packing")) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+    if let Expr::Path(ExprPath { ref path, .. }) = value {
+        if path.is_ident(&dummy_ident("start")) {
+            return Ok(Some(Ident::new("Start", path.span())));
+        }
+        else if path.is_ident(&dummy_ident("end")) {
+            return Ok(Some(Ident::new("End", path.span())));
+        }
+    }
+    Err(Error::new(value.span(), "expected start or end for packing"))
+}
+
 struct ChildProp {
-    name: Ident,
+    name: ChildPropertyName,
     value: Value,
 }
 
 impl Parse for ChildProp {
     fn parse(input: ParseStream) -> Result<Self> {
-        let name: Ident = input.parse()?;
+        let name =
+            if input.peek(LitStr) {
+                Name(input.parse::<LitStr>()?.value())
+            }
+            else {
+                Setter(input.parse()?)
+            };
         let _token: Token![:] = input.parse()?;
         let value = Value::parse(input)?;
         Ok(ChildProp {
@@ -1077,7 +1498,7 @@ impl Parse for ChildProp {
 }
 
 struct ChildPropertiesParser {
-    properties: HashMap<Ident, Expr>,
+    properties: HashMap<ChildPropertyName, Expr>,
 }
 
 impl Parse for ChildPropertiesParser {
@@ -1124,6 +1545,13 @@ fn gen_widget_name(path: &Path) -> Ident {
     Ident::new(&format!("{}{}", name, index), path.span())
 }
 
+fn gen_list_name() -> Ident {
+    let mut hashmap = NAMES_INDEX.lock().expect("lock() in gen_list_name()");
+    let index = hashmap.entry("__relm_list".to_string()).or_insert(0);
+    *index += 1;
+    Ident::new(&format!("__relm_list{}", index), Span::call_site())
+}
+
 fn path_to_string(path: &Path) -> String {
     let mut string = String::new();
     for segment in &path.segments {
@@ -1149,6 +1577,17 @@ fn adjust_widget_with_attributes(mut widget: ChildItem, attributes: &HashMap<Str
             for style_class in style_classes {
                 widget.style_classes.push((*style_class).clone());
             }
+            if let Some(param_msg) = attributes.get("param_msg") {
+                let param_msg = param_msg.as_ref().expect("Invalid param_msg specification");
+                if widget.init_parameters.len() != 1 {
+                    panic!("param_msg requires a widget with one model parameter");
+                }
+                match widget.widget {
+                    Relm(ref mut relm_widget) =>
+                        relm_widget.param_msg = Some(param_msg.parse().expect("param_msg should be a message variant")),
+                    Gtk(_) => panic!("param_msg is only supported on relm widgets"),
+                }
+            }
             widget.is_container = !widget.children.is_empty();
             widget.container_type = container_type;
             parent_id = attributes.get("parent").and_then(|opt_str| opt_str.as_ref().map(|lit| lit.value()));
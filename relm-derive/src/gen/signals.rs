@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::{Ident, Path};
+
+// Signals of common GTK+ widgets, to suggest the right name for a misspelled signal.
+const WIDGET: &[&str] = &["accel_closures_changed", "button_press_event", "button_release_event",
+    "can_activate_accel", "child_notify", "composited_changed", "configure_event", "damage_event", "delete_event",
+    "destroy", "destroy_event", "direction_changed", "drag_begin", "drag_data_delete", "drag_data_get",
+    "drag_data_received", "drag_drop", "drag_end", "drag_failed", "drag_leave", "drag_motion", "draw",
+    "enter_notify_event", "event", "event_after", "focus", "focus_in_event", "focus_out_event", "grab_broken_event",
+    "grab_focus", "grab_notify", "hide", "hierarchy_changed", "key_press_event", "key_release_event",
+    "keynav_failed", "leave_notify_event", "map", "map_event", "mnemonic_activate", "motion_notify_event",
+    "move_focus", "notify", "parent_set", "popup_menu", "property_notify_event", "proximity_in_event",
+    "proximity_out_event", "query_tooltip", "realize", "screen_changed", "scroll_event", "selection_clear_event",
+    "selection_get", "selection_notify_event", "selection_received", "selection_request_event", "show",
+    "show_help", "size_allocate", "state_changed", "state_flags_changed", "style_set", "style_updated",
+    "touch_event", "unmap", "unmap_event", "unrealize", "visibility_notify_event", "window_state_event"];
+
+const CONTAINER: &[&str] = &["add", "check_resize", "remove", "set_focus_child"];
+
+const WINDOW: &[&str] = &["activate_default", "activate_focus", "enable_debugging", "keys_changed", "set_focus"];
+
+const BUTTON: &[&str] = &["activate", "clicked", "enter", "leave", "pressed", "released"];
+
+const TOGGLE_BUTTON: &[&str] = &["toggled"];
+
+const RADIO_BUTTON: &[&str] = &["group_changed"];
+
+const CELL_EDITABLE: &[&str] = &["editing_done", "remove_widget"];
+
+const ENTRY: &[&str] = &["activate", "backspace", "changed", "copy_clipboard", "cut_clipboard", "delete_from_cursor",
+    "delete_text", "icon_press", "icon_release", "insert_at_cursor", "insert_emoji", "insert_text", "move_cursor",
+    "paste_clipboard", "populate_popup", "preedit_changed", "toggle_overwrite"];
+
+const SEARCH_ENTRY: &[&str] = &["next_match", "previous_match", "search_changed", "stop_search"];
+
+const SPIN_BUTTON: &[&str] = &["change_value", "input", "output", "value_changed", "wrapped"];
+
+const LABEL: &[&str] = &["activate_current_link", "activate_link", "copy_clipboard", "move_cursor",
+    "populate_popup"];
+
+const SWITCH: &[&str] = &["activate", "state_set"];
+
+const POPOVER: &[&str] = &["closed"];
+
+const RANGE: &[&str] = &["adjust_bounds", "change_value", "move_slider", "value_changed"];
+
+const SCALE: &[&str] = &["format_value"];
+
+const COMBO_BOX: &[&str] = &["changed", "format_entry_text", "move_active", "popdown", "popup"];
+
+const NOTEBOOK: &[&str] = &["change_current_page", "create_window", "focus_tab", "move_focus_out", "page_added",
+    "page_removed", "page_reordered", "reorder_tab", "select_page", "switch_page"];
+
+const PANED: &[&str] = &["accept_position", "cancel_position", "cycle_child_focus", "cycle_handle_focus",
+    "move_handle", "toggle_handle_focus"];
+
+const SCROLLED_WINDOW: &[&str] = &["edge_overshot", "edge_reached", "move_focus_out", "scroll_child"];
+
+const TREE_VIEW: &[&str] = &["columns_changed", "cursor_changed", "expand_collapse_cursor_row", "move_cursor",
+    "row_activated", "row_collapsed", "row_expanded", "select_all", "select_cursor_parent", "select_cursor_row",
+    "start_interactive_search", "test_collapse_row", "test_expand_row", "toggle_cursor_row", "unselect_all"];
+
+// Get the signals of the widget and of its parent classes and interfaces, or None when the widget
+// is unknown.
+fn widget_signals(widget: &str) -> Option<Vec<&'static [&'static str]>> {
+    let signals: Vec<&[&str]> =
+        match widget {
+            "Box" | "EventBox" | "Frame" | "Grid" | "HeaderBar" => vec![CONTAINER],
+            "Button" => vec![CONTAINER, BUTTON],
+            "CheckButton" | "ToggleButton" => vec![CONTAINER, BUTTON, TOGGLE_BUTTON],
+            "ComboBox" | "ComboBoxText" => vec![CONTAINER, CELL_EDITABLE, COMBO_BOX],
+            "DrawingArea" | "Image" => vec![],
+            "Entry" => vec![CELL_EDITABLE, ENTRY],
+            "Label" => vec![LABEL],
+            "Notebook" => vec![CONTAINER, NOTEBOOK],
+            "Paned" => vec![CONTAINER, PANED],
+            "Popover" | "PopoverMenu" => vec![CONTAINER, POPOVER],
+            "RadioButton" => vec![CONTAINER, BUTTON, TOGGLE_BUTTON, RADIO_BUTTON],
+            "Scale" => vec![RANGE, SCALE],
+            "ScrolledWindow" => vec![CONTAINER, SCROLLED_WINDOW],
+            "SearchEntry" => vec![CELL_EDITABLE, ENTRY, SEARCH_ENTRY],
+            "SpinButton" => vec![CELL_EDITABLE, ENTRY, SPIN_BUTTON],
+            "Switch" => vec![SWITCH],
+            "TreeView" => vec![CONTAINER, TREE_VIEW],
+            "Window" => vec![CONTAINER, WINDOW],
+            _ => return None,
+        };
+    Some(signals.into_iter().chain(Some(WIDGET)).collect())
+}
+
+/*
+ * Generate a compile error when the signal is unknown for a common gtk widget and looks like one of
+ * its signals.
+ * Nothing is generated for the other widgets or names: if the signal doesn't exist, rustc reports
+ * the missing connect_ method.
+ */
+pub fn check_signal(widget_type: &Path, name: &Ident) -> Option<TokenStream> {
+    let segments = &widget_type.segments;
+    if segments.len() != 2 || segments[0].ident != "gtk" {
+        return None;
+    }
+    let signals = widget_signals(&segments[1].ident.to_string())?;
+    let name_string = name.to_string();
+    if name_string.starts_with("property_") {
+        return None;
+    }
+    let signals = signals.into_iter().flat_map(|signals| signals.iter());
+    let mut suggestion = None;
+    for &signal in signals {
+        if signal == name_string {
+            return None;
+        }
+        let distance = edit_distance(signal, &name_string);
+        if distance <= 2 && suggestion.map(|(best, _)| distance < best).unwrap_or(true) {
+            suggestion = Some((distance, signal));
+        }
+    }
+    suggestion.map(|(_, signal)| {
+        let message = format!("unknown signal `{}` for `gtk::{}`, did you mean `{}`?", name, segments[1].ident,
+            signal);
+        quote_spanned! { name.span() =>
+            compile_error!(#message);
+        }
+    })
+}
+
+fn edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+    for (i, char1) in first.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &char2) in second.iter().enumerate() {
+            let substitution = previous[j] + usize::from(char1 != char2);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[second.len()]
+}
@@ -21,6 +21,7 @@
 
 //! Transformer to transform the self.model by the actual model identifier.
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use quote::quote_spanned;
@@ -29,6 +30,7 @@ use syn::{
     Expr,
     ExprField,
     ExprMacro,
+    ExprMethodCall,
     ExprPath,
     Ident,
     Macro,
@@ -89,6 +91,20 @@ impl Fold for Transformer {
                     }
                 }
             },
+            Expr::MethodCall(ExprMethodCall { ref receiver, ref method, .. }) => {
+                if let Expr::Path(ExprPath { ref path, .. }) = **receiver {
+                    // The view is created before the widget, so its methods cannot be called there.
+                    if path.is_ident(&dummy_ident("self")) {
+                        let message = format!("`self.{}()` cannot be called in the view, since the widget does not \
+                            exist yet when it is created: call an associated function (`Self::{}(..)`) instead",
+                            method, method);
+                        let tokens = quote_spanned! { expr.span() =>
+                            compile_error!(#message)
+                        };
+                        return parse(tokens.into()).expect("self method call error");
+                    }
+                }
+            },
             Expr::Macro(ExprMacro { mac: Macro { ref path, ref tokens, .. }, .. }) => {
                 if path.is_ident(&dummy_ident("view")) {
                     self.nested_widgets.push(tokens.clone());
@@ -107,3 +123,42 @@ impl Fold for Transformer {
         fold_expr(self, expr)
     }
 }
+
+/// Transformer to replace the references to the widgets of the view (`self.widgets.name`) by the
+/// local variables holding them.
+pub struct WidgetReferences {
+    widget_names: HashSet<Ident>,
+    pub references: Vec<Ident>,
+}
+
+impl WidgetReferences {
+    pub fn new(widget_names: HashSet<Ident>) -> Self {
+        WidgetReferences {
+            widget_names,
+            references: vec![],
+        }
+    }
+}
+
+impl Fold for WidgetReferences {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Expr::Field(ExprField { ref base, member: Named(ref ident), .. }) = expr {
+            if let Expr::Field(ExprField { base: ref self_expr, member: Named(ref widgets), .. }) = **base {
+                if let Expr::Path(ExprPath { ref path, .. }) = **self_expr {
+                    if path.is_ident(&dummy_ident("self")) && widgets == "widgets" &&
+                        self.widget_names.contains(ident)
+                    {
+                        if !self.references.contains(ident) {
+                            self.references.push(ident.clone());
+                        }
+                        let tokens = quote_spanned! { expr.span() =>
+                            #ident
+                        };
+                        return parse(tokens.into()).expect("widget reference");
+                    }
+                }
+            }
+        }
+        fold_expr(self, expr)
+    }
+}
@@ -32,19 +32,26 @@ mod gen;
 use quote::{quote, quote_spanned};
 use proc_macro2::TokenStream;
 use syn::{
+    AttributeArgs,
     GenericParam,
     Generics,
     Ident,
     Item,
+    ItemEnum,
     LifetimeDef,
+    Meta,
+    MetaList,
+    NestedMeta,
     TypeParam,
+    Variant,
     parse,
+    parse_macro_input,
 };
 use syn::spanned::Spanned;
 
 use gen::{gen_widget, gen_where_clause, parser::dummy_ident};
 
-#[proc_macro_derive(Msg)]
+#[proc_macro_derive(Msg, attributes(msg))]
 pub fn msg(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: Item = parse(input).expect("msg > parse failed");
     let gen = impl_msg(&ast, Ident::new("relm", ast.span()));
@@ -52,22 +59,25 @@ pub fn msg(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn widget(_attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn widget(attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let attributes = parse_macro_input!(attributes as AttributeArgs);
     let ast: Item = parse(input).expect("widget.parse failed");
     let tokens = quote! {
         #ast
     };
-    let expanded = gen_widget(tokens);
+    let expanded = gen_widget(attributes, tokens);
     expanded.into()
 }
 
 fn impl_msg(ast: &Item, krate: Ident) -> TokenStream {
     let display = derive_display_variant(ast, &krate);
     let into_option = derive_into_option(ast, &krate);
+    let message_variant = derive_message_variant(ast, &krate);
 
     quote! {
         #display
         #into_option
+        #message_variant
     }
 }
 
@@ -80,14 +90,7 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
             #name #generics_without_bound
         };
 
-        let variant_patterns = enum_item.variants.iter().map(|variant| {
-            let doc_ident = dummy_ident("doc");
-            let attrs = variant.attrs.iter().filter(|attr| !attr.path.is_ident(&doc_ident));
-            let ident = &variant.ident;
-            quote! {
-                #(#attrs)* #name::#ident { .. }
-            }
-        });
+        let variant_patterns = gen_variant_patterns(enum_item);
         let variant_names = enum_item.variants.iter().map(|variant| {
             variant.ident.to_string()
         });
@@ -101,6 +104,71 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
                         #(#variant_patterns => #variant_names,)*
                     }
                 }
+
+                fn debug_message(&self) -> ::std::option::Option<::std::string::String> {
+                    #[allow(unused_imports)]
+                    use ::#krate::{DebuggableMessage as _, UndebuggableMessage as _};
+                    (&&::#krate::DebugMessage(self)).debug_message()
+                }
+            }
+        }
+    }
+    else {
+        panic!("Expected enum");
+    }
+}
+
+fn derive_message_variant(ast: &Item, krate: &Ident) -> TokenStream {
+    if let Item::Enum(ref enum_item) = *ast {
+        let generics = &enum_item.generics;
+        let name = &enum_item.ident;
+        let generics_without_bound = remove_generic_bounds(generics);
+        let typ = quote! {
+            #name #generics_without_bound
+        };
+        let variant_patterns = gen_variant_patterns(enum_item);
+        let variant_indexes = 0..enum_item.variants.len();
+        let variant_count = enum_item.variants.len();
+        let where_clause = gen_where_clause(generics);
+        let is_recorded =
+            if enum_item.variants.iter().any(is_skipped_by_recorder) {
+                let variant_patterns = gen_variant_patterns(enum_item);
+                let recorded = enum_item.variants.iter().map(|variant| !is_skipped_by_recorder(variant));
+                quote_spanned! { krate.span() =>
+                    #[allow(unused_qualifications)]
+                    fn is_recorded(&self) -> bool {
+                        match *self {
+                            #(#variant_patterns => #recorded,)*
+                        }
+                    }
+                }
+            }
+            else {
+                quote! {}
+            };
+
+        quote_spanned! { krate.span() =>
+            impl #generics ::#krate::MessageVariant for #typ #where_clause {
+                #[allow(unused_qualifications)]
+                fn variant_index(&self) -> usize {
+                    match *self {
+                        #(#variant_patterns => #variant_indexes,)*
+                    }
+                }
+
+                fn variant_count() -> usize {
+                    #variant_count
+                }
+
+                #is_recorded
+            }
+
+            impl #generics #typ #where_clause {
+                /// Get the name of the current variant of this message.
+                #[allow(dead_code)]
+                pub fn variant_name(&self) -> &'static str {
+                    ::#krate::DisplayVariant::display_variant(self)
+                }
             }
         }
     }
@@ -109,6 +177,47 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
     }
 }
 
+/*
+ * Generate the patterns matching each variant, keeping the attributes of the variants allowed on
+ * match arms, like #[cfg], but not the doc comments or the attributes of the derives, like
+ * #[serde(skip)].
+ */
+fn gen_variant_patterns(enum_item: &ItemEnum) -> Vec<TokenStream> {
+    let name = &enum_item.ident;
+    enum_item.variants.iter().map(|variant| {
+        let attrs = variant.attrs.iter()
+            .filter(|attr| ["allow", "cfg", "deny", "forbid", "warn"].iter().any(|ident| attr.path.is_ident(ident)));
+        let ident = &variant.ident;
+        quote! {
+            #(#attrs)* #name::#ident { .. }
+        }
+    }).collect()
+}
+
+/*
+ * Check if the variant has the #[msg(skip_record)] attribute, so that the recorder doesn't record
+ * it, for instance because it contains a widget, which cannot be serialized.
+ */
+fn is_skipped_by_recorder(variant: &Variant) -> bool {
+    let msg_ident = dummy_ident("msg");
+    let skip_record_ident = dummy_ident("skip_record");
+    let mut skipped = false;
+    for attr in variant.attrs.iter().filter(|attr| attr.path.is_ident(&msg_ident)) {
+        match attr.parse_meta() {
+            Ok(Meta::List(MetaList { ref nested, .. })) => {
+                for meta in nested {
+                    match *meta {
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident(&skip_record_ident) => skipped = true,
+                        _ => panic!("Unexpected argument in #[msg] attribute, expected skip_record"),
+                    }
+                }
+            },
+            _ => panic!("Expected #[msg(skip_record)]"),
+        }
+    }
+    skipped
+}
+
 fn derive_into_option(ast: &Item, krate: &Ident) -> TokenStream {
     if let Item::Enum(ref enum_item) = *ast {
         let generics = &enum_item.generics;
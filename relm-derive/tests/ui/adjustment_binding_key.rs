@@ -0,0 +1,31 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Scrolled(f64),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> f64 {
+        0.0
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            gtk::ScrolledWindow {
+                vadjustment: {
+                    value: bind self.model,
+                    on_change: Msg::Scrolled,
+                },
+            },
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+#![allow(unused_imports)]
+
+use gtk::{ButtonExt, Inhibit, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Go,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            gtk::Button {
+                clicke => Msg::Go,
+            },
+        }
+    }
+}
+
+fn main() {}
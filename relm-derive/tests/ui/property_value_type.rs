@@ -0,0 +1,35 @@
+#![allow(unused_imports)]
+
+use gtk::{Inhibit, LabelExt, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+pub struct Model {
+    count: i32,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            count: 0,
+        }
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            gtk::Label {
+                text: self.model.count,
+            },
+        }
+    }
+}
+
+fn main() {}
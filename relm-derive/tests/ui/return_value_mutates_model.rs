@@ -0,0 +1,33 @@
+#![allow(unused_imports)]
+
+use gtk::{Inhibit, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+pub struct Model {
+    presses: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Press,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            presses: 0,
+        }
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            key_press_event(_, _) => (Msg::Press, Inhibit({ self.model.presses += 1; false })),
+        }
+    }
+}
+
+fn main() {}
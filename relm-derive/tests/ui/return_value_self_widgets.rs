@@ -0,0 +1,30 @@
+#![allow(unused_imports)]
+
+use gtk::{Inhibit, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Press,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> bool {
+        false
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            #[name="entry"]
+            gtk::Entry {
+                key_press_event(_, _) => (Msg::Press, Inhibit(self.widgets.entry.has_focus())),
+            },
+        }
+    }
+}
+
+fn main() {}
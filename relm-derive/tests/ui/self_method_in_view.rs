@@ -0,0 +1,29 @@
+use gtk::LabelExt;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> u32 {
+        0
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    fn title(&self) -> String {
+        self.model.to_string()
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Label {
+                text: &self.title(),
+            },
+        }
+    }
+}
+
+fn main() {}
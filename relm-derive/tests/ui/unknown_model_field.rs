@@ -0,0 +1,35 @@
+#![allow(unused_imports)]
+
+use gtk::{Inhibit, LabelExt, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+pub struct Model {
+    text: String,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            gtk::Label {
+                text: &self.model.txt,
+            },
+        }
+    }
+}
+
+fn main() {}
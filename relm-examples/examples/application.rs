@@ -0,0 +1,69 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::process;
+
+use gio::ApplicationFlags;
+use gtk::{
+    ButtonExt,
+    GtkWindowExt,
+    Inhibit,
+    WidgetExt,
+};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            // Quits the application started by run_application().
+            Quit => relm::quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            title: "Application",
+            gtk::Button {
+                clicked => Quit,
+                label: "Quit",
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    let status = relm::run_application::<Win>("com.github.antoyo.relm.Application", ApplicationFlags::empty(), ())
+        .expect("run_application failed");
+    process::exit(status);
+}
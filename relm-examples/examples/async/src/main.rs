@@ -22,6 +22,7 @@
 use gio::{
     AppInfo,
     AppLaunchContext,
+    Cancellable,
     CancellableExt,
     File,
     FileExt,
@@ -41,7 +42,7 @@ use gtk::{
     WidgetExt,
 };
 use gtk::Orientation::Vertical;
-use relm::{connect_async_full, connect_async_func_full, Relm, Widget};
+use relm::{connect_async, connect_async_func_full, Relm, Widget};
 use relm_derive::{Msg, widget};
 
 use self::Msg::*;
@@ -135,7 +136,10 @@ impl Win {
                 //connect_async!(file, load_contents_async, self.model.relm, FileRead);
                 //let cancellable = connect_async_full!(file, load_contents_async, self.model.relm, FileRead);
                 //connect_async!(file, load_contents_async, self.model.relm, FileRead, ReadError);
-                let cancellable = connect_async_full!(file, load_contents_async, self.model.relm, FileRead, ReadError);
+                let cancellable = Cancellable::new();
+                connect_async(self.model.relm.stream(), |callback| {
+                    file.load_contents_async(Some(&cancellable), callback)
+                }, FileRead, ReadError);
                 cancellable.cancel();
             }
         }
@@ -144,5 +148,5 @@ impl Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
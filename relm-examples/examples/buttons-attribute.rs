@@ -97,7 +97,7 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
 
 #[cfg(test)]
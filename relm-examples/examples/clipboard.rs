@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    ButtonExt,
+    EntryExt,
+    Inhibit,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget, clipboard};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    relm: Relm<Win>,
+}
+
+#[derive(Clone, Msg)]
+pub enum Msg {
+    Copy,
+    Paste,
+    Pasted(Option<String>),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            relm: relm.clone(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Copy => clipboard::set_text(&self.widgets.entry.get_text()),
+            Paste => clipboard::request_text(self.model.relm.stream(), Pasted),
+            Pasted(text) => {
+                if let Some(text) = text {
+                    self.widgets.entry.set_text(&text);
+                }
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    placeholder_text: Some("Text to copy"),
+                },
+                gtk::Button {
+                    clicked => Copy,
+                    label: "Copy",
+                },
+                gtk::Button {
+                    clicked => Paste,
+                    label: "Paste",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::EntryExt;
+
+    use gtk_test::assert_text;
+    use relm::clipboard;
+    use relm_test::{enter_keys, relm_observer_new, relm_observer_wait};
+
+    use crate::Msg::{Copy, Paste, Pasted};
+    use crate::Win;
+
+    #[test]
+    fn copy_paste() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let pasted_observer = relm_observer_new!(component, Pasted(_));
+
+        enter_keys(entry, "copied");
+        component.emit(Copy);
+        entry.set_text("");
+
+        component.emit(Paste);
+        relm_observer_wait!(let Pasted(text) = pasted_observer);
+        assert_eq!(text, Some("copied".to_string()));
+        assert_text!(entry, "copied");
+
+        clipboard::set_text("set directly");
+        component.emit(Paste);
+        relm_observer_wait!(let Pasted(text) = pasted_observer);
+        assert_eq!(text, Some("set directly".to_string()));
+        assert_text!(entry, "set directly");
+    }
+}
@@ -23,19 +23,31 @@ use chrono::{DateTime, Local};
 use gtk::{
     Inhibit,
     LabelExt,
+    OrientableExt,
     WidgetExt,
 };
+use gtk::Orientation::Vertical;
 use relm::{Relm, Widget, interval};
 use relm_derive::{Msg, widget};
 
 use self::Msg::*;
 
 pub struct Model {
+    label_updates: u32,
+    ticks: u32,
     time: DateTime<Local>,
 }
 
+impl Model {
+    fn stats(&self) -> String {
+        format!("{} ticks, {} label updates, {} skipped", self.ticks, self.label_updates,
+            self.ticks.saturating_sub(self.label_updates))
+    }
+}
+
 #[derive(Msg)]
 pub enum Msg {
+    LabelChanged,
     Quit,
     Tick,
 }
@@ -44,26 +56,42 @@ pub enum Msg {
 impl Widget for Win {
     fn model() -> Model {
         Model {
+            label_updates: 0,
+            ticks: 0,
             time: Local::now(),
         }
     }
 
+    // Tick more often than the displayed time changes: the setter of the label is only called
+    // when its text is different, i.e. once every 10 ticks.
     fn subscriptions(&mut self, relm: &Relm<Self>) {
-        interval(relm.stream(), 1000, || Tick);
+        interval(relm.stream(), 100, || Tick);
     }
 
     fn update(&mut self, event: Msg) {
         match event {
-            Tick => self.model.time = Local::now(),
+            LabelChanged => self.model.label_updates += 1,
+            Tick => {
+                self.model.ticks += 1;
+                self.model.time = Local::now();
+            },
             Quit => gtk::main_quit(),
         }
     }
 
     view! {
         gtk::Window {
-            #[name="label"]
-            gtk::Label {
-                text: &self.model.time.format("%H:%M:%S").to_string(),
+            gtk::Box {
+                orientation: Vertical,
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.time.format("%H:%M:%S").to_string(),
+                    property_label_notify(_) => LabelChanged,
+                },
+                gtk::Label {
+                    widget_name: "stats",
+                    text: &self.model.stats(),
+                },
             },
             delete_event(_, _) => (Quit, Inhibit(false)),
         }
@@ -71,15 +99,15 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::{Local, NaiveTime};
-    use gtk::LabelExt;
+    use gtk::{LabelExt, WidgetExt};
 
-    use gtk_test::wait;
+    use gtk_test::{find_child_by_name, wait};
 
     use crate::Win;
 
@@ -105,4 +133,24 @@ mod tests {
         assert_ne!(time, time2);
         assert!(time_close(label.get_text(), time2.format("%H:%M:%S").to_string()));
     }
+
+    #[test]
+    fn skipped_setters() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        wait(2000);
+
+        let window = widgets.label.get_toplevel().expect("window");
+        let stats: gtk::Label = find_child_by_name(&window, "stats").expect("stats label");
+        let stats = stats.get_text();
+        let numbers: Vec<u32> = stats.split(|c: char| !c.is_ascii_digit())
+            .filter(|number| !number.is_empty())
+            .map(|number| number.parse().expect("number"))
+            .collect();
+        let (ticks, label_updates, skipped) = (numbers[0], numbers[1], numbers[2]);
+        assert!(ticks >= 10);
+        // The label is only set when the displayed time changes.
+        assert!(label_updates <= 3);
+        assert_eq!(skipped, ticks - label_updates);
+    }
 }
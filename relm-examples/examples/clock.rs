@@ -112,7 +112,7 @@ impl WidgetTest for Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
 
 #[cfg(test)]
@@ -168,5 +168,5 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(()).unwrap();
+    Win::run().unwrap();
 }
@@ -1,3 +1,6 @@
+use std::env;
+use std::error::Error;
+
 use gtk::{Inhibit};
 use gtk::Orientation::{Vertical};
 use gtk::prelude::*;
@@ -42,6 +45,7 @@ impl Widget for Header {
             gtk::Button {
                 clicked => Remove,
                 label: "Remove",
+                packing: end,
             },
         }
     }
@@ -87,6 +91,8 @@ impl Widget for Win {
     }
 }
 
-fn main() {
-    Win::run(()).expect("Window::run");
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    Win::run_with_args((), &args)?;
+    Ok(())
 }
@@ -375,5 +375,5 @@ fn join_chunks(buffer: Vec<u8>) -> Vec<u8> {
 
 fn main() {
     TermLogger::init(Warn, Config::default()).expect("TermLogger::init failed");
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
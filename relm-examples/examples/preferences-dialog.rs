@@ -0,0 +1,232 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    EditableSignals,
+    EntryExt,
+    GtkWindowExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    ToggleButtonExt,
+    WidgetExt,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::{Relm, Widget};
+use relm::dialog::{self, Dialog};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+use self::PreferencesMsg::*;
+
+#[derive(Clone)]
+pub struct Settings {
+    dark_theme: bool,
+    username: String,
+}
+
+fn describe(settings: &Settings) -> String {
+    let theme = if settings.dark_theme { "dark" } else { "light" };
+    format!("{} ({} theme)", settings.username, theme)
+}
+
+pub struct PreferencesModel {
+    relm: Relm<PreferencesDialog>,
+    settings: Settings,
+}
+
+#[derive(Msg)]
+pub enum PreferencesMsg {
+    Accept,
+    Cancel,
+    DarkTheme(bool),
+    Done(Option<Settings>),
+    Username(String),
+}
+
+#[widget]
+impl Widget for PreferencesDialog {
+    fn model(relm: &Relm<Self>, settings: Settings) -> PreferencesModel {
+        PreferencesModel {
+            relm: relm.clone(),
+            settings,
+        }
+    }
+
+    fn update(&mut self, event: PreferencesMsg) {
+        match event {
+            Accept => {
+                let settings = self.model.settings.clone();
+                self.model.relm.stream().emit(Done(Some(settings)));
+            },
+            Cancel => self.model.relm.stream().emit(Done(None)),
+            DarkTheme(dark_theme) => self.model.settings.dark_theme = dark_theme,
+            // The dialog is destroyed by relm after this message.
+            Done(_) => (),
+            Username(username) => self.model.settings.username = username,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            title: "Preferences",
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Entry {
+                    widget_name: "username_entry",
+                    text: &self.model.settings.username,
+                    changed(entry) => Username(entry.get_text().to_string()),
+                },
+                gtk::CheckButton {
+                    label: "Dark theme",
+                    active: self.model.settings.dark_theme,
+                    toggled(button) => DarkTheme(button.get_active()),
+                },
+                gtk::Box {
+                    orientation: Horizontal,
+                    gtk::Button {
+                        clicked => Cancel,
+                        label: "Cancel",
+                    },
+                    gtk::Button {
+                        clicked => Accept,
+                        label: "OK",
+                        widget_name: "ok_button",
+                    },
+                },
+            },
+            // Closing the window cancels the dialog: relm destroys it when the response is sent.
+            delete_event(_, _) => (Cancel, Inhibit(true)),
+        }
+    }
+}
+
+impl Dialog for PreferencesDialog {
+    type Response = Option<Settings>;
+
+    fn response(msg: &PreferencesMsg) -> Option<Option<Settings>> {
+        match *msg {
+            Done(ref settings) => Some(settings.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub struct Model {
+    relm: Relm<Win>,
+    settings: Settings,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    OpenPreferences,
+    Quit,
+    SettingsChanged(Option<Settings>),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            relm: relm.clone(),
+            settings: Settings {
+                dark_theme: false,
+                username: "guest".to_string(),
+            },
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            OpenPreferences =>
+                dialog::show::<PreferencesDialog, _, _, _>(&self.widgets.window, self.model.relm.stream(),
+                    self.model.settings.clone(), SettingsChanged)
+                    .expect("preferences dialog"),
+            Quit => gtk::main_quit(),
+            SettingsChanged(Some(settings)) => self.model.settings = settings,
+            SettingsChanged(None) => (),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="label"]
+                gtk::Label {
+                    text: &describe(&self.model.settings),
+                },
+                gtk::Button {
+                    clicked => OpenPreferences,
+                    label: "Preferences",
+                    widget_name: "preferences_button",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{Button, EntryExt, GtkWindowExt, LabelExt};
+    use gtk::prelude::Cast;
+
+    use gtk_test::{assert_text, find_child_by_name, run_loop};
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn preferences_response() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let label = &widgets.label;
+
+        assert_text!(label, "guest (light theme)");
+
+        let preferences_button: Button = find_child_by_name(&widgets.window, "preferences_button")
+            .expect("preferences button");
+        click(&preferences_button);
+        let dialog = gtk::Window::list_toplevels().into_iter()
+            .filter_map(|window| window.downcast::<gtk::Window>().ok())
+            .find(|window| window.get_transient_for().as_ref() == Some(&widgets.window))
+            .expect("preferences dialog");
+        assert!(dialog.get_modal());
+
+        let entry: gtk::Entry = find_child_by_name(&dialog, "username_entry").expect("username entry");
+        let ok_button: Button = find_child_by_name(&dialog, "ok_button").expect("ok button");
+        entry.set_text("relm");
+        click(&ok_button);
+        run_loop();
+
+        // The response is delivered from the main loop, after the dialog was destroyed.
+        assert_text!(label, "relm (light theme)");
+        assert!(gtk::Window::list_toplevels().into_iter()
+            .filter_map(|window| window.downcast::<gtk::Window>().ok())
+            .all(|window| window.get_transient_for().as_ref() != Some(&widgets.window)));
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    StackExt,
+    StackSwitcherExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+const PAGES: [&str; 3] = ["home", "settings", "about"];
+
+pub struct Model {
+    page: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    NextPage,
+    PageChanged(String),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.widgets.switcher.set_stack(Some(&self.widgets.stack));
+    }
+
+    fn model() -> Model {
+        Model {
+            page: PAGES[0].to_string(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            NextPage => {
+                let index = PAGES.iter().position(|&page| page == self.model.page).unwrap_or(0);
+                self.model.page = PAGES[(index + 1) % PAGES.len()].to_string();
+            },
+            // Sent when the page is switched with the StackSwitcher.
+            PageChanged(page) => self.model.page = page,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="switcher"]
+                gtk::StackSwitcher {
+                },
+                #[name="stack"]
+                gtk::Stack {
+                    visible_child_name: &self.model.page,
+                    property_visible_child_name_notify(stack) =>
+                        PageChanged(stack.get_visible_child_name().map(|name| name.to_string()).unwrap_or_default()),
+                    gtk::Label {
+                        stack_child: {
+                            name: "home",
+                            title: "Home",
+                        },
+                        text: "Welcome",
+                    },
+                    gtk::Label {
+                        stack_child: {
+                            name: "settings",
+                            title: "Settings",
+                        },
+                        text: "No settings yet",
+                    },
+                    gtk::Label {
+                        stack_child: {
+                            name: "about",
+                            title: "About",
+                        },
+                        text: "Stack example",
+                    },
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.page,
+                },
+                gtk::Button {
+                    clicked => NextPage,
+                    label: "Next page",
+                    widget_name: "next_button",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{Button, LabelExt, StackExt, WidgetExt};
+
+    use gtk_test::{assert_text, find_child_by_name};
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn stack_pages() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let stack = &widgets.stack;
+        let label = &widgets.label;
+        let window = stack.get_toplevel().expect("window");
+        let next_button: Button = find_child_by_name(&window, "next_button").expect("next button");
+        let settings_page = stack.get_child_by_name("settings").expect("settings page");
+
+        assert_eq!(stack.get_child_name(&settings_page).expect("name"), "settings");
+        assert_eq!(stack.get_child_title(&settings_page).expect("title"), "Settings");
+        assert_eq!(stack.get_visible_child_name().expect("visible child"), "home");
+        assert_text!(label, "home");
+
+        click(&next_button);
+        assert_eq!(stack.get_visible_child_name().expect("visible child"), "settings");
+        assert_text!(label, "settings");
+
+        // Like a click in the StackSwitcher.
+        stack.set_visible_child_name("about");
+        assert_text!(label, "about");
+
+        click(&next_button);
+        assert_eq!(stack.get_visible_child_name().expect("visible child"), "home");
+        assert_text!(label, "home");
+    }
+}
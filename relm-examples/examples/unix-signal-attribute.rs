@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) 2020-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    WidgetExt,
+};
+use libc::{SIGINT, SIGTERM};
+use relm::{Relm, SourceHandle, Widget, unix_signal};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    // The signals are handled as long as the handles live.
+    signal_handles: Vec<SourceHandle>,
+}
+
+#[derive(Clone, Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            signal_handles: vec![],
+        }
+    }
+
+    fn subscriptions(&mut self, relm: &Relm<Self>) {
+        // Quit cleanly on Ctrl-C or kill.
+        self.model.signal_handles = vec![
+            unix_signal(SIGINT, relm.stream(), Quit),
+            unix_signal(SIGTERM, relm.stream(), Quit),
+        ];
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => {
+                println!("Saving the state before quitting");
+                gtk::main_quit();
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Label {
+                text: "Press Ctrl-C in the terminal to quit",
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
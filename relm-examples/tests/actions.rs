@@ -0,0 +1,52 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gio::ActionGroupExt;
+use glib::{ToVariant, VariantTy};
+use relm::{ActionGroupBuilder, EventStream};
+
+#[test]
+fn actions() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &i32| received.borrow_mut().push(*msg));
+    }
+    let group = ActionGroupBuilder::new(&stream.stream())
+        .action("first", 1)
+        .action_with_parameter("add", VariantTy::new("i").expect("variant type"), |parameter| {
+            parameter.get::<i32>().map(|value| value + 1)
+        })
+        .build();
+    assert!(group.has_action("first"));
+
+    group.activate_action("first", None);
+    group.activate_action("add", Some(&41.to_variant()));
+    assert_eq!(*received.borrow(), vec![1, 42]);
+
+    drop(stream);
+    // Must not panic.
+    group.activate_action("first", None);
+}
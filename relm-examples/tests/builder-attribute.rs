@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    WidgetExt,
+};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Decrement,
+    Increment,
+    Quit,
+}
+
+#[widget(ui = "examples/window.glade")]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.model.counter -= 1,
+            Increment => self.model.counter += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    // The widgets come from the ui file: the names must match the ids of its objects.
+    view! {
+        #[name="window"]
+        gtk::Window {
+            #[name="inc_button"]
+            gtk::Button {
+                clicked => Increment,
+            },
+            #[name="label"]
+            gtk::Label {
+                text: &self.model.counter.to_string(),
+            },
+            #[name="dec_button"]
+            gtk::Button {
+                clicked => Decrement,
+                label: "Decrement",
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+// The id of the objects of this ui file is not their first attribute.
+#[widget(ui = "tests/id-first.ui")]
+impl Widget for IdFirstWin {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.model.counter -= 1,
+            Increment => self.model.counter += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            #[name="button"]
+            gtk::Button {
+                clicked => Increment,
+            },
+            #[name="label"]
+            gtk::Label {
+                text: &self.model.counter.to_string(),
+            },
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{ButtonExt, LabelExt, WidgetExt};
+
+    use gtk_test::assert_text;
+    use relm_test::click;
+
+    use crate::{IdFirstWin, Win};
+
+    #[test]
+    fn builder_widgets() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let inc_button = &widgets.inc_button;
+        let dec_button = &widgets.dec_button;
+        let label = &widgets.label;
+
+        assert!(widgets.window.get_visible());
+        assert_eq!(inc_button.get_label().expect("label"), "+");
+        assert_eq!(dec_button.get_label().expect("label"), "Decrement");
+        assert_text!(label, 0);
+
+        click(inc_button);
+        assert_text!(label, 1);
+        click(inc_button);
+        assert_text!(label, 2);
+        click(dec_button);
+        assert_text!(label, 1);
+    }
+
+    #[test]
+    fn builder_id_first() {
+        let (_component, _, widgets) = relm::init_test::<IdFirstWin>(()).expect("init_test failed");
+        let button = &widgets.button;
+        let label = &widgets.label;
+
+        assert_eq!(button.get_label().expect("label"), "+");
+        assert_text!(label, 0);
+
+        click(button);
+        assert_text!(label, 1);
+    }
+}
@@ -94,7 +94,7 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run");
+    Win::run().expect("Win::run");
 }
 
 #[cfg(test)]
@@ -0,0 +1,134 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use gio::CancellableExt;
+use glib::MainContext;
+use relm_derive::Msg;
+use relm::{CancellationToken, Channel, EventStream, Relm, Update, UpdateNew, execute, progress_channel};
+
+#[derive(Msg)]
+enum Msg {
+    Ping,
+}
+
+struct Worker;
+
+impl Update for Worker {
+    type Model = ();
+    type ModelParam = Rc<RefCell<Option<CancellationToken>>>;
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, token: Self::ModelParam) {
+        *token.borrow_mut() = Some(relm.cancellation());
+    }
+
+    fn update(&mut self, _event: Msg) {
+    }
+}
+
+impl UpdateNew for Worker {
+    fn new(_relm: &Relm<Self>, _model: ()) -> Self {
+        Worker
+    }
+}
+
+// The tests use their own context since the tests run in parallel.
+fn iterate(context: &MainContext) {
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    while context.iteration(false) {
+    }
+    context.release();
+}
+
+#[test]
+fn cancelled_on_destroy() {
+    let token = Rc::new(RefCell::new(None));
+    let stream = execute::<Worker>(token.clone());
+    let token = token.borrow_mut().take().expect("token");
+    let worker_token = token.clone();
+    assert!(!token.is_cancelled());
+    assert!(!token.cancellable().is_cancelled());
+
+    stream.emit(Msg::Ping);
+    drop(stream);
+    assert!(token.is_cancelled());
+    assert!(token.cancellable().is_cancelled());
+    assert!(thread::spawn(move || worker_token.is_cancelled()).join().expect("join"));
+}
+
+#[test]
+fn sender_cancellation() {
+    let context = MainContext::new();
+    let received = Rc::new(RefCell::new(vec![]));
+    let (_channel, sender) = {
+        let received = received.clone();
+        Channel::with_context(&context, move |msg| received.borrow_mut().push(msg))
+    };
+    let token = CancellationToken::new();
+    let sender = sender.with_cancellation(&token);
+    sender.send(1).expect("send");
+    token.cancel();
+    sender.send(2).expect("send is a no-op");
+    iterate(&context);
+    assert_eq!(*received.borrow(), vec![1]);
+}
+
+#[test]
+fn crossbeam_sender_cancellation() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let (_channel, sender) = {
+        let received = received.clone();
+        Channel::new_crossbeam(move |msg| received.borrow_mut().push(msg))
+    };
+    let token = CancellationToken::new();
+    let sender = sender.with_cancellation(&token);
+    sender.send(1).expect("send");
+    token.cancel();
+    sender.send(2).expect("send is a no-op");
+    iterate(&MainContext::default());
+    assert_eq!(*received.borrow(), vec![1]);
+}
+
+#[test]
+fn progress_cancellation() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &i32| received.borrow_mut().push(*msg));
+    }
+    let token = CancellationToken::new();
+    let sender = progress_channel(&stream.stream(), |progress: i32| progress, |result: i32| result)
+        .with_cancellation(&token);
+    sender.progress(1).expect("progress");
+    token.cancel();
+    sender.progress(2).expect("progress is a no-op");
+    sender.finish(3).expect("finish is a no-op");
+    iterate(&MainContext::default());
+    assert_eq!(*received.borrow(), vec![1]);
+}
@@ -0,0 +1,360 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, TrySendError};
+use std::thread;
+
+use futures::SinkExt;
+use futures::executor::block_on;
+use glib::{MainContext, Priority};
+use glib::translate::from_glib;
+use relm::{Channel, EventStream, Sender, oneshot_with_context, progress_channel};
+
+// Most tests use their own context since the tests run in parallel.
+// Return the number of iterations of the main loop.
+fn iterate(context: &MainContext) -> usize {
+    // Wait for the other tests to release the default context.
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    let mut iterations = 0;
+    while context.iteration(false) {
+        iterations += 1;
+    }
+    context.release();
+    iterations
+}
+
+#[test]
+fn bounded() {
+    let context = MainContext::new();
+    let received = Rc::new(RefCell::new(vec![]));
+    let (_channel, sender) = {
+        let received = received.clone();
+        Channel::bounded_with_context(&context, 2, move |msg| received.borrow_mut().push(msg))
+    };
+
+    sender.try_send(1).expect("send");
+    sender.try_send(2).expect("send");
+    match sender.try_send(3) {
+        Err(TrySendError::Full(3)) => (),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    iterate(&context);
+    assert_eq!(*received.borrow(), vec![1, 2]);
+    sender.send(3).expect("send");
+    iterate(&context);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+}
+
+#[test]
+fn disconnect() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let disconnected = Rc::new(Cell::new(false));
+    let (channel, sender) = {
+        let received = received.clone();
+        let disconnected = disconnected.clone();
+        Channel::with_disconnect_handler(move |msg| received.borrow_mut().push(msg),
+            move || disconnected.set(true))
+    };
+
+    let other_sender = sender.clone();
+    sender.send(1).expect("send");
+    drop(sender);
+    other_sender.send(2).expect("send");
+    drop(other_sender);
+
+    iterate(&MainContext::default());
+    assert_eq!(*received.borrow(), vec![1, 2]);
+    assert!(disconnected.get());
+    assert!(channel.is_disconnected());
+}
+
+#[test]
+fn batch_dispatch() {
+    let context = MainContext::new();
+    let count = Rc::new(Cell::new(0));
+    let (channel, sender) = {
+        let count = count.clone();
+        Channel::with_context(&context, move |()| count.set(count.get() + 1))
+    };
+
+    for _ in 0..1000 {
+        sender.send(()).expect("send");
+    }
+    assert!(iterate(&context) <= 16);
+    assert_eq!(count.get(), 1000);
+
+    channel.set_max_messages_per_dispatch(100);
+    for _ in 0..1000 {
+        sender.send(()).expect("send");
+    }
+    assert!(iterate(&context) <= 10);
+    assert_eq!(count.get(), 2000);
+}
+
+#[test]
+fn close() {
+    let context = MainContext::new();
+    let count = Rc::new(Cell::new(0));
+    let (channel, sender) = {
+        let count = count.clone();
+        Channel::with_context(&context, move |()| count.set(count.get() + 1))
+    };
+
+    sender.send(()).expect("send");
+    assert!(!channel.is_closed());
+    channel.close();
+    assert!(channel.is_closed());
+    assert!(sender.send(()).is_err());
+    iterate(&context);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn close_from_callback() {
+    let context = MainContext::new();
+    let count = Rc::new(Cell::new(0));
+    let channel = Rc::new(RefCell::new(None::<Channel<()>>));
+    let callback_sender = Rc::new(RefCell::new(None::<Sender<()>>));
+    let send_after_close = Rc::new(Cell::new(None));
+    let (new_channel, sender) = {
+        let channel = channel.clone();
+        let callback_sender = callback_sender.clone();
+        let count = count.clone();
+        let send_after_close = send_after_close.clone();
+        Channel::with_context(&context, move |()| {
+            count.set(count.get() + 1);
+            if let Some(ref channel) = *channel.borrow() {
+                channel.close();
+            }
+            if let Some(ref sender) = *callback_sender.borrow() {
+                send_after_close.set(Some(sender.send(()).is_err()));
+            }
+        })
+    };
+    *channel.borrow_mut() = Some(new_channel);
+    *callback_sender.borrow_mut() = Some(sender.clone());
+
+    sender.send(()).expect("send");
+    sender.send(()).expect("send");
+    iterate(&context);
+    assert_eq!(count.get(), 1);
+    assert_eq!(send_after_close.get(), Some(true));
+    assert!(sender.send(()).is_err());
+    channel.borrow_mut().take();
+}
+
+#[test]
+fn sink() {
+    let context = MainContext::new();
+    let received = Rc::new(RefCell::new(vec![]));
+    let (_channel, mut sender) = {
+        let received = received.clone();
+        Channel::with_context(&context, move |msg| received.borrow_mut().push(msg))
+    };
+
+    block_on(SinkExt::send(&mut sender, 1)).expect("send");
+    block_on(sender.send_async(2)).expect("send");
+    iterate(&context);
+    assert_eq!(*received.borrow(), vec![1, 2]);
+}
+
+#[test]
+fn bounded_sink() {
+    let context = MainContext::new();
+    let received = Rc::new(RefCell::new(vec![]));
+    let (_channel, sender) = {
+        let received = received.clone();
+        Channel::bounded_with_context(&context, 1, move |msg| received.borrow_mut().push(msg))
+    };
+
+    let other_sender = sender.clone();
+    let thread = thread::spawn(move || {
+        let mut sink = sender.into_sink();
+        for i in 0..5 {
+            block_on(sink.send(i)).expect("send");
+        }
+        for i in 5..10 {
+            block_on(other_sender.send_async(i)).expect("send");
+        }
+    });
+
+    while received.borrow().len() < 10 {
+        context.iteration(true);
+    }
+    thread.join().expect("join");
+    assert_eq!(*received.borrow(), (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn oneshot() {
+    let context = MainContext::new();
+    let stream = EventStream::with_context(&context);
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &String| received.borrow_mut().push(msg.clone()));
+    }
+
+    let (sender, receiver) = oneshot_with_context(&context);
+    receiver.on_receive(&stream.stream(), |value: i32| value.to_string());
+    thread::spawn(move || sender.send(42).expect("send"))
+        .join().expect("join");
+    iterate(&context);
+    assert_eq!(*received.borrow(), vec!["42".to_string()]);
+
+    let (sender, receiver) = oneshot_with_context::<i32>(&context);
+    drop(receiver);
+    assert!(sender.send(42).is_err());
+}
+
+#[test]
+fn forward_to_stream() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &String| received.borrow_mut().push(msg.clone()));
+    }
+
+    let (_channel, sender) = Channel::forward_to_stream(&stream.stream(), |num: i32| num.to_string());
+    sender.send(1).expect("send");
+    iterate(&MainContext::default());
+    assert_eq!(*received.borrow(), vec!["1".to_string()]);
+
+    drop(stream);
+    sender.send(2).expect("send");
+    iterate(&MainContext::default());
+    assert_eq!(received.borrow().len(), 1);
+}
+
+#[test]
+fn progress() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &String| received.borrow_mut().push(msg.clone()));
+    }
+
+    let sender = progress_channel(&stream.stream(), |percent: u32| format!("{}%", percent),
+        |result: Result<&str, ()>| result.expect("result").to_string());
+    let other_sender = sender.clone();
+    thread::spawn(move || {
+        sender.progress(50).expect("progress");
+        sender.progress(100).expect("progress");
+        sender.finish(Ok("done")).expect("finish");
+        // The progress and results sent after the first result are ignored.
+        sender.progress(0).expect("progress");
+        other_sender.finish(Err(())).expect("finish");
+    }).join().expect("join");
+    iterate(&MainContext::default());
+    assert_eq!(*received.borrow(), vec!["50%".to_string(), "100%".to_string(), "done".to_string()]);
+}
+
+#[test]
+fn builder() {
+    let context = MainContext::new();
+    let count = Rc::new(Cell::new(0));
+    let (channel, sender) = {
+        let count = count.clone();
+        Channel::builder()
+            .context(&context)
+            .name("builder")
+            .priority(glib::PRIORITY_LOW)
+            .can_recurse(true)
+            .build(move |()| count.set(count.get() + 1))
+    };
+    assert_eq!(channel.priority(), glib::PRIORITY_LOW);
+
+    sender.send(()).expect("send");
+    iterate(&context);
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn builder_context_of_other_thread() {
+    let context = MainContext::new();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_sender, ready_receiver) = mpsc::channel();
+    let thread = {
+        let context = context.clone();
+        let stop = stop.clone();
+        thread::spawn(move || {
+            assert!(context.acquire());
+            ready_sender.send(()).expect("send ready");
+            while !stop.load(Ordering::SeqCst) {
+                context.iteration(true);
+            }
+            context.release();
+        })
+    };
+    ready_receiver.recv().expect("receive ready");
+
+    // The source can be dispatched by the other thread as soon as it is attached, so the options
+    // must already be applied at this point.
+    let (source_sender, source_receiver) = mpsc::channel();
+    let (_channel, sender) = Channel::builder()
+        .context(&context)
+        .name("other-thread")
+        .priority(glib::PRIORITY_LOW)
+        .build(move |()| {
+            let source = glib::main_current_source().expect("current source");
+            let priority: Priority = from_glib(source.get_priority());
+            source_sender.send((priority, source.get_name().map(|name| name.to_string())))
+                .expect("send source");
+            stop.store(true, Ordering::SeqCst);
+        });
+    sender.send(()).expect("send");
+
+    let (priority, name) = source_receiver.recv().expect("receive source");
+    assert_eq!(priority, glib::PRIORITY_LOW);
+    assert_eq!(name.as_deref(), Some("other-thread"));
+    thread.join().expect("join thread");
+}
+
+#[test]
+fn crossbeam() {
+    let count = Rc::new(Cell::new(0));
+    let (_channel, sender) = {
+        let count = count.clone();
+        Channel::new_crossbeam(move |()| count.set(count.get() + 1))
+    };
+
+    let sender = Arc::new(sender);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || sender.send(()).expect("send"))
+        })
+        .collect();
+    for thread in threads {
+        thread.join().expect("join");
+    }
+    iterate(&MainContext::default());
+    assert_eq!(count.get(), 4);
+}
@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+use self::RowMsg::*;
+
+#[derive(Msg)]
+pub enum RowMsg {
+    SetName(String),
+}
+
+#[widget]
+impl Widget for Row {
+    fn model(name: String) -> String {
+        name
+    }
+
+    fn update(&mut self, event: RowMsg) {
+        match event {
+            SetName(name) => self.model = name,
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model,
+        }
+    }
+}
+
+pub struct Item {
+    id: u32,
+    name: String,
+}
+
+pub struct Model {
+    items: Vec<Item>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add(u32, &'static str),
+    Remove(u32),
+    Rename(u32, &'static str),
+    Reverse,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            items: vec![
+                Item { id: 1, name: "one".to_string() },
+                Item { id: 2, name: "two".to_string() },
+            ],
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Add(id, name) => {
+                self.model.items.push(Item { id, name: name.to_string() });
+            },
+            Remove(id) => {
+                self.model.items.retain(|item| item.id != id);
+            },
+            Rename(id, name) => {
+                self.model.items.iter_mut()
+                    .filter(|item| item.id == id)
+                    .for_each(|item| item.name = name.to_string());
+            },
+            Reverse => {
+                self.model.items.reverse();
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="labels"]
+                gtk::Box {
+                    orientation: Vertical,
+                    gtk::Label {
+                        text: "Items",
+                    },
+                    for item in &self.model.items {
+                        gtk::Label {
+                            text: &item.name,
+                        },
+                    },
+                },
+                // The rows of the second loop stay after those of the first one.
+                #[name="two_loops"]
+                gtk::Box {
+                    orientation: Vertical,
+                    gtk::Label {
+                        text: "Names",
+                    },
+                    for item in &self.model.items {
+                        gtk::Label {
+                            text: &item.name,
+                        },
+                    },
+                    gtk::Label {
+                        text: "Ids",
+                    },
+                    for item in &self.model.items {
+                        gtk::Label {
+                            text: &item.id.to_string(),
+                        },
+                    },
+                    gtk::Label {
+                        text: "End",
+                    },
+                },
+                #[name="rows"]
+                gtk::Box {
+                    orientation: Vertical,
+                    for (id, item) in self.model.items.iter().map(|item| (item.id, item)) {
+                        Row(item.name.clone()) {
+                            SetName: item.name.clone(),
+                        },
+                    },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{ContainerExt, Label, LabelExt};
+    use gtk_test::run_loop;
+    use relm::Cast;
+
+    use crate::Msg::{Add, Remove, Rename, Reverse};
+    use crate::Win;
+
+    fn texts<C: ContainerExt>(container: &C) -> Vec<String> {
+        container.get_children().into_iter()
+            .map(|child| child.downcast::<Label>().expect("label").get_text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn child_loop() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let labels = &widgets.labels;
+        let rows = &widgets.rows;
+        let two_loops = &widgets.two_loops;
+        let emit = |msg| {
+            component.emit(msg);
+            // Also process the messages sent to the rows.
+            run_loop();
+        };
+
+        assert_eq!(texts(labels), ["Items", "one", "two"]);
+        assert_eq!(texts(rows), ["one", "two"]);
+        assert_eq!(texts(two_loops), ["Names", "one", "two", "Ids", "1", "2", "End"]);
+
+        emit(Add(3, "three"));
+        assert_eq!(texts(labels), ["Items", "one", "two", "three"]);
+        assert_eq!(texts(rows), ["one", "two", "three"]);
+        assert_eq!(texts(two_loops), ["Names", "one", "two", "three", "Ids", "1", "2", "3", "End"]);
+
+        emit(Rename(2, "deux"));
+        assert_eq!(texts(labels), ["Items", "one", "deux", "three"]);
+        assert_eq!(texts(rows), ["one", "deux", "three"]);
+
+        emit(Remove(1));
+        assert_eq!(texts(labels), ["Items", "deux", "three"]);
+        assert_eq!(texts(rows), ["deux", "three"]);
+        assert_eq!(texts(two_loops), ["Names", "deux", "three", "Ids", "2", "3", "End"]);
+
+        let children = rows.get_children();
+        emit(Reverse);
+        assert_eq!(texts(two_loops), ["Names", "three", "deux", "Ids", "3", "2", "End"]);
+        assert_eq!(texts(labels), ["Items", "three", "deux"]);
+        assert_eq!(texts(rows), ["three", "deux"]);
+        let reversed: Vec<_> = children.into_iter().rev().collect();
+        assert_eq!(rows.get_children(), reversed);
+    }
+}
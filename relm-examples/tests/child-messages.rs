@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    WidgetExt,
+};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    received: u32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    SetCount(i32),
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model() -> CounterModel {
+        CounterModel {
+            received: 0,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            SetCount(_) => self.model.received += 1,
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model.received.to_string(),
+        }
+    }
+}
+
+pub struct Model {
+    count: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    Set(i32),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            count: 1,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            Set(count) => self.model.count = count,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            #[name="counter"]
+            Counter {
+                SetCount: self.model.count,
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+    use gtk_test::run_loop;
+
+    use crate::Msg::Set;
+    use crate::Win;
+
+    #[test]
+    fn child_messages() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        run_loop();
+        assert_eq!(widgets.counter.get_text(), "1");
+
+        // The value didn't change, so the message is not sent again.
+        component.emit(Set(1));
+        run_loop();
+        assert_eq!(widgets.counter.get_text(), "1");
+
+        component.emit(Set(2));
+        run_loop();
+        assert_eq!(widgets.counter.get_text(), "2");
+    }
+}
@@ -0,0 +1,139 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::GreeterMsg::*;
+use self::Msg::*;
+
+#[derive(Clone, PartialEq)]
+pub struct Config {
+    greeting: String,
+}
+
+pub struct GreeterModel {
+    config: Config,
+}
+
+#[derive(Msg)]
+pub enum GreeterMsg {
+    SetConfig(Config),
+}
+
+#[widget]
+impl Widget for Greeter {
+    fn model(config: Config) -> GreeterModel {
+        GreeterModel {
+            config,
+        }
+    }
+
+    fn update(&mut self, event: GreeterMsg) {
+        match event {
+            SetConfig(config) => self.model.config = config,
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: &self.model.config.greeting,
+        }
+    }
+}
+
+pub struct Model {
+    config: Config,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    ChangeGreeting,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            config: Config {
+                greeting: "Hello".to_string(),
+            },
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            ChangeGreeting => {
+                self.model.config = Config {
+                    greeting: "Bonjour".to_string(),
+                };
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="greeter"]
+                #[param_msg="SetConfig"]
+                Greeter(self.model.config.clone()) {
+                },
+                #[name="button"]
+                gtk::Button {
+                    clicked => ChangeGreeting,
+                    label: "Change greeting",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+    use gtk_test::run_loop;
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn child_model_param() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        assert_eq!(widgets.greeter.get_text(), "Hello");
+
+        click(&widgets.button);
+        run_loop();
+        assert_eq!(widgets.greeter.get_text(), "Bonjour");
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk::{ButtonExt, LabelExt};
+use relm::{Component, Widget};
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Change,
+}
+
+#[widget]
+impl Widget for Child {
+    fn model(destroyed: Rc<Cell<bool>>) -> Rc<Cell<bool>> {
+        destroyed
+    }
+
+    fn on_destroy(&mut self) {
+        self.model.set(true);
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Change => self.widgets.label.set_text("changed"),
+        }
+    }
+
+    view! {
+        #[name="label"]
+        gtk::Label {
+            text: "child",
+        }
+    }
+}
+
+#[widget]
+impl Widget for ClickableChild {
+    fn model(destroyed: Rc<Cell<bool>>) -> Rc<Cell<bool>> {
+        destroyed
+    }
+
+    fn on_destroy(&mut self) {
+        self.model.set(true);
+    }
+
+    fn update(&mut self, _event: Msg) {
+    }
+
+    view! {
+        gtk::Button {
+            clicked => Msg::Change,
+            label: "child",
+        }
+    }
+}
+
+pub struct SelfDestroyingModel {
+    component: Rc<RefCell<Option<Component<SelfDestroying>>>>,
+    destroyed: Rc<Cell<bool>>,
+}
+
+// Destroys itself from update(), like a dialog closing itself.
+#[widget]
+impl Widget for SelfDestroying {
+    fn model((destroyed, component): (Rc<Cell<bool>>, Rc<RefCell<Option<Component<SelfDestroying>>>>))
+        -> SelfDestroyingModel
+    {
+        SelfDestroyingModel {
+            component,
+            destroyed,
+        }
+    }
+
+    fn on_destroy(&mut self) {
+        self.model.destroyed.set(true);
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Change => {
+                let component = self.model.component.borrow_mut().take();
+                if let Some(component) = component {
+                    component.destroy();
+                }
+            },
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: "self-destroying",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use gtk::{ButtonExt, ContainerExt, Orientation};
+    use gtk_test::run_loop;
+
+    use super::{Child, ClickableChild, Msg, SelfDestroying};
+
+    #[test]
+    fn destroy() {
+        gtk::init().expect("gtk init");
+        let container = gtk::Box::new(Orientation::Vertical, 0);
+        let destroyed = Rc::new(Cell::new(false));
+        let component = relm::create_component::<Child>(destroyed.clone());
+        container.add(component.widget());
+        assert_eq!(container.get_children().len(), 1);
+
+        let stream = component.stream();
+        component.destroy();
+        assert!(destroyed.get());
+        assert!(container.get_children().is_empty());
+        assert!(stream.try_emit(Msg::Change).is_err());
+    }
+
+    #[test]
+    fn destroy_disconnects_signal_handlers() {
+        gtk::init().expect("gtk init");
+        let destroyed = Rc::new(Cell::new(false));
+        let component = relm::create_component::<ClickableChild>(destroyed.clone());
+        let button = component.widget().clone();
+        component.destroy();
+        assert!(destroyed.get());
+        // The handler would emit on the dropped stream and panic if it was still connected.
+        button.clicked();
+    }
+
+    #[test]
+    fn destroy_from_update() {
+        gtk::init().expect("gtk init");
+        let destroyed = Rc::new(Cell::new(false));
+        let slot = Rc::new(RefCell::new(None));
+        let component = relm::create_component::<SelfDestroying>((destroyed.clone(), slot.clone()));
+        let stream = component.stream();
+        *slot.borrow_mut() = Some(component);
+
+        stream.emit(Msg::Change);
+        run_loop();
+        // on_destroy() is called once update() returns.
+        assert!(destroyed.get());
+        assert!(slot.borrow().is_none());
+        assert!(!stream.is_alive());
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Increment,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model() -> CounterModel {
+        CounterModel {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Increment => self.model.counter += 1,
+        }
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Vertical,
+            #[name="inc_button"]
+            gtk::Button {
+                clicked => Increment,
+                label: "+",
+            },
+            #[name="label"]
+            gtk::Label {
+                text: &self.model.counter.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            #[name="counter"]
+            Counter,
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+    use relm_test::click;
+
+    use crate::{Counter, Win};
+
+    #[test]
+    fn component_widgets() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        assert_eq!(component.widgets().counter, widgets.counter);
+
+        let counter = relm::create_component::<Counter>(());
+        assert_eq!(counter.widgets().label.get_text(), "0");
+
+        click(&counter.widgets().inc_button);
+        assert_eq!(counter.widgets().label.get_text(), "1");
+    }
+}
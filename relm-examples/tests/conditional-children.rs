@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum BadgeMsg {
+}
+
+#[widget]
+impl Widget for Badge {
+    fn model() {
+    }
+
+    fn update(&mut self, _msg: BadgeMsg) {
+    }
+
+    view! {
+        gtk::Label {
+            text: "Admin",
+        }
+    }
+}
+
+pub struct Model {
+    admin: bool,
+    logged_in: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    LogIn,
+    LogOut,
+    Promote,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            admin: false,
+            logged_in: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            LogIn => self.model.logged_in = true,
+            LogOut => {
+                self.model.admin = false;
+                self.model.logged_in = false;
+            },
+            Promote => self.model.admin = true,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                if self.model.logged_in {
+                    #[name="welcome"]
+                    gtk::Label {
+                        text: "Welcome",
+                    },
+                    if self.model.admin {
+                        #[name="badge"]
+                        Badge,
+                    }
+                    else {
+                        #[name="promote_button"]
+                        gtk::Button {
+                            clicked => Promote,
+                            label: "Promote",
+                        },
+                    },
+                }
+                else {
+                    #[name="login_button"]
+                    gtk::Button {
+                        clicked => LogIn,
+                        label: "Log in",
+                    },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::WidgetExt;
+    use relm_test::click;
+
+    use crate::Msg::LogOut;
+    use crate::Win;
+
+    #[test]
+    fn conditional_children() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let welcome = &widgets.welcome;
+        let badge = &widgets.badge;
+        let promote_button = &widgets.promote_button;
+        let login_button = &widgets.login_button;
+
+        assert!(!welcome.get_visible());
+        assert!(!badge.get_visible());
+        assert!(!promote_button.get_visible());
+        assert!(login_button.get_visible());
+
+        click(login_button);
+        assert!(welcome.get_visible());
+        assert!(!badge.get_visible());
+        assert!(promote_button.get_visible());
+        assert!(!login_button.get_visible());
+
+        click(promote_button);
+        assert!(welcome.get_visible());
+        assert!(badge.get_visible());
+        assert!(!promote_button.get_visible());
+
+        component.emit(LogOut);
+        let _ = component.owned_stream().dispatch_pending();
+        assert!(!welcome.get_visible());
+        assert!(!badge.get_visible());
+        assert!(!promote_button.get_visible());
+        assert!(login_button.get_visible());
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm::dnd::Uris;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum Msg {
+    Dropped(Vec<String>),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Vec<String> {
+        vec![]
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Dropped(uris) => self.model = uris,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="source"]
+                gtk::Label {
+                    drag_source: || "Dragged text".to_string(),
+                    text: "Drag me",
+                },
+                #[name="destination"]
+                gtk::Label {
+                    drop: |uris: Uris, _, _| Dropped(uris.0),
+                    text: &self.model.join("\n"),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::WidgetExt;
+
+    use crate::Win;
+
+    #[test]
+    fn drag_and_drop_targets() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        let source_targets = widgets.source.drag_source_get_target_list().expect("source targets");
+        assert!(source_targets.find(&gdk::Atom::intern("text/plain")).is_some());
+        assert!(source_targets.find(&gdk::Atom::intern("text/uri-list")).is_none());
+
+        let destination_targets = widgets.destination.drag_dest_get_target_list().expect("destination targets");
+        assert!(destination_targets.find(&gdk::Atom::intern("text/uri-list")).is_some());
+        assert!(destination_targets.find(&gdk::Atom::intern("text/plain")).is_none());
+    }
+}
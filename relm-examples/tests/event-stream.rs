@@ -0,0 +1,684 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::mem;
+use std::os::unix::process::ExitStatusExt;
+use std::process::Command;
+use std::rc::Rc;
+use std::thread;
+
+use futures::StreamExt;
+use futures::executor::block_on;
+use glib::MainContext;
+use relm::{EventStream, MessagePriority, ObservePhase, ObserverHandle, StreamError};
+
+fn count_messages<MSG>(stream: &EventStream<MSG>) -> Rc<Cell<usize>> {
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    stream.observe(move |_| counter.set(counter.get() + 1));
+    count
+}
+
+#[test]
+fn nested_locks() {
+    let stream = EventStream::new_detached();
+    let count = count_messages(&stream);
+
+    let outer_lock = stream.lock();
+    let inner_lock = stream.lock();
+    drop(inner_lock);
+    stream.emit(1);
+    assert_eq!(count.get(), 0);
+
+    drop(outer_lock);
+    stream.emit(2);
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn observe_once() {
+    let stream = EventStream::new_detached();
+    let first = Rc::new(Cell::new(0));
+    let second = Rc::new(Cell::new(0));
+    {
+        let first = first.clone();
+        stream.observe_once(move |_| first.set(first.get() + 1));
+    }
+    {
+        let second = second.clone();
+        stream.observe_once(move |_| second.set(second.get() + 1));
+    }
+    let count = count_messages(&stream);
+
+    stream.emit(1);
+    assert_eq!(first.get(), 1);
+    assert_eq!(second.get(), 1);
+    assert_eq!(count.get(), 1);
+
+    stream.emit(2);
+    assert_eq!(first.get(), 1);
+    assert_eq!(second.get(), 1);
+    assert_eq!(count.get(), 2);
+}
+
+// Count the number of times the message is cloned.
+struct Counted(i32, Rc<Cell<usize>>);
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        self.1.set(self.1.get() + 1);
+        Counted(self.0, self.1.clone())
+    }
+}
+
+#[test]
+fn observe_owned() {
+    let stream = EventStream::new_detached();
+    let clones = Rc::new(Cell::new(0));
+    let all = Rc::new(RefCell::new(vec![]));
+    let even = Rc::new(RefCell::new(vec![]));
+    {
+        let all = all.clone();
+        stream.observe_owned(move |msg: Counted| all.borrow_mut().push(msg.0));
+    }
+    {
+        let even = even.clone();
+        stream.stream().observe_filtered(|msg| msg.0 % 2 == 0, move |msg| even.borrow_mut().push(msg.0));
+    }
+
+    for value in 1..5 {
+        stream.emit(Counted(value, clones.clone()));
+    }
+    assert_eq!(*all.borrow(), vec![1, 2, 3, 4]);
+    assert_eq!(*even.borrow(), vec![2, 4]);
+    // One clone per owned observer, only for the messages they accept.
+    assert_eq!(clones.get(), 6);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ChildMsg {
+    Increment,
+    Decrement,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ParentMsg {
+    ChildIncremented,
+}
+
+#[test]
+fn map_forward_to() {
+    let child = EventStream::new_detached();
+    let parent = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        parent.observe(move |msg: &ParentMsg| received.borrow_mut().push(msg.clone()));
+    }
+
+    let forwarding = child.stream().map_forward_to(parent.stream(), |msg| match msg {
+        ChildMsg::Increment => Some(ParentMsg::ChildIncremented),
+        ChildMsg::Decrement => None,
+    });
+    child.emit(ChildMsg::Increment);
+    child.emit(ChildMsg::Decrement);
+    child.emit(ChildMsg::Increment);
+    assert_eq!(*received.borrow(), vec![ParentMsg::ChildIncremented, ParentMsg::ChildIncremented]);
+
+    forwarding.remove();
+    child.emit(ChildMsg::Increment);
+    assert_eq!(received.borrow().len(), 2);
+}
+
+#[test]
+fn filter_forward_to() {
+    let child = EventStream::new_detached();
+    let other = EventStream::new_detached();
+    let count = count_messages(&other);
+
+    child.stream().filter_forward_to(other.stream(), |msg| *msg == ChildMsg::Increment);
+    child.emit(ChildMsg::Increment);
+    child.emit(ChildMsg::Decrement);
+    assert_eq!(count.get(), 1);
+
+    child.stream().forward_to(other.stream());
+    child.emit(ChildMsg::Decrement);
+    assert_eq!(count.get(), 2);
+}
+
+#[test]
+fn observer_adds_observer() {
+    let stream = EventStream::new_detached();
+    let added_count = Rc::new(Cell::new(0));
+    {
+        let handle = stream.stream();
+        let added_count = added_count.clone();
+        stream.observe_once(move |_| {
+            let added_count = added_count.clone();
+            handle.observe(move |_| added_count.set(added_count.get() + 1));
+        });
+    }
+
+    // The observer added during an emit only sees the next messages.
+    stream.emit(1);
+    assert_eq!(added_count.get(), 0);
+    stream.emit(2);
+    assert_eq!(added_count.get(), 1);
+}
+
+#[test]
+fn observer_emits_message() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let handle = stream.stream();
+        stream.observe(move |msg: &i32| {
+            if *msg < 3 {
+                handle.emit(msg + 1);
+            }
+        });
+    }
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &i32| received.borrow_mut().push(*msg));
+    }
+
+    stream.emit(1);
+    assert_eq!(*received.borrow(), vec![3, 2, 1]);
+    assert_eq!(stream.len(), 3);
+}
+
+#[test]
+fn observer_removes_observer() {
+    let stream = EventStream::new_detached();
+    let count = Rc::new(Cell::new(0));
+    let second: Rc<RefCell<Option<ObserverHandle<i32>>>> = Rc::new(RefCell::new(None));
+    {
+        let second = second.clone();
+        stream.observe(move |_| {
+            if let Some(handle) = second.borrow_mut().take() {
+                handle.remove();
+            }
+        });
+    }
+    {
+        let count = count.clone();
+        *second.borrow_mut() = Some(stream.observe(move |_| count.set(count.get() + 1)));
+    }
+
+    // The second observer is removed before being called.
+    stream.emit(1);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn debug() {
+    let stream = EventStream::new_detached();
+    stream.set_name("progress");
+    stream.observe(|_| ());
+    stream.emit(1);
+    assert_eq!(format!("{:?}", stream), r#"EventStream { name: Some("progress"), len: 1, observers: 1 }"#);
+    let handle = stream.stream();
+    assert_eq!(format!("{:?}", handle), r#"StreamHandle { name: Some("progress"), len: 1, observers: 1 }"#);
+    drop(stream);
+    assert_eq!(format!("{:?}", handle), "StreamHandle { dropped }");
+}
+
+#[test]
+fn panic_handler() {
+    let stream = EventStream::new_detached();
+    let panics = Rc::new(RefCell::new(vec![]));
+    {
+        let panics = panics.clone();
+        stream.set_panic_handler(move |error| {
+            let message = error.downcast_ref::<&str>().map(|message| message.to_string());
+            panics.borrow_mut().push(message);
+        });
+    }
+    stream.observe(|msg: &i32| {
+        if *msg == 2 {
+            panic!("observer panicked");
+        }
+    });
+    let count = count_messages(&stream);
+
+    stream.emit(1);
+    stream.emit(2);
+    stream.emit(3);
+    assert_eq!(*panics.borrow(), vec![Some("observer panicked".to_string())]);
+    assert_eq!(count.get(), 3);
+}
+
+#[test]
+fn observe_stream() {
+    let stream = EventStream::new_detached();
+    let mut messages = stream.observe_stream();
+    let mut latest = stream.observe_stream_bounded(2);
+    stream.emit(1);
+    stream.emit(2);
+    stream.emit(3);
+    assert_eq!(block_on(messages.next()), Some(1));
+    assert_eq!(block_on(latest.next()), Some(2));
+
+    drop(latest);
+    assert_eq!(format!("{:?}", stream), "EventStream { name: None, len: 3, observers: 1 }");
+
+    drop(stream);
+    assert_eq!(block_on(messages.collect::<Vec<_>>()), vec![2, 3]);
+}
+
+#[test]
+fn upgrade() {
+    let stream = EventStream::new_detached();
+    let count = count_messages(&stream);
+    let handle = stream.stream();
+    assert!(handle.is_alive());
+
+    let strong = handle.upgrade().expect("strong handle");
+    strong.emit(1);
+    assert_eq!(count.get(), 1);
+    assert_eq!(strong.len(), 1);
+
+    drop(strong);
+    drop(stream);
+    assert!(!handle.is_alive());
+    assert!(handle.upgrade().is_none());
+}
+
+#[test]
+fn builder() {
+    let stream = EventStream::<()>::builder()
+        .name("low")
+        .priority(glib::PRIORITY_LOW)
+        .can_recurse(true)
+        .build();
+    assert_eq!(stream.priority(), glib::PRIORITY_LOW);
+    assert_eq!(format!("{:?}", stream), r#"EventStream { name: Some("low"), len: 0, observers: 0 }"#);
+}
+
+#[test]
+fn dispatch_pending() {
+    let stream = EventStream::new_detached();
+    let handle = stream.stream();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        let handle = handle.clone();
+        stream.set_callback(move |msg| {
+            received.borrow_mut().push(msg);
+            if msg == 1 {
+                // Re-entrant call: the message is only queued.
+                handle.emit(3);
+                assert_eq!(handle.dispatch_pending(), 0);
+            }
+        });
+    }
+    stream.emit(1);
+    stream.emit(2);
+    assert!(received.borrow().is_empty());
+
+    assert_eq!(handle.dispatch_pending(), 3);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+
+    stream.emit(4);
+    assert_eq!(stream.close(), 1);
+    assert_eq!(stream.dispatch_pending(), 0);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+}
+
+#[test]
+fn close_flushing() {
+    let stream = EventStream::new_detached();
+    let handle = stream.stream();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| {
+            received.borrow_mut().push(msg);
+            if msg == 1 {
+                handle.emit(3);
+            }
+        });
+    }
+    stream.emit(1);
+    stream.emit(2);
+    assert_eq!(stream.close_flushing(), 0);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+
+    stream.emit(4);
+    assert_eq!(stream.dispatch_pending(), 0);
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+}
+
+#[test]
+fn close_draining() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| received.borrow_mut().push(msg));
+    }
+    stream.emit(1);
+    stream.emit_with_priority(2, MessagePriority::High);
+    assert_eq!(stream.close_draining(), vec![2, 1]);
+    assert!(stream.is_empty());
+    assert!(received.borrow().is_empty());
+}
+
+#[test]
+fn close_draining_while_locked() {
+    let stream = EventStream::new_detached();
+    stream.emit(1);
+    let _lock = stream.lock_queueing();
+    stream.emit_with_priority(2, MessagePriority::Low);
+    stream.emit_with_priority(3, MessagePriority::High);
+    stream.emit(4);
+    assert_eq!(stream.close_draining(), vec![3, 1, 4, 2]);
+}
+
+#[test]
+fn close_from_coalescer() {
+    let stream = Rc::new(EventStream::new_detached());
+    let handle = stream.stream();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| received.borrow_mut().push(msg));
+    }
+    let discarded = Rc::new(Cell::new(None));
+    {
+        let discarded = discarded.clone();
+        let weak_stream = Rc::downgrade(&stream);
+        // The stream is borrowed while the coalescer is called.
+        stream.set_coalescer(move |_, _| {
+            if let Some(stream) = weak_stream.upgrade() {
+                discarded.set(Some(stream.close()));
+            }
+            None
+        });
+    }
+    handle.emit(1);
+    handle.emit(2);
+    assert_eq!(discarded.get(), Some(1));
+    // The callback was removed.
+    handle.dispatch_pending();
+    assert!(received.borrow().is_empty());
+}
+
+fn count_on_close<MSG>(stream: &EventStream<MSG>) -> Rc<Cell<usize>> {
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    stream.on_close(move || counter.set(counter.get() + 1));
+    count
+}
+
+#[test]
+fn close_then_drop() {
+    let stream = EventStream::new_detached();
+    let handle = stream.stream();
+    let closed = count_on_close(&stream);
+    stream.emit(1);
+    assert_eq!(stream.close(), 1);
+    // Closing again does nothing.
+    assert_eq!(stream.close(), 0);
+    stream.emit(2);
+    assert_eq!(stream.close_draining(), Vec::<i32>::new());
+    assert_eq!(closed.get(), 1);
+
+    drop(stream);
+    assert_eq!(closed.get(), 1);
+    assert!(!handle.is_alive());
+}
+
+#[test]
+fn drop_without_close() {
+    for &detached in &[false, true] {
+        let stream =
+            if detached {
+                EventStream::new_detached()
+            }
+            else {
+                EventStream::with_context(&MainContext::default())
+            };
+        let handle = stream.stream();
+        let closed = count_on_close(&stream);
+        stream.emit(1);
+        drop(stream);
+        assert_eq!(closed.get(), 1);
+        assert!(!handle.is_alive());
+        assert!(handle.try_emit(2).is_err());
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Progress {
+    Done,
+    Step(f64),
+}
+
+#[test]
+fn coalescer() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| received.borrow_mut().push(msg));
+    }
+    let count = count_messages(&stream);
+    stream.set_coalescer(|last: &i32, msg: &i32| {
+        if *last < 10 && *msg < 10 {
+            Some(last + msg)
+        }
+        else {
+            None
+        }
+    });
+
+    for msg in &[1, 2, 3, 10, 4, 5] {
+        stream.emit(*msg);
+    }
+    assert_eq!(stream.len(), 3);
+    // The observers still see every message.
+    assert_eq!(count.get(), 6);
+
+    assert_eq!(stream.dispatch_pending(), 3);
+    assert_eq!(*received.borrow(), vec![6, 10, 9]);
+}
+
+#[test]
+fn coalesce_matching() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| received.borrow_mut().push(msg));
+    }
+    stream.coalesce_matching(|last, msg| mem::discriminant(last) == mem::discriminant(msg));
+
+    for step in 0..1000 {
+        stream.emit(Progress::Step(f64::from(step)));
+    }
+    stream.emit(Progress::Done);
+    stream.emit(Progress::Step(0.0));
+    assert_eq!(stream.dispatch_pending(), 3);
+    assert_eq!(*received.borrow(), vec![Progress::Step(999.0), Progress::Done, Progress::Step(0.0)]);
+
+    // Only the messages waiting to be dispatched are replaced.
+    stream.emit(Progress::Step(1.0));
+    assert_eq!(stream.dispatch_pending(), 1);
+    assert_eq!(received.borrow().last(), Some(&Progress::Step(1.0)));
+}
+
+#[test]
+fn priority_lanes() {
+    let stream = EventStream::new_detached();
+    let handle = stream.stream();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.set_callback(move |msg| received.borrow_mut().push(msg));
+    }
+    stream.emit_with_priority(1, MessagePriority::Low);
+    stream.emit(2);
+    stream.emit_with_priority(3, MessagePriority::High);
+    handle.emit_with_priority(4, MessagePriority::Low);
+    stream.emit(5);
+    handle.emit_with_priority(6, MessagePriority::High);
+    assert_eq!(stream.len(), 6);
+
+    assert_eq!(stream.dispatch_pending(), 6);
+    assert_eq!(*received.borrow(), vec![3, 6, 2, 5, 1, 4]);
+
+    {
+        let _lock = stream.lock_queueing();
+        stream.emit_with_priority(7, MessagePriority::Low);
+        stream.emit_with_priority(8, MessagePriority::High);
+    }
+    stream.emit(9);
+    assert_eq!(stream.drain_pending(), vec![8, 9, 7]);
+}
+
+#[test]
+fn replay() {
+    let stream = EventStream::new_detached();
+    stream.emit(0);
+    stream.set_replay(2);
+    for msg in 1..4 {
+        stream.emit(msg);
+    }
+
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg| received.borrow_mut().push(*msg));
+    }
+    // The last messages are sent as soon as the observer is added.
+    assert_eq!(*received.borrow(), vec![2, 3]);
+    stream.emit(4);
+    assert_eq!(*received.borrow(), vec![2, 3, 4]);
+
+    let other = EventStream::new_detached();
+    let forwarded = count_messages(&other);
+    stream.stream().forward_to(other.stream());
+    assert_eq!(forwarded.get(), 2);
+
+    let once = Rc::new(RefCell::new(vec![]));
+    {
+        let once = once.clone();
+        stream.observe_once(move |msg| once.borrow_mut().push(*msg));
+    }
+    assert_eq!(*once.borrow(), vec![3]);
+
+    stream.set_replay(0);
+    let count = count_messages(&stream);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn observe_after_update() {
+    let stream = EventStream::new_detached();
+    let model = Rc::new(Cell::new(0));
+    {
+        let model = model.clone();
+        stream.set_callback(move |msg| model.set(model.get() + msg));
+    }
+    let seen = Rc::new(RefCell::new(vec![]));
+    {
+        let model = model.clone();
+        let seen = seen.clone();
+        stream.observe_in_phase(ObservePhase::BeforeUpdate, move |msg| {
+            seen.borrow_mut().push(("before", *msg, model.get()))
+        });
+    }
+    {
+        let model = model.clone();
+        let seen = seen.clone();
+        stream.observe_in_phase(ObservePhase::AfterUpdate, move |msg| {
+            seen.borrow_mut().push(("after", *msg, model.get()))
+        });
+    }
+
+    stream.emit(1);
+    stream.emit(2);
+    assert_eq!(*seen.borrow(), vec![("before", 1, 0), ("before", 2, 0)]);
+
+    assert_eq!(stream.dispatch_pending(), 2);
+    assert_eq!(*seen.borrow(), vec![("before", 1, 0), ("before", 2, 0), ("after", 1, 1), ("after", 2, 3)]);
+}
+
+#[test]
+fn connect_async() {
+    let stream = EventStream::new_detached();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &Result<i32, String>| received.borrow_mut().push(msg.clone()));
+    }
+    relm::connect_async(&stream.stream(), |callback| callback(Ok(1)), Ok, |error| Err(error.to_string()));
+    relm::connect_async(&stream.stream(), |callback: relm::AsyncCallback<i32>| {
+        callback(Err(glib::Error::new(glib::FileError::Failed, "failed")))
+    }, Ok, |error| Err(error.to_string()));
+    assert_eq!(*received.borrow(), vec![Ok(1), Err("failed".to_string())]);
+}
+
+struct PanicOnDrop;
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("destructor panicked");
+    }
+}
+
+#[test]
+fn panicking_destructor_aborts() {
+    // The test is run again in a subprocess since the process is expected to be aborted.
+    if env::var_os("RELM_TEST_PANICKING_DESTRUCTOR").is_some() {
+        let stream = EventStream::new_detached();
+        let guard = PanicOnDrop;
+        stream.set_callback(move |()| {
+            let _guard = &guard;
+        });
+        drop(stream);
+        return;
+    }
+
+    let status = Command::new(env::current_exe().expect("current exe"))
+        .args(["--exact", "panicking_destructor_aborts", "--test-threads=1"])
+        .env("RELM_TEST_PANICKING_DESTRUCTOR", "1")
+        .status()
+        .expect("run test");
+    assert_eq!(status.signal(), Some(libc::SIGABRT));
+}
+
+#[test]
+fn new_before_gtk_init() {
+    assert_eq!(EventStream::<()>::try_new().err(), Some(StreamError::NotInitialized));
+
+    // A thread owning the default main context can dispatch the messages without GTK.
+    let context = MainContext::default();
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    assert!(EventStream::<()>::try_new().is_ok());
+    context.release();
+}
@@ -0,0 +1,53 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::thread;
+use std::time::Duration;
+
+use relm::EventStream;
+use relm::execute::{shutdown, spawn};
+
+#[test]
+fn spawn_and_shutdown() {
+    let stream = EventStream::new();
+    spawn(async {
+        // Runs on another thread, so blocking doesn't freeze the main loop.
+        thread::sleep(Duration::from_millis(10));
+        21
+    }, stream.stream(), |value| value * 2);
+    let value = relm_test::wait_for(stream.stream(), |_| true, Duration::from_secs(5));
+    assert_eq!(value.expect("spawned future output"), 42);
+
+    // The output of a future whose stream was dropped is discarded.
+    let dropped_stream = EventStream::<i32>::new();
+    spawn(async { 1 }, dropped_stream.stream(), |value| value);
+    drop(dropped_stream);
+
+    shutdown();
+
+    // A new runtime is started after the shutdown.
+    spawn(async { thread::current().name() == Some("relm-executor") }, stream.stream(),
+        |on_runtime_thread| on_runtime_thread as i32);
+    let value = relm_test::wait_for(stream.stream(), |_| true, Duration::from_secs(5));
+    assert_eq!(value.expect("spawned future output"), 1);
+    shutdown();
+}
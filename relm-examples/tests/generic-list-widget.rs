@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::fmt::Display;
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::ListMsg::*;
+use self::Msg::*;
+
+pub struct ListModel<T> {
+    items: Vec<T>,
+}
+
+#[derive(Msg)]
+pub enum ListMsg<T> {
+    Append(T),
+    Clear,
+}
+
+#[widget]
+impl<T: 'static + Display> Widget for ListView<T>
+    where T: Clone,
+{
+    fn model(items: Vec<T>) -> ListModel<T> {
+        ListModel {
+            items,
+        }
+    }
+
+    fn update(&mut self, event: ListMsg<T>) {
+        match event {
+            Append(item) => self.model.items.push(item),
+            Clear => self.model.items.clear(),
+        }
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Vertical,
+            #[name="count"]
+            gtk::Label {
+                text: &self.model.items.len().to_string(),
+            },
+            for item in &self.model.items {
+                gtk::Label {
+                    text: &item.to_string(),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    AddName,
+    AddNumber,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            AddName => self.components.names.emit(Append("Carol".to_string())),
+            AddNumber => self.components.numbers.emit(Append(3)),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Horizontal,
+                #[name="numbers"]
+                ListView<i32>(vec![1, 2]),
+                #[name="names"]
+                ListView<String>(vec!["Alice".to_string(), "Bob".to_string()]),
+                #[name="add_number"]
+                gtk::Button {
+                    clicked => AddNumber,
+                    label: "Add number",
+                },
+                #[name="add_name"]
+                gtk::Button {
+                    clicked => AddName,
+                    label: "Add name",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{ContainerExt, Label, LabelExt};
+    use gtk_test::run_loop;
+    use relm::Cast;
+    use relm_test::click;
+
+    use crate::Win;
+
+    fn texts(container: &gtk::Box) -> Vec<String> {
+        container.get_children().into_iter()
+            .map(|child| child.downcast::<Label>().expect("label").get_text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn generic_list_widget() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        assert_eq!(texts(&widgets.numbers), ["2", "1", "2"]);
+        assert_eq!(texts(&widgets.names), ["2", "Alice", "Bob"]);
+
+        click(&widgets.add_number);
+        run_loop();
+        assert_eq!(texts(&widgets.numbers), ["3", "1", "2", "3"]);
+
+        click(&widgets.add_name);
+        run_loop();
+        assert_eq!(texts(&widgets.names), ["3", "Alice", "Bob", "Carol"]);
+    }
+}
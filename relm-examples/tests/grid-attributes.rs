@@ -23,6 +23,7 @@ use gtk::{
     ButtonExt,
     GridExt,
     Inhibit,
+    LabelExt,
     WidgetExt,
 };
 use relm::Widget;
@@ -30,6 +31,25 @@ use relm_derive::{Msg, widget};
 
 use self::Msg::*;
 
+#[derive(Msg)]
+pub enum DisplayMsg {
+}
+
+#[widget]
+impl Widget for Display {
+    fn model() {
+    }
+
+    fn update(&mut self, _event: DisplayMsg) {
+    }
+
+    view! {
+        gtk::Label {
+            text: "0",
+        }
+    }
+}
+
 #[derive(Msg)]
 pub enum Msg {
     Quit,
@@ -144,7 +164,25 @@ impl Widget for Win {
                         left_attach: 2,
                         top_attach: 3,
                     },
-                }
+                },
+                #[name="button_equal"]
+                gtk::Button {
+                    label: "=",
+                    cell: {
+                        left: 0,
+                        top: 4,
+                        width: 3,
+                        height: 1,
+                    },
+                },
+                #[name="display"]
+                Display {
+                    cell: {
+                        left: 0,
+                        top: 5,
+                        width: 3,
+                    },
+                },
             },
             delete_event(_, _) => (Quit, Inhibit(false))
         }
@@ -152,7 +190,7 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
 
 #[cfg(test)]
@@ -176,6 +214,8 @@ mod tests {
         let button_plus_minus = &widgets.button_plus_minus;
         let button0 = &widgets.button0;
         let button_dot = &widgets.button_dot;
+        let button_equal = &widgets.button_equal;
+        let display = &widgets.display;
 
         let button7_allocation = button7.get_allocation();
         let button8_allocation = button8.get_allocation();
@@ -189,6 +229,8 @@ mod tests {
         let button_pm_allocation = button_plus_minus.get_allocation();
         let button0_allocation = button0.get_allocation();
         let button_dot_allocation = button_dot.get_allocation();
+        let button_equal_allocation = button_equal.get_allocation();
+        let display_allocation = display.get_allocation();
         assert!(button7_allocation.x < button8_allocation.x);
         assert_eq!(button7_allocation.x, button4_allocation.x);
         assert!(button7_allocation.y < button4_allocation.y);
@@ -215,5 +257,13 @@ mod tests {
         assert!(button2_allocation.y < button0_allocation.y);
         assert_eq!(button3_allocation.x, button_dot_allocation.x);
         assert!(button3_allocation.y < button_dot_allocation.y);
+
+        assert_eq!(button_pm_allocation.x, button_equal_allocation.x);
+        assert!(button_pm_allocation.y < button_equal_allocation.y);
+        assert_eq!(button_equal_allocation.width,
+            button_dot_allocation.x + button_dot_allocation.width - button_pm_allocation.x);
+        assert_eq!(button_equal_allocation.x, display_allocation.x);
+        assert!(button_equal_allocation.y < display_allocation.y);
+        assert_eq!(button_equal_allocation.width, display_allocation.width);
     }
 }
@@ -134,15 +134,17 @@ fn inhibit_press_event(press_count: &Rc<Cell<i32>>, _relm: &Relm<Win>) -> Inhibi
 }
 
 fn main() {
-    Win::run(()).expect("Win::run failed");
+    Win::run().expect("Win::run failed");
 }
 
 #[cfg(test)]
 mod tests {
-    use gtk::EntryExt;
+    use gdk::ModifierType;
+    use gdk::keys::constants as key;
+    use gtk::{EntryExt, WidgetExt};
 
     use gtk_test::assert_text;
-    use relm_test::enter_keys;
+    use relm_test::{enter_keys, enter_text, focus, key_press_with_modifiers, key_release};
 
     use crate::Win;
 
@@ -169,4 +171,26 @@ mod tests {
         enter_keys(entry, "f");
         assert_text!(entry, "abcd");
     }
+
+    #[test]
+    fn synthesized_events() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+
+        focus(entry);
+        assert!(entry.has_focus());
+
+        enter_text(entry, "ab");
+        assert_text!(entry, "ab");
+
+        // Shift+Home selects the text before the cursor, which is replaced by the typed text.
+        key_press_with_modifiers(entry, key::Home, ModifierType::SHIFT_MASK);
+        key_release(entry, key::Home);
+        enter_text(entry, "c");
+        assert_text!(entry, "c");
+
+        // Four key presses were sent, so the entry now inhibits the key press events.
+        enter_text(entry, "d");
+        assert_text!(entry, "c");
+    }
 }
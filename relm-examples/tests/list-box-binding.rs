@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{ListBoxBinding, Relm, Widget};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+use self::RowMsg::*;
+
+#[derive(Msg)]
+pub enum RowMsg {
+    Delete,
+    Rename,
+}
+
+#[widget]
+impl Widget for Row {
+    fn model(_: &Relm<Self>, text: String) -> String {
+        text
+    }
+
+    fn update(&mut self, _event: RowMsg) {
+    }
+
+    view! {
+        gtk::Box {
+            gtk::Label {
+                widget_name: "label",
+                text: &self.model,
+            },
+            gtk::Button {
+                widget_name: "rename_button",
+                clicked => Rename,
+                label: "Rename",
+            },
+            gtk::Button {
+                widget_name: "delete_button",
+                clicked => Delete,
+                label: "Delete",
+            },
+        }
+    }
+}
+
+pub struct Model {
+    count: usize,
+    rows: ListBoxBinding<String, Row>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add,
+    Quit,
+    Remove(usize),
+    Update(usize),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.model.rows.bind(&self.widgets.list_box);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            count: 0,
+            rows: ListBoxBinding::new(relm.stream(), |index, msg| {
+                match msg {
+                    Delete => Some(Remove(index)),
+                    Rename => Some(Update(index)),
+                }
+            }),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Add => {
+                let item = format!("Item {}", self.model.count);
+                self.model.rows.push(item);
+                self.model.count += 1;
+            },
+            Quit => gtk::main_quit(),
+            Remove(index) => {
+                self.model.rows.remove(index);
+            },
+            Update(index) => {
+                let item = self.model.rows.items()[index].clone() + "!";
+                self.model.rows.update(index, item);
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="add_button"]
+                gtk::Button {
+                    clicked => Add,
+                    label: "Add",
+                },
+                #[name="list_box"]
+                gtk::ListBox {
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{Button, ContainerExt, Label, LabelExt, ListBoxExt};
+    use gtk::prelude::IsA;
+
+    use gtk_test::{assert_text, find_child_by_name};
+    use relm_test::click;
+
+    use crate::Win;
+
+    fn row_child<T: IsA<gtk::Widget>>(list_box: &gtk::ListBox, index: i32, name: &str) -> T {
+        let row = list_box.get_row_at_index(index).expect("row");
+        find_child_by_name(&row, name).expect("row child")
+    }
+
+    #[test]
+    fn list_box_binding() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let list_box = &widgets.list_box;
+
+        click(&widgets.add_button);
+        click(&widgets.add_button);
+        click(&widgets.add_button);
+        assert_eq!(list_box.get_children().len(), 3);
+        let label: Label = row_child(list_box, 1, "label");
+        assert_text!(label, "Item 1");
+
+        let delete_button: Button = row_child(list_box, 1, "delete_button");
+        click(&delete_button);
+        assert_eq!(list_box.get_children().len(), 2);
+        let label: Label = row_child(list_box, 1, "label");
+        assert_text!(label, "Item 2");
+
+        // The index sent by the row follows the removal of the previous rows.
+        let delete_button: Button = row_child(list_box, 0, "delete_button");
+        click(&delete_button);
+        let rename_button: Button = row_child(list_box, 0, "rename_button");
+        click(&rename_button);
+        assert_eq!(list_box.get_children().len(), 1);
+        let label: Label = row_child(list_box, 0, "label");
+        assert_text!(label, "Item 2!");
+    }
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    ScrolledWindowExt,
+    TextBufferExt,
+    TextViewExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    scroll: f64,
+    stick_to_end: bool,
+    user_scrolls: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Append(String),
+    Quit,
+    Scrolled(f64),
+    Top,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            scroll: 0.0,
+            stick_to_end: true,
+            user_scrolls: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Append(line) => {
+                if let Some(buffer) = self.widgets.log.get_buffer() {
+                    buffer.insert(&mut buffer.get_end_iter(), &(line + "\n"));
+                }
+                // Follow the new lines unless the user scrolled up.
+                if self.model.stick_to_end {
+                    relm::scroll_to_end(&self.widgets.scrolled_window);
+                }
+            },
+            Quit => gtk::main_quit(),
+            // Only sent when the user scrolls, not when the view scrolls to the end or to the top.
+            Scrolled(value) => {
+                self.model.scroll = value;
+                self.model.stick_to_end = relm::is_scrolled_to_end(&self.widgets.scrolled_window);
+                self.model.user_scrolls += 1;
+            },
+            Top => {
+                self.model.scroll = 0.0;
+                self.model.stick_to_end = false;
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="scrolled_window"]
+                gtk::ScrolledWindow {
+                    min_content_height: 100,
+                    vadjustment: {
+                        value: bind self.model.scroll,
+                        on_user_change: Scrolled,
+                    },
+                    #[name="log"]
+                    gtk::TextView {
+                        editable: false,
+                    },
+                },
+                #[name="top_button"]
+                gtk::Button {
+                    clicked => Top,
+                    label: "Top",
+                },
+                #[name="user_scrolls"]
+                gtk::Label {
+                    text: &self.model.user_scrolls.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{AdjustmentExt, LabelExt};
+    use gtk::Orientation::Vertical;
+    use gtk_test::{assert_text, run_loop};
+
+    use relm_test::click;
+
+    use crate::Msg::Append;
+    use crate::Win;
+
+    #[test]
+    fn stick_to_end() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let adjustment = relm::get_adjustment(&widgets.scrolled_window, Vertical).expect("adjustment");
+
+        for line in 0..100 {
+            component.emit(Append(format!("Line {}", line)));
+        }
+        run_loop();
+        assert!(relm::is_scrolled_to_end(&widgets.scrolled_window));
+        assert_text!(widgets.user_scrolls, 0);
+
+        click(&widgets.top_button);
+        assert_eq!(adjustment.get_value(), 0.0);
+        assert_text!(widgets.user_scrolls, 0);
+
+        // The view doesn't follow the new lines anymore after the user scrolled up.
+        adjustment.set_value(10.0);
+        run_loop();
+        assert_text!(widgets.user_scrolls, 1);
+        component.emit(Append("Last line".to_string()));
+        run_loop();
+        assert_eq!(adjustment.get_value(), 10.0);
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    CheckMenuItemExt,
+    GtkMenuItemExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    action: String,
+    large_font: bool,
+    wrap_lines: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    About,
+    LargeFont(bool),
+    Open,
+    Quit,
+    WrapLines(bool),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            action: String::new(),
+            large_font: false,
+            wrap_lines: true,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            About => self.model.action = "about".to_string(),
+            LargeFont(large_font) => self.model.large_font = large_font,
+            Open => self.model.action = "open".to_string(),
+            Quit => gtk::main_quit(),
+            WrapLines(wrap_lines) => self.model.wrap_lines = wrap_lines,
+        }
+    }
+
+    // The menus declared in a menu item are its submenu.
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::MenuBar {
+                    #[name="file_item"]
+                    gtk::MenuItem {
+                        label: "File",
+                        gtk::Menu {
+                            #[name="open_item"]
+                            gtk::MenuItem {
+                                label: "Open",
+                                activate => Open,
+                            },
+                            gtk::SeparatorMenuItem {
+                            },
+                            gtk::MenuItem {
+                                label: "Quit",
+                                activate => Quit,
+                            },
+                        },
+                    },
+                    gtk::MenuItem {
+                        label: "View",
+                        gtk::Menu {
+                            #[name="wrap_item"]
+                            gtk::CheckMenuItem {
+                                label: "Wrap lines",
+                                active: self.model.wrap_lines,
+                                toggled(item) => WrapLines(item.get_active()),
+                            },
+                            gtk::SeparatorMenuItem {
+                            },
+                            #[name="small_item"]
+                            gtk::RadioMenuItem {
+                                label: "Small font",
+                                active: !self.model.large_font,
+                            },
+                            #[name="large_item"]
+                            gtk::RadioMenuItem({ group: self.small_item }) {
+                                label: "Large font",
+                                active: self.model.large_font,
+                                toggled(item) => LargeFont(item.get_active()),
+                            },
+                        },
+                    },
+                    gtk::MenuItem {
+                        label: "Help",
+                        gtk::Menu {
+                            #[name="about_item"]
+                            gtk::MenuItem {
+                                label: "About",
+                                activate => About,
+                            },
+                        },
+                    },
+                },
+                #[name="action_label"]
+                gtk::Label {
+                    text: &self.model.action,
+                },
+                #[name="options_label"]
+                gtk::Label {
+                    text: &format_options(self.model.wrap_lines, self.model.large_font),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn format_options(wrap_lines: bool, large_font: bool) -> String {
+    format!("wrap: {}, large: {}", wrap_lines, large_font)
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{CheckMenuItemExt, GtkMenuItemExt, LabelExt, WidgetExt};
+
+    use gtk_test::assert_text;
+
+    use crate::Win;
+
+    #[test]
+    fn menubar() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let action_label = &widgets.action_label;
+        let options_label = &widgets.options_label;
+
+        let submenu = widgets.file_item.get_submenu().expect("file submenu");
+        assert_eq!(widgets.open_item.get_parent(), Some(submenu));
+
+        widgets.open_item.activate();
+        assert_text!(action_label, "open");
+        widgets.about_item.activate();
+        assert_text!(action_label, "about");
+
+        assert_text!(options_label, "wrap: true, large: false");
+        widgets.wrap_item.set_active(false);
+        assert_text!(options_label, "wrap: false, large: false");
+
+        widgets.large_item.set_active(true);
+        assert!(!widgets.small_item.get_active());
+        assert_text!(options_label, "wrap: false, large: true");
+    }
+}
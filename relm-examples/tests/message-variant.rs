@@ -0,0 +1,65 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::marker::PhantomData;
+
+use relm_derive::Msg;
+use relm::MessageVariant;
+
+// Doesn't implement Debug.
+struct Resolver;
+
+#[derive(Msg)]
+enum Msg {
+    Open(Resolver),
+    Save { path: String },
+    Quit,
+}
+
+#[derive(Msg)]
+enum GenericMsg<T> {
+    Value(T),
+    Nothing(PhantomData<T>),
+}
+
+#[test]
+fn variant_name() {
+    assert_eq!(Msg::Open(Resolver).variant_name(), "Open");
+    let save = Msg::Save { path: "file.txt".to_string() };
+    assert_eq!(save.variant_name(), "Save");
+    if let Msg::Save { path } = save {
+        assert_eq!(path, "file.txt");
+    }
+    assert_eq!(Msg::Quit.variant_name(), "Quit");
+    assert_eq!(GenericMsg::Value(Resolver).variant_name(), "Value");
+}
+
+#[test]
+fn variant_index() {
+    assert_eq!(Msg::Open(Resolver).variant_index(), 0);
+    assert_eq!(Msg::Save { path: String::new() }.variant_index(), 1);
+    assert_eq!(Msg::Quit.variant_index(), 2);
+    assert_eq!(Msg::variant_count(), 3);
+
+    assert_eq!(GenericMsg::Nothing::<Resolver>(PhantomData).variant_index(), 1);
+    assert_eq!(GenericMsg::<Resolver>::variant_count(), 2);
+}
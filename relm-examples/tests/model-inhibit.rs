@@ -0,0 +1,116 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    locked: bool,
+    presses: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    KeyPressed,
+    Quit,
+    ToggleLock,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            locked: false,
+            presses: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            KeyPressed => self.model.presses += 1,
+            Quit => gtk::main_quit(),
+            ToggleLock => self.model.locked = !self.model.locked,
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    // The entry doesn't get the keys while it is locked.
+                    key_press_event(_, _) => (KeyPressed, Inhibit(self.model.locked)),
+                },
+                #[name="lock_button"]
+                gtk::Button {
+                    clicked => ToggleLock,
+                    label: "Lock",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.presses.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{EntryExt, LabelExt};
+
+    use gtk_test::assert_text;
+    use relm_test::{click, enter_keys};
+
+    use crate::Win;
+
+    #[test]
+    fn model_inhibit() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+
+        enter_keys(entry, "ab");
+        assert_text!(entry, "ab");
+        assert_text!(widgets.label, 2);
+
+        click(&widgets.lock_button);
+        enter_keys(entry, "cd");
+        assert_text!(entry, "ab");
+        assert_text!(widgets.label, 4);
+
+        click(&widgets.lock_button);
+        enter_keys(entry, "e");
+        assert_text!(entry, "abe");
+    }
+}
@@ -0,0 +1,141 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    GtkWindowExt,
+    Inhibit,
+    LabelExt,
+    WidgetExt,
+};
+use relm::{Relm, Widget};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct AppConfig {
+    pub port: u16,
+    pub title: String,
+}
+
+pub struct Model {
+    address: String,
+    title: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Increment,
+}
+
+#[widget]
+impl Widget for Win {
+    // The parameter can be destructured like in any function.
+    fn model(_relm: &Relm<Self>, AppConfig { port, title }: AppConfig) -> Model {
+        Model {
+            address: format!("localhost:{}", port),
+            title,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            title: &self.model.title,
+            #[name="label"]
+            gtk::Label {
+                text: &self.model.address,
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[widget]
+impl Widget for Counter {
+    // Multiple parameters are grouped in a tuple: the model parameter type is (&'static str, u32).
+    fn model(prefix: &'static str, count: u32) -> (&'static str, u32) {
+        (prefix, count)
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            CounterMsg::Increment => self.model.1 += 1,
+        }
+    }
+
+    fn text(prefix: &str, count: u32) -> String {
+        format!("{}{}", prefix, count)
+    }
+
+    view! {
+        #[name="label"]
+        gtk::Label {
+            text: &Self::text(self.model.0, self.model.1),
+        }
+    }
+}
+
+fn main() {
+    Win::run_with(AppConfig {
+        port: 8080,
+        title: "Server".to_string(),
+    }).expect("Win::run_with failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{GtkWindowExt, LabelExt};
+
+    use gtk_test::assert_text;
+
+    use crate::{AppConfig, Counter, CounterMsg, Win};
+
+    #[test]
+    fn model_param_struct() {
+        let (component, _, widgets) = relm::init_test::<Win>(AppConfig {
+            port: 8080,
+            title: "Server".to_string(),
+        }).expect("init_test failed");
+
+        assert_text!(widgets.label, "localhost:8080");
+        assert_eq!(component.widget().get_title().expect("title"), "Server");
+    }
+
+    #[test]
+    fn multiple_model_params() {
+        let (component, _, widgets) = relm::init_test::<Counter>(("Count: ", 3)).expect("init_test failed");
+
+        assert_text!(widgets.label, "Count: 3");
+        component.emit(CounterMsg::Increment);
+        assert_text!(widgets.label, "Count: 4");
+    }
+}
@@ -115,7 +115,7 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run(42).expect("Win::run failed");
+    Win::run_with(42).expect("Win::run_with failed");
 }
 
 #[cfg(test)]
@@ -148,7 +148,7 @@ impl WidgetTest for Win {
 }
 
 fn main() {
-    Win::run(42).expect("Win::run failed");
+    Win::run_with(42).expect("Win::run_with failed");
 }
 
 #[cfg(test)]
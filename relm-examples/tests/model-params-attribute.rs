@@ -92,7 +92,7 @@ impl Widget for Win {
 }
 
 fn main() {
-    Win::run((40, 2)).expect("Win::run failed");
+    Win::run_with((40, 2)).expect("Win::run_with failed");
 }
 
 #[cfg(test)]
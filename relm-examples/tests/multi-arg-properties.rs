@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    GtkWindowExt,
+    Inhibit,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    margin: i32,
+    width: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Grow,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            margin: 8,
+            width: 100,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Grow => {
+                self.model.margin *= 2;
+                self.model.width += 50;
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        #[name="window"]
+        gtk::Window {
+            default_size: (400, 300),
+            gtk::Box {
+                orientation: Vertical,
+                #[name="button"]
+                gtk::Button {
+                    clicked => Grow,
+                    label: "Grow",
+                    margin: self.model.margin,
+                    size_request: (self.model.width, 30),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{GtkWindowExt, WidgetExt};
+
+    use relm_test::click;
+
+    use crate::Win;
+
+    fn margins(widget: &gtk::Button) -> [i32; 4] {
+        [widget.get_margin_top(), widget.get_margin_bottom(), widget.get_margin_start(), widget.get_margin_end()]
+    }
+
+    #[test]
+    fn multi_arg_properties() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let button = &widgets.button;
+
+        assert_eq!(widgets.window.get_default_size(), (400, 300));
+        assert_eq!(button.get_size_request(), (100, 30));
+        assert_eq!(margins(button), [8; 4]);
+
+        click(button);
+        assert_eq!(button.get_size_request(), (150, 30));
+        assert_eq!(margins(button), [16; 4]);
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    NotebookExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::prelude::NotebookExtManual;
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum TabMsg {
+}
+
+#[widget]
+impl Widget for TabLabel {
+    fn model(_: &relm::Relm<Self>, text: String) -> String {
+        text
+    }
+
+    fn update(&mut self, event: TabMsg) {
+        match event {
+        }
+    }
+
+    view! {
+        gtk::Label {
+            text: &("[".to_string() + &self.model + "]"),
+        }
+    }
+}
+
+pub struct Model {
+    page: u32,
+    unread: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    NewMessage,
+    NextPage,
+    PageSwitched(u32),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            page: 0,
+            unread: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            NewMessage => self.model.unread += 1,
+            NextPage => self.model.page = (self.model.page + 1) % 3,
+            PageSwitched(page) => self.model.page = page,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="notebook"]
+                gtk::Notebook {
+                    current_page: Some(self.model.page),
+                    switch_page(_, _, page) => PageSwitched(page),
+                    #[name="general_page"]
+                    gtk::Label {
+                        tab: gtk::Label {
+                            text: "General",
+                        },
+                        text: "General settings",
+                    },
+                    #[name="inbox_page"]
+                    gtk::Label {
+                        tab: #[name="inbox_tab"] gtk::Label {
+                            text: &format_unread(self.model.unread),
+                        },
+                        text: "Messages",
+                    },
+                    #[name="advanced_page"]
+                    gtk::Label {
+                        tab: TabLabel("Advanced".to_string()),
+                        text: "Advanced settings",
+                    },
+                },
+                #[name="new_message_button"]
+                gtk::Button {
+                    clicked => NewMessage,
+                    label: "New message",
+                },
+                #[name="next_button"]
+                gtk::Button {
+                    clicked => NextPage,
+                    label: "Next page",
+                },
+                #[name="page_label"]
+                gtk::Label {
+                    text: &self.model.page.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn format_unread(unread: u32) -> String {
+    format!("Inbox ({})", unread)
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{LabelExt, NotebookExt};
+    use gtk::prelude::NotebookExtManual;
+
+    use gtk_test::assert_text;
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn notebook_tabs() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let notebook = &widgets.notebook;
+        let page_label = &widgets.page_label;
+
+        assert_eq!(notebook.get_tab_label_text(&widgets.general_page).expect("general tab"), "General");
+        assert_eq!(notebook.get_tab_label_text(&widgets.inbox_page).expect("inbox tab"), "Inbox (0)");
+        assert_eq!(notebook.get_tab_label_text(&widgets.advanced_page).expect("advanced tab"), "[Advanced]");
+
+        click(&widgets.new_message_button);
+        assert_text!(widgets.inbox_tab, "Inbox (1)");
+
+        assert_eq!(notebook.get_current_page(), Some(0));
+        click(&widgets.next_button);
+        assert_eq!(notebook.get_current_page(), Some(1));
+        assert_text!(page_label, 1);
+
+        // Like a click on a tab.
+        notebook.set_current_page(Some(2));
+        assert_text!(page_label, 2);
+        click(&widgets.next_button);
+        assert_eq!(notebook.get_current_page(), Some(0));
+        assert_text!(page_label, 0);
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    PanedExt,
+    SwitchExt,
+    WidgetExt,
+};
+use gtk::Orientation::{Horizontal, Vertical};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    active: bool,
+    position: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    PaneMoved(i32),
+    Quit,
+    Toggled(bool),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            active: false,
+            position: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            PaneMoved(position) => self.model.position = position,
+            Quit => gtk::main_quit(),
+            Toggled(active) => self.model.active = active,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="paned"]
+                gtk::Paned {
+                    orientation: Horizontal,
+                    notify(position, paned) => PaneMoved(paned.get_position()),
+                    gtk::Label {
+                        text: "Left",
+                    },
+                    gtk::Label {
+                        text: "Right",
+                    },
+                },
+                #[name="switch"]
+                gtk::Switch {
+                    property_active_notify(switch) => Toggled(switch.get_active()),
+                },
+                #[name="position_label"]
+                gtk::Label {
+                    text: &self.model.position.to_string(),
+                },
+                #[name="active_label"]
+                gtk::Label {
+                    text: &self.model.active.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{LabelExt, PanedExt, SwitchExt};
+
+    use gtk_test::{assert_text, run_loop};
+
+    use crate::Win;
+
+    #[test]
+    fn notify() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        widgets.paned.set_position(42);
+        run_loop();
+        assert_text!(widgets.position_label, 42);
+
+        widgets.switch.set_active(true);
+        run_loop();
+        assert_text!(widgets.active_label, true);
+    }
+}
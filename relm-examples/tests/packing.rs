@@ -0,0 +1,132 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    GtkWindowExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Horizontal;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum StatusMsg {
+}
+
+#[widget]
+impl Widget for Status {
+    fn model() {
+    }
+
+    fn update(&mut self, _event: StatusMsg) {
+    }
+
+    view! {
+        gtk::Label {
+            text: "Ready",
+        }
+    }
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            titlebar: view! {
+                #[name="header_bar"]
+                gtk::HeaderBar {
+                    #[name="open_button"]
+                    gtk::Button {
+                        label: "Open",
+                    },
+                    #[name="menu_button"]
+                    gtk::Button {
+                        label: "Menu",
+                        packing: end,
+                    },
+                }
+            },
+            #[name="toolbar"]
+            gtk::Box {
+                orientation: Horizontal,
+                #[name="title"]
+                gtk::Label {
+                    text: "Title",
+                    packing: start,
+                },
+                #[name="close_button"]
+                gtk::Button {
+                    label: "Close",
+                    packing: end,
+                    child: {
+                        "padding": 5,
+                    },
+                },
+                #[name="status"]
+                Status {
+                    packing: end,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{BoxExt, HeaderBarExt, PackType};
+
+    use crate::Win;
+
+    #[test]
+    fn packing() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let toolbar = &widgets.toolbar;
+        let header_bar = &widgets.header_bar;
+
+        assert_eq!(toolbar.query_child_packing(&widgets.title), (false, true, 0, PackType::Start));
+        assert_eq!(toolbar.query_child_packing(&widgets.close_button), (false, true, 5, PackType::End));
+        assert_eq!(toolbar.get_child_pack_type(&widgets.status), PackType::End);
+
+        assert_eq!(header_bar.get_child_pack_type(&widgets.open_button), PackType::Start);
+        assert_eq!(header_bar.get_child_pack_type(&widgets.menu_button), PackType::End);
+    }
+}
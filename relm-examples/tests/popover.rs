@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    PopoverExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    menu_open: bool,
+    selected: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    MenuClosed,
+    Quit,
+    Select(&'static str),
+    ToggleMenu,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            menu_open: false,
+            selected: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            // The popover was closed by GTK, e.g. with Escape or a click outside of it.
+            MenuClosed => self.model.menu_open = false,
+            Quit => gtk::main_quit(),
+            Select(item) => {
+                self.model.selected = item.to_string();
+                self.model.menu_open = false;
+            },
+            ToggleMenu => self.model.menu_open = !self.model.menu_open,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="menu_button"]
+                gtk::Button {
+                    clicked => ToggleMenu,
+                    label: "Menu",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.selected,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+
+        // The popover is not a child of the window: it is shown next to the button.
+        #[name="menu"]
+        gtk::Popover {
+            relative_to: Some(&self.menu_button),
+            visible: self.model.menu_open,
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Button {
+                    clicked => Select("Open"),
+                    label: "Open",
+                },
+                #[name="save_item"]
+                gtk::Button {
+                    clicked => Select("Save"),
+                    label: "Save",
+                },
+            },
+            closed => MenuClosed,
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{LabelExt, WidgetExt};
+
+    use gtk_test::{assert_text, click};
+
+    use crate::Win;
+
+    #[test]
+    fn popover() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let menu = &widgets.menu;
+
+        assert!(!menu.get_visible());
+        click(&widgets.menu_button);
+        assert!(menu.get_visible());
+
+        click(&widgets.save_item);
+        assert!(!menu.get_visible());
+        assert_text!(widgets.label, "Save");
+
+        click(&widgets.menu_button);
+        assert!(menu.get_visible());
+        // Hiding the popover like GTK does when it is dismissed emits the closed signal.
+        menu.hide();
+        click(&widgets.menu_button);
+        assert!(menu.get_visible());
+    }
+}
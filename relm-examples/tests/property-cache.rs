@@ -0,0 +1,154 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+static CACHED_CALLS: AtomicUsize = AtomicUsize::new(0);
+static UNCACHED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+// Setters counting how many times they are called.
+trait CountedLabelExt {
+    fn set_cached_text(&self, text: &str);
+    fn set_uncached_text(&self, text: &str);
+}
+
+impl CountedLabelExt for gtk::Label {
+    fn set_cached_text(&self, text: &str) {
+        CACHED_CALLS.fetch_add(1, Ordering::SeqCst);
+        self.set_text(text);
+    }
+
+    fn set_uncached_text(&self, text: &str) {
+        UNCACHED_CALLS.fetch_add(1, Ordering::SeqCst);
+        self.set_text(text);
+    }
+}
+
+pub struct Model {
+    count: u32,
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Increment,
+    Quit,
+    SetText(String),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            count: 0,
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Increment => self.model.count += 1,
+            Quit => gtk::main_quit(),
+            SetText(text) => self.model.text = text,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="cached_label"]
+                gtk::Label {
+                    cached_text: &self.model.text,
+                },
+                #[name="uncached_label"]
+                gtk::Label {
+                    #[uncached]
+                    uncached_text: &self.model.text,
+                },
+                #[name="count_label"]
+                gtk::Label {
+                    text: &self.model.count.to_string(),
+                    tooltip_text: Some(&self.model.text),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use gtk::{LabelExt, WidgetExt};
+
+    use gtk_test::{assert_text, run_loop};
+
+    use crate::Msg::{Increment, SetText};
+    use crate::{CACHED_CALLS, UNCACHED_CALLS, Win};
+
+    fn calls() -> (usize, usize) {
+        (CACHED_CALLS.load(Ordering::SeqCst), UNCACHED_CALLS.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn unchanged_properties_are_not_set() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        assert_eq!(calls(), (1, 1));
+
+        component.emit(SetText("text".to_string()));
+        run_loop();
+        assert_text!(widgets.cached_label, "text");
+        assert_eq!(calls(), (2, 2));
+
+        // The cached setter is not called again for the same value, unlike the uncached one.
+        component.emit(SetText("text".to_string()));
+        run_loop();
+        assert_text!(widgets.cached_label, "text");
+        assert_text!(widgets.uncached_label, "text");
+        assert_eq!(calls(), (2, 3));
+
+        component.emit(SetText("other".to_string()));
+        run_loop();
+        assert_text!(widgets.cached_label, "other");
+        assert_eq!(calls(), (3, 4));
+
+        // The properties whose value is a temporary or needs a coercion are still updated.
+        component.emit(Increment);
+        run_loop();
+        assert_text!(widgets.count_label, "1");
+        assert_eq!(widgets.count_label.get_tooltip_text().map(|text| text.to_string()), Some("other".to_string()));
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use glib::MainContext;
+use relm_derive::Msg;
+use relm::EventStream;
+use relm::recorder::{self, ReplaySpeed};
+use serde::{Deserialize, Serialize};
+
+// Doesn't implement Serialize.
+#[derive(Debug, PartialEq)]
+struct Resolver;
+
+#[derive(Debug, Deserialize, Msg, PartialEq, Serialize)]
+enum Msg {
+    Increment(i32),
+    #[msg(skip_record)]
+    #[serde(skip)]
+    Resolve(Resolver),
+    Reset,
+}
+
+// The sources can only be added from the thread owning the default context, so the tests,
+// which run in parallel, own it while they run.
+struct ContextGuard(MainContext);
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+fn acquire_default_context() -> ContextGuard {
+    let context = MainContext::default();
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    ContextGuard(context)
+}
+
+fn run_for(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        MainContext::default().iteration(false);
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn collect_messages(stream: &EventStream<Msg>) -> Rc<RefCell<Vec<Msg>>> {
+    let messages = Rc::new(RefCell::new(vec![]));
+    let collector = messages.clone();
+    stream.set_callback(move |msg| collector.borrow_mut().push(msg));
+    messages
+}
+
+fn record() -> Vec<u8> {
+    let stream = EventStream::new_detached();
+    let recorder = recorder::attach(&stream.stream(), vec![]);
+    stream.emit(Msg::Increment(1));
+    stream.emit(Msg::Resolve(Resolver));
+    thread::sleep(Duration::from_millis(50));
+    stream.emit(Msg::Reset);
+    recorder.finish().expect("finish")
+}
+
+#[test]
+fn record_skips_variants() {
+    let recording = String::from_utf8(record()).expect("utf-8");
+    let lines: Vec<_> = recording.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with(r#"{"Increment":1}]"#));
+    assert!(lines[1].ends_with(r#""Reset"]"#));
+}
+
+#[test]
+fn replay_instant() {
+    let recording = record();
+    let stream = EventStream::new_detached();
+    let messages = collect_messages(&stream);
+    let replay = recorder::replay(&stream.stream(), &recording[..], ReplaySpeed::Instant).expect("replay");
+    assert!(replay.is_finished());
+    let _ = stream.dispatch_pending();
+    assert_eq!(*messages.borrow(), vec![Msg::Increment(1), Msg::Reset]);
+}
+
+#[test]
+fn replay_recorded_timing() {
+    let _guard = acquire_default_context();
+    let recording = record();
+    let stream = EventStream::new();
+    let messages = collect_messages(&stream);
+    let replay = recorder::replay(&stream.stream(), &recording[..], ReplaySpeed::Recorded(1.0)).expect("replay");
+    run_for(Duration::from_millis(20));
+    assert_eq!(*messages.borrow(), vec![Msg::Increment(1)]);
+    assert!(!replay.is_finished());
+
+    run_for(Duration::from_millis(100));
+    assert_eq!(*messages.borrow(), vec![Msg::Increment(1), Msg::Reset]);
+    assert!(replay.is_finished());
+}
+
+#[test]
+fn replay_cancel() {
+    let _guard = acquire_default_context();
+    let recording = record();
+    let stream = EventStream::new();
+    let messages = collect_messages(&stream);
+    let replay = recorder::replay(&stream.stream(), &recording[..], ReplaySpeed::Recorded(1.0)).expect("replay");
+    run_for(Duration::from_millis(20));
+    replay.cancel();
+    run_for(Duration::from_millis(100));
+    assert_eq!(*messages.borrow(), vec![Msg::Increment(1)]);
+}
+
+#[test]
+fn replay_invalid_recording() {
+    let stream = EventStream::<Msg>::new_detached();
+    let result = recorder::replay(&stream.stream(), &b"[0, {\"Unknown\": 1}]\n"[..], ReplaySpeed::Instant);
+    assert!(result.is_err());
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use glib::MainContext;
+use relm_derive::Msg;
+use relm::{Channel, EventStream, Relm, RelmHandle, Update, UpdateNew, execute, oneshot_with_context, progress_channel};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+enum Msg {
+    Load,
+    Loaded(u32),
+    Progress(u32),
+}
+
+// A helper that doesn't depend on the component type.
+struct Loader {
+    handle: RelmHandle<Msg>,
+}
+
+impl Loader {
+    fn load(&self) {
+        let sender = progress_channel(&self.handle, Progress, Loaded)
+            .with_cancellation(self.handle.cancellation());
+        thread::spawn(move || {
+            sender.progress(50).expect("progress");
+            sender.finish(100).expect("finish");
+        });
+    }
+}
+
+struct Model {
+    loader: Loader,
+    received: Rc<RefCell<Vec<u32>>>,
+}
+
+struct Component {
+    model: Model,
+}
+
+impl Update for Component {
+    type Model = Model;
+    type ModelParam = (Rc<RefCell<Vec<u32>>>, Rc<RefCell<Option<RelmHandle<Msg>>>>);
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, (received, handle): Self::ModelParam) -> Model {
+        *handle.borrow_mut() = Some(relm.handle());
+        Model {
+            loader: Loader {
+                handle: relm.handle(),
+            },
+            received,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Load => self.model.loader.load(),
+            Loaded(value) | Progress(value) => self.model.received.borrow_mut().push(value),
+        }
+    }
+}
+
+impl UpdateNew for Component {
+    fn new(_relm: &Relm<Self>, model: Model) -> Self {
+        Component {
+            model,
+        }
+    }
+}
+
+#[test]
+fn relm_handle() {
+    let received = Rc::new(RefCell::new(vec![]));
+    let handle = Rc::new(RefCell::new(None));
+    let stream = execute::<Component>((received.clone(), handle.clone()));
+    let handle = handle.borrow_mut().take().expect("handle");
+    assert_eq!(handle.context(), &MainContext::default());
+
+    handle.stream().emit(Load);
+    let context = MainContext::default();
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    while received.borrow().len() < 2 {
+        context.iteration(true);
+    }
+    context.release();
+    assert_eq!(*received.borrow(), vec![50, 100]);
+
+    drop(stream);
+    assert!(handle.cancellation().is_cancelled());
+    assert!(!handle.clone().stream().is_alive());
+}
+
+#[test]
+fn relm_handle_context() {
+    let context = MainContext::new();
+    let stream = EventStream::<Msg>::with_context(&context);
+    let relm = Relm::<Component>::new(&stream);
+    assert_eq!(relm.handle().context(), &context);
+    assert_ne!(relm.handle().context(), &MainContext::default());
+}
+
+#[test]
+fn relm_handle_channel_helpers() {
+    let context = MainContext::new();
+    let stream = EventStream::<Msg>::with_context(&context);
+    let handle = Relm::<Component>::new(&stream).handle();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &Msg| {
+            if let Loaded(value) = *msg {
+                received.borrow_mut().push(value);
+            }
+        });
+    }
+
+    // The messages are received from the context of the handle.
+    let (_channel, sender) = Channel::forward_to_stream(&handle, Loaded);
+    sender.send(1).expect("send");
+    let (oneshot_sender, receiver) = oneshot_with_context(handle.context());
+    receiver.on_receive(&handle, Loaded);
+    oneshot_sender.send(2).expect("send");
+    assert!(context.acquire());
+    while received.borrow().len() < 2 {
+        context.iteration(true);
+    }
+    context.release();
+    // The sources have the same priority, so they can be dispatched in any order.
+    received.borrow_mut().sort();
+    assert_eq!(*received.borrow(), vec![1, 2]);
+}
@@ -0,0 +1,147 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    ButtonExt,
+    GtkWindowExt,
+    HeaderBarExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    RevealerExt,
+    SearchBarExt,
+    ToggleButtonExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use gtk::RevealerTransitionType::SlideDown;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    searching: bool,
+    show_details: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    Search(bool),
+    ShowDetails(bool),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            searching: false,
+            show_details: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            // Also sent when the search bar is closed by the user, e.g. with Escape.
+            Search(searching) => self.model.searching = searching,
+            ShowDetails(show_details) => self.model.show_details = show_details,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            titlebar: view! {
+                #[name="header_bar"]
+                gtk::HeaderBar {
+                    show_close_button: true,
+                    title: Some("Search"),
+                    #[name="search_button"]
+                    gtk::ToggleButton {
+                        active: bind self.model.searching => Search,
+                        label: "Search",
+                    },
+                    #[name="details_button"]
+                    gtk::ToggleButton {
+                        active: bind self.model.show_details => ShowDetails,
+                        label: "Details",
+                    },
+                }
+            },
+            gtk::Box {
+                orientation: Vertical,
+                #[name="search_bar"]
+                gtk::SearchBar {
+                    search_mode_enabled: bind self.model.searching => Search,
+                    show_close_button: true,
+                    gtk::SearchEntry {
+                    },
+                },
+                #[name="revealer"]
+                gtk::Revealer {
+                    reveal_child: bind self.model.show_details => ShowDetails,
+                    transition_duration: 200,
+                    transition_type: SlideDown,
+                    gtk::Label {
+                        text: "Details",
+                    },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{HeaderBarExt, RevealerExt, SearchBarExt, ToggleButtonExt};
+    use gtk_test::run_loop;
+
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn search_bar() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        assert_eq!(widgets.header_bar.get_title().expect("title"), "Search");
+        assert!(!widgets.search_bar.get_search_mode());
+        click(&widgets.search_button);
+        assert!(widgets.search_bar.get_search_mode());
+
+        // Closing the search bar, like with Escape, releases the toggle button.
+        widgets.search_bar.set_search_mode(false);
+        run_loop();
+        assert!(!widgets.search_button.get_active());
+
+        click(&widgets.details_button);
+        assert!(widgets.revealer.get_reveal_child());
+        click(&widgets.details_button);
+        assert!(!widgets.revealer.get_reveal_child());
+    }
+}
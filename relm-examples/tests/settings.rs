@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gio::SettingsExt;
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget, settings};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+const WIDTH_KEY: &str = "window-width";
+
+pub struct Model {
+    received: u32,
+    settings: gio::Settings,
+    width: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    SetWidth(i32),
+    WidthChanged(i32),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, settings: gio::Settings) -> Model {
+        settings::bind(&settings, WIDTH_KEY, relm.stream(),
+            |value| WidthChanged(value.get::<i32>().unwrap_or_default()));
+        Model {
+            received: 0,
+            width: settings.get_int(WIDTH_KEY),
+            settings,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            SetWidth(width) => {
+                self.model.width = width;
+                settings::set_from_model(&self.model.settings, WIDTH_KEY, &width)
+                    .expect("set window-width");
+            },
+            WidthChanged(width) => {
+                self.model.received += 1;
+                self.model.width = width;
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="width_label"]
+                gtk::Label {
+                    text: &self.model.width.to_string(),
+                },
+                #[name="received_label"]
+                gtk::Label {
+                    text: &self.model.received.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::process::Command;
+
+    use gio::SettingsExt;
+    use glib::ToVariant;
+    use gtk::LabelExt;
+
+    use gtk_test::{assert_text, run_loop};
+
+    use crate::Msg::SetWidth;
+    use crate::{WIDTH_KEY, Win};
+
+    const SCHEMA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<schemalist>
+  <schema id="org.relm.test.settings" path="/org/relm/test/settings/">
+    <key name="window-width" type="i">
+      <default>300</default>
+    </key>
+  </schema>
+</schemalist>"#;
+
+    fn settings() -> gio::Settings {
+        let directory = std::env::temp_dir().join("relm-test-settings");
+        fs::create_dir_all(&directory).expect("create schema directory");
+        fs::write(directory.join("org.relm.test.settings.gschema.xml"), SCHEMA).expect("write schema");
+        let status = Command::new("glib-compile-schemas")
+            .arg(&directory)
+            .status()
+            .expect("glib-compile-schemas");
+        assert!(status.success());
+
+        let source = gio::SettingsSchemaSource::from_directory(&directory, None, false)
+            .expect("schema source");
+        let schema = source.lookup("org.relm.test.settings", false).expect("schema");
+        let backend = gio::memory_settings_backend_new().expect("memory backend");
+        gio::Settings::new_full(&schema, Some(&backend), None)
+    }
+
+    #[test]
+    fn settings_binding() {
+        let settings = settings();
+        let (component, _, widgets) = relm::init_test::<Win>(settings.clone()).expect("init_test failed");
+        let width_label = &widgets.width_label;
+        let received_label = &widgets.received_label;
+
+        assert_text!(width_label, 300);
+        assert_text!(received_label, 0);
+
+        settings.set_value(WIDTH_KEY, &640.to_variant()).expect("set window-width");
+        run_loop();
+        assert_text!(width_label, 640);
+        assert_text!(received_label, 1);
+
+        // The value written from the model is not sent back as a message.
+        component.emit(SetWidth(800));
+        run_loop();
+        assert_eq!(settings.get_int(WIDTH_KEY), 800);
+        assert_text!(width_label, 800);
+        assert_text!(received_label, 1);
+
+        settings.set_value(WIDTH_KEY, &1024.to_variant()).expect("set window-width");
+        run_loop();
+        assert_text!(width_label, 1024);
+        assert_text!(received_label, 2);
+    }
+}
@@ -0,0 +1,348 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::process::Command;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gio::FileMonitorEvent;
+use glib::{IOCondition, MainContext, Pid};
+use relm::{
+    EventStream,
+    child_watch,
+    debounce,
+    file_monitor,
+    interval_cancellable,
+    io_watch,
+    throttle,
+    timeout_cancellable,
+    unix_signal,
+};
+
+// The sources can only be added from the thread owning the default context, so the tests,
+// which run in parallel, own it while they run.
+struct ContextGuard(MainContext);
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+fn acquire_default_context() -> ContextGuard {
+    let context = MainContext::default();
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    ContextGuard(context)
+}
+
+// Run the default main loop for `duration`.
+fn run_for(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        MainContext::default().iteration(false);
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn count_messages<MSG>(stream: &EventStream<MSG>) -> Rc<Cell<usize>> {
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    stream.observe(move |_| counter.set(counter.get() + 1));
+    count
+}
+
+#[test]
+fn cancel_timeout() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let count = count_messages(&stream);
+    let handle = timeout_cancellable(&stream.stream(), 10, || ());
+    handle.cancel();
+    run_for(Duration::from_millis(50));
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn cancel_interval() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let count = count_messages(&stream);
+    let handle = interval_cancellable(&stream.stream(), 5, || ());
+    run_for(Duration::from_millis(50));
+    assert!(count.get() > 0);
+
+    drop(handle);
+    let emitted = count.get();
+    run_for(Duration::from_millis(50));
+    assert_eq!(count.get(), emitted);
+}
+
+#[test]
+fn interval_dropped_stream() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::<()>::new();
+    let handle = interval_cancellable(&stream.stream(), 5, || ());
+    drop(stream);
+    run_for(Duration::from_millis(50));
+    assert!(handle.is_cancelled());
+}
+
+#[test]
+fn emit_idle() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |msg: &i32| received.borrow_mut().push(*msg));
+    }
+    let handle = stream.stream();
+    handle.emit_idle(1).forget();
+    let cancelled = handle.emit_idle(2);
+    handle.emit_timeout(3, 20).forget();
+    drop(handle.emit_timeout(4, 5));
+    cancelled.cancel();
+    assert!(received.borrow().is_empty());
+
+    run_for(Duration::from_millis(50));
+    assert_eq!(*received.borrow(), vec![1, 3]);
+}
+
+#[test]
+fn emit_idle_dropped_stream() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::<()>::new();
+    let idle = stream.stream().emit_idle(());
+    let timeout = stream.stream().emit_timeout((), 5);
+    drop(stream);
+    run_for(Duration::from_millis(50));
+    assert!(idle.is_cancelled());
+    assert!(timeout.is_cancelled());
+}
+
+#[test]
+fn watch_fd() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let conditions = Rc::new(RefCell::new(vec![]));
+    {
+        let conditions = conditions.clone();
+        stream.observe(move |condition: &IOCondition| conditions.borrow_mut().push(*condition));
+    }
+
+    let (mut writer, reader) = UnixStream::pair().expect("socket pair");
+    let handle = io_watch(reader.as_raw_fd(), IOCondition::IN, &stream.stream(), |condition| condition);
+    run_for(Duration::from_millis(20));
+    assert!(conditions.borrow().is_empty());
+
+    writer.write_all(b"message").expect("write");
+    run_for(Duration::from_millis(20));
+    assert!(!conditions.borrow().is_empty());
+    assert!(conditions.borrow().iter().all(|condition| condition.contains(IOCondition::IN)));
+
+    drop(handle);
+    conditions.borrow_mut().clear();
+    run_for(Duration::from_millis(20));
+    assert!(conditions.borrow().is_empty());
+}
+
+#[test]
+fn signal() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let count = count_messages(&stream);
+    let _handle = unix_signal(libc::SIGUSR1, &stream.stream(), ());
+    unsafe {
+        libc::raise(libc::SIGUSR1);
+    }
+    run_for(Duration::from_millis(20));
+    assert_eq!(count.get(), 1);
+}
+
+// The child is reaped by glib, not by wait().
+#[allow(clippy::zombie_processes)]
+#[test]
+fn watch_child() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let statuses = Rc::new(RefCell::new(vec![]));
+    {
+        let statuses = statuses.clone();
+        stream.observe(move |status: &i32| statuses.borrow_mut().push(*status));
+    }
+
+    let child = Command::new("sh").args(["-c", "exit 3"]).spawn().expect("spawn");
+    let handle = child_watch(Pid(child.id() as i32), &stream.stream(), |status| libc::WEXITSTATUS(status));
+    run_for(Duration::from_millis(200));
+    assert_eq!(*statuses.borrow(), vec![3]);
+    assert!(handle.is_cancelled());
+}
+
+#[test]
+fn monitor_file() {
+    let _guard = acquire_default_context();
+    let path = std::env::temp_dir().join("relm-test-file-monitor");
+    fs::write(&path, "first").expect("write");
+
+    let stream = EventStream::new();
+    let events = Rc::new(RefCell::new(vec![]));
+    {
+        let events = events.clone();
+        stream.observe(move |event: &FileMonitorEvent| events.borrow_mut().push(*event));
+    }
+
+    let file = gio::File::new_for_path(&path);
+    let handle = file_monitor(&file, 1000, &stream.stream(), |event, _other_file| event)
+        .expect("file monitor");
+    run_for(Duration::from_millis(50));
+    assert!(events.borrow().is_empty());
+
+    // The burst of writes only produces one Changed event.
+    for _ in 0..10 {
+        fs::write(&path, "second").expect("write");
+    }
+    run_for(Duration::from_millis(200));
+    let changed = events.borrow().iter().filter(|event| **event == FileMonitorEvent::Changed).count();
+    assert_eq!(changed, 1);
+
+    drop(handle);
+    events.borrow_mut().clear();
+    fs::write(&path, "third").expect("write");
+    run_for(Duration::from_millis(200));
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn file_monitor_dropped_stream() {
+    let _guard = acquire_default_context();
+    let path = std::env::temp_dir().join("relm-test-file-monitor-dropped");
+    fs::write(&path, "first").expect("write");
+
+    let stream = EventStream::<()>::new();
+    let file = gio::File::new_for_path(&path);
+    let handle = file_monitor(&file, 0, &stream.stream(), |_, _| ()).expect("file monitor");
+    drop(stream);
+    fs::write(&path, "second").expect("write");
+    run_for(Duration::from_millis(200));
+    assert!(handle.is_cancelled());
+}
+
+fn collect_messages(stream: &EventStream<i32>) -> Rc<RefCell<Vec<i32>>> {
+    let messages = Rc::new(RefCell::new(vec![]));
+    let collector = messages.clone();
+    stream.observe(move |msg: &i32| collector.borrow_mut().push(*msg));
+    messages
+}
+
+#[test]
+fn debounce_burst() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let messages = collect_messages(&stream);
+    let debounced = debounce(&stream.stream(), 50);
+
+    for msg in 0..5 {
+        debounced.emit(msg);
+        run_for(Duration::from_millis(10));
+    }
+    assert!(messages.borrow().is_empty());
+
+    run_for(Duration::from_millis(100));
+    assert_eq!(*messages.borrow(), vec![4]);
+
+    // Dropping the debounced stream cancels its pending message.
+    debounced.emit(5);
+    run_for(Duration::from_millis(10));
+    drop(debounced);
+    run_for(Duration::from_millis(100));
+    assert_eq!(*messages.borrow(), vec![4]);
+}
+
+#[test]
+fn throttle_burst() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let messages = collect_messages(&stream);
+    let throttled = throttle(&stream.stream(), 100);
+
+    throttled.emit(1);
+    run_for(Duration::from_millis(10));
+    assert_eq!(*messages.borrow(), vec![1]);
+
+    throttled.emit(2);
+    throttled.emit(3);
+    run_for(Duration::from_millis(10));
+    assert_eq!(*messages.borrow(), vec![1]);
+
+    run_for(Duration::from_millis(150));
+    assert_eq!(*messages.borrow(), vec![1, 3]);
+
+    // After a quiet interval, the next message is sent right away.
+    run_for(Duration::from_millis(150));
+    throttled.emit(4);
+    run_for(Duration::from_millis(10));
+    assert_eq!(*messages.borrow(), vec![1, 3, 4]);
+}
+
+#[test]
+fn wait_for_message() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let counter = Cell::new(0);
+    let _handle = interval_cancellable(&stream.stream(), 5, move || {
+        counter.set(counter.get() + 1);
+        counter.get()
+    });
+    let msg = relm_test::wait_for(stream.stream(), |msg| *msg == 3, Duration::from_secs(1));
+    assert_eq!(msg.expect("wait_for failed"), 3);
+}
+
+#[test]
+fn wait_for_timeout() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let _handle = interval_cancellable(&stream.stream(), 5, || 1);
+    let start = Instant::now();
+    let error = relm_test::wait_for(stream.stream(), |msg| *msg == 2, Duration::from_millis(50))
+        .expect_err("wait_for should time out");
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert!(!error.observed.is_empty());
+    assert!(error.observed.iter().all(|msg| *msg == 1));
+    assert!(error.to_string().contains("observed messages: [1"));
+}
+
+#[test]
+fn collect_messages_in_order() {
+    let _guard = acquire_default_context();
+    let stream = EventStream::new();
+    let _second = timeout_cancellable(&stream.stream(), 20, || 2);
+    let _first = timeout_cancellable(&stream.stream(), 10, || 1);
+    let messages = relm_test::collect_messages(stream.stream(), Duration::from_millis(100));
+    assert_eq!(messages, vec![1, 2]);
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use futures::channel::oneshot;
+use glib::MainContext;
+use relm::{EventStream, Relm, Update};
+use relm_derive::Msg;
+
+use self::Msg::*;
+
+pub struct Loader;
+
+#[derive(Msg)]
+pub enum Msg {
+    Loaded(i32),
+}
+
+impl Update for Loader {
+    type Model = ();
+    type ModelParam = ();
+    type Msg = Msg;
+
+    fn model(_relm: &Relm<Self>, _param: ()) -> Self::Model {
+    }
+
+    fn update(&mut self, _event: Msg) {
+    }
+}
+
+// Run `func` with `context` as the thread-default context, then iterate it until it has
+// nothing to dispatch.
+fn with_context<F: FnOnce()>(context: &MainContext, func: F) {
+    while !context.acquire() {
+        thread::yield_now();
+    }
+    context.with_thread_default(func);
+    while context.iteration(false) {
+    }
+    context.release();
+}
+
+#[test]
+fn spawn_local() {
+    let context = MainContext::new();
+    let stream = EventStream::with_context(&context);
+    let received = Rc::new(RefCell::new(vec![]));
+    {
+        let received = received.clone();
+        stream.observe(move |Loaded(value)| received.borrow_mut().push(*value));
+    }
+    let relm = Relm::<Loader>::new(&stream);
+    let (sender, receiver) = oneshot::channel();
+
+    with_context(&context, || relm.spawn_local(async { receiver.await.expect("receive") * 2 }, Loaded));
+    assert!(received.borrow().is_empty());
+
+    sender.send(21).expect("send");
+    with_context(&context, || ());
+    assert_eq!(*received.borrow(), vec![42]);
+}
+
+#[test]
+fn spawn_local_dropped_stream() {
+    let context = MainContext::new();
+    let stream = EventStream::with_context(&context);
+    let relm = Relm::<Loader>::new(&stream);
+    let (sender, receiver) = oneshot::channel();
+
+    with_context(&context, || relm.spawn_local(async { receiver.await.expect("receive") }, Loaded));
+    drop(stream);
+    sender.send(1).expect("send");
+    // Must not panic.
+    with_context(&context, || ());
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use relm_derive::Msg;
+use relm::{StateMachine, TraceKind, set_tracer};
+
+use self::Msg::*;
+
+#[derive(Clone, Debug, PartialEq)]
+enum State {
+    Idle,
+    Loading,
+    Loaded,
+    Error,
+}
+
+#[derive(Debug, Msg)]
+enum Msg {
+    Fail,
+    Load,
+    Loaded(usize),
+}
+
+fn new_machine(loaded: Rc<RefCell<Vec<usize>>>) -> StateMachine<State, Msg> {
+    StateMachine::new(State::Idle)
+        .transition(State::Idle, "Load", State::Loading)
+        .transition(State::Error, "Load", State::Loading)
+        .transition(State::Loading, "Fail", State::Error)
+        .transition_with(State::Loading, "Loaded", move |msg| {
+            if let Loaded(size) = msg {
+                loaded.borrow_mut().push(size);
+            }
+            State::Loaded
+        })
+}
+
+#[test]
+fn transitions() {
+    let loaded = Rc::new(RefCell::new(vec![]));
+    let mut machine = new_machine(loaded.clone());
+    assert_eq!(*machine.state(), State::Idle);
+
+    assert!(machine.handle(Load));
+    assert_eq!(*machine.state(), State::Loading);
+    assert!(machine.handle(Fail));
+    assert_eq!(*machine.state(), State::Error);
+    assert!(machine.handle(Load));
+    assert!(machine.handle(Loaded(42)));
+    assert_eq!(*machine.state(), State::Loaded);
+    assert_eq!(*loaded.borrow(), vec![42]);
+}
+
+#[test]
+fn illegal_transition() {
+    let illegal = Rc::new(RefCell::new(vec![]));
+    let mut machine = {
+        let illegal = illegal.clone();
+        new_machine(Rc::new(RefCell::new(vec![])))
+            .on_illegal(move |state, msg| illegal.borrow_mut().push(format!("{:?} {:?}", state, msg)))
+    };
+
+    assert!(!machine.handle(Loaded(1)));
+    assert_eq!(*machine.state(), State::Idle);
+    assert!(machine.handle(Load));
+    assert!(!machine.handle(Load));
+    assert_eq!(*machine.state(), State::Loading);
+    assert_eq!(*illegal.borrow(), vec!["Idle Loaded(1)", "Loading Load"]);
+}
+
+#[test]
+fn trace_transitions() {
+    let traced = Rc::new(RefCell::new(vec![]));
+    {
+        let traced = traced.clone();
+        set_tracer(move |event| {
+            if event.kind == TraceKind::Transition {
+                let (from, to) = event.transition.expect("transition");
+                traced.borrow_mut().push(format!("{} -> {} on {}", from, to, event.message.unwrap_or(event.variant)));
+            }
+        });
+    }
+    let mut machine = new_machine(Rc::new(RefCell::new(vec![])));
+    assert!(machine.handle(Load));
+    assert!(machine.handle(Loaded(3)));
+    assert!(!machine.handle(Load));
+    assert_eq!(*traced.borrow(), vec!["Idle -> Loading on Load", "Loading -> Loaded on Loaded(3)"]);
+}
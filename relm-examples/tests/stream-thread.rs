@@ -0,0 +1,51 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::mpsc;
+use std::thread;
+
+use glib::MainContext;
+use relm::{EventStream, StreamError};
+
+#[test]
+fn stream_in_other_thread() {
+    gtk::init().expect("gtk init");
+    assert!(EventStream::<()>::try_new().is_ok());
+
+    let result = thread::spawn(|| EventStream::<()>::try_new().err())
+        .join().expect("join");
+    assert_eq!(result, Some(StreamError::NotMainThread));
+
+    let (acquired_sender, acquired) = mpsc::channel();
+    let (release_sender, release) = mpsc::channel::<()>();
+    let owner = thread::spawn(move || {
+        let context = MainContext::default();
+        assert!(context.acquire());
+        acquired_sender.send(()).expect("send");
+        release.recv().expect("recv");
+        context.release();
+    });
+    acquired.recv().expect("recv");
+    assert_eq!(EventStream::<()>::try_new().err(), Some(StreamError::ContextNotOwned));
+    release_sender.send(()).expect("send");
+    owner.join().expect("join");
+    assert!(EventStream::<()>::try_new().is_ok());
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    EditableSignals,
+    EntryExt,
+    Inhibit,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    error: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Changed,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            error: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Changed => self.model.error = self.widgets.entry.get_text().parse::<i32>().is_err(),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                css: "button { padding: 4px; }",
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    changed => Changed,
+                    style_classes: if self.model.error { ["error"] } else { [] },
+                },
+                #[name="button"]
+                gtk::Button {
+                    label: "OK",
+                    style_classes: ["suggested-action", "flat"],
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{EntryExt, StyleContextExt, WidgetExt};
+
+    use gtk_test::run_loop;
+
+    use crate::Win;
+
+    #[test]
+    fn style_classes() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let button_style = widgets.button.get_style_context();
+        let entry_style = widgets.entry.get_style_context();
+
+        assert!(button_style.has_class("suggested-action"));
+        assert!(button_style.has_class("flat"));
+        assert!(!entry_style.has_class("error"));
+
+        widgets.entry.set_text("abc");
+        run_loop();
+        assert!(entry_style.has_class("error"));
+        widgets.entry.set_text("42");
+        run_loop();
+        assert!(!entry_style.has_class("error"));
+    }
+}
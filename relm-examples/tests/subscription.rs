@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use gtk::{Inhibit, LabelExt, OrientableExt, WidgetExt};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Subscription, Widget};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+pub struct Model {
+    ticks: u32,
+    total: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add(u32),
+    Quit,
+    Tick,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            ticks: 0,
+            total: 0,
+        }
+    }
+
+    fn subscribe(_relm: &Relm<Self>) -> Vec<Subscription<Msg>> {
+        let (channel, sender) = Subscription::channel(Add);
+        thread::spawn(move || {
+            for value in 1..4 {
+                sender.send(value).expect("send");
+            }
+        });
+        vec![
+            channel,
+            Subscription::interval(10, || Tick),
+            Subscription::new(|_stream| || STOPPED.store(true, Ordering::SeqCst)),
+        ]
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Add(value) => self.model.total += value,
+            Quit => gtk::main_quit(),
+            Tick => self.model.ticks += 1,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="ticks"]
+                gtk::Label {
+                    text: &self.model.ticks.to_string(),
+                },
+                #[name="total"]
+                gtk::Label {
+                    text: &self.model.total.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use gtk::LabelExt;
+
+    use gtk_test::{assert_text, wait};
+
+    use crate::{STOPPED, Win};
+
+    #[test]
+    fn subscriptions() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        wait(200);
+        assert_text!(widgets.total, 6);
+        assert_ne!(widgets.ticks.get_text(), "0");
+        assert!(!STOPPED.load(Ordering::SeqCst));
+
+        drop(component);
+        assert!(STOPPED.load(Ordering::SeqCst));
+    }
+}
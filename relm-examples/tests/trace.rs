@@ -0,0 +1,140 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use relm_derive::Msg;
+use relm::{Relm, TraceKind, Update, UpdateNew, execute, set_tracer};
+
+#[derive(Debug, Msg)]
+enum CounterMsg {
+    Add(i32),
+    Reset,
+}
+
+struct Counter {
+    relm: Relm<Counter>,
+    value: i32,
+}
+
+impl Update for Counter {
+    type Model = ();
+    type ModelParam = ();
+    type Msg = CounterMsg;
+
+    fn model(_: &Relm<Self>, _: ()) {
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            CounterMsg::Add(value) => {
+                self.value += value;
+                if self.value > 2 {
+                    self.relm.stream().emit(CounterMsg::Reset);
+                }
+            },
+            CounterMsg::Reset => self.value = 0,
+        }
+    }
+}
+
+impl UpdateNew for Counter {
+    fn new(relm: &Relm<Self>, _: ()) -> Self {
+        Counter {
+            relm: relm.clone(),
+            value: 0,
+        }
+    }
+}
+
+#[derive(Msg)]
+enum QuietMsg {
+    Ping,
+}
+
+struct Quiet;
+
+impl Update for Quiet {
+    type Model = ();
+    type ModelParam = ();
+    type Msg = QuietMsg;
+
+    fn model(_: &Relm<Self>, _: ()) {
+    }
+
+    fn update(&mut self, _: QuietMsg) {
+    }
+}
+
+impl UpdateNew for Quiet {
+    fn new(_: &Relm<Self>, _: ()) -> Self {
+        Quiet
+    }
+}
+
+type Traced = Rc<RefCell<Vec<(u64, TraceKind, String, &'static str, Option<String>)>>>;
+
+fn record_traces() -> Traced {
+    let traced = Rc::new(RefCell::new(vec![]));
+    let events = traced.clone();
+    set_tracer(move |event| {
+        events.borrow_mut().push((event.sequence, event.kind, event.component.to_string(), event.variant,
+            event.message.map(str::to_string)));
+    });
+    traced
+}
+
+#[test]
+fn trace_messages() {
+    let traced = record_traces();
+    let stream = execute::<Counter>(());
+    stream.emit(CounterMsg::Add(3));
+    let _ = stream.dispatch_pending();
+
+    let traced = traced.borrow();
+    let events: Vec<_> = traced.iter()
+        .map(|(_, kind, _, _, message)| (*kind, message.as_deref()))
+        .collect();
+    assert_eq!(events, vec![
+        (TraceKind::Emit, Some("Add(3)")),
+        (TraceKind::Dispatch, Some("Add(3)")),
+        (TraceKind::Emit, Some("Reset")),
+        (TraceKind::Dispatch, Some("Reset")),
+    ]);
+    assert!(traced.windows(2).all(|events| events[0].0 < events[1].0));
+    assert!(traced.iter().all(|(_, _, component, _, _)| component.ends_with("Counter")));
+    assert_eq!(traced[0].3, "Add");
+}
+
+#[test]
+fn trace_message_without_debug() {
+    let traced = record_traces();
+    let stream = execute::<Quiet>(());
+    stream.emit(QuietMsg::Ping);
+    let _ = stream.dispatch_pending();
+
+    let traced = traced.borrow();
+    assert_eq!(traced.len(), 2);
+    assert_eq!(traced[0].3, "Ping");
+    assert_eq!(traced[0].4, None);
+}
@@ -0,0 +1,129 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    CellRendererTextExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    TreePath,
+    TreeViewColumnExt,
+    TreeViewExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use gtk::prelude::{GtkListStoreExtManual, StaticType};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    activated: String,
+    store: gtk::ListStore,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    RowActivated(TreePath),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        let store = gtk::ListStore::new(&[String::static_type(), u32::static_type()]);
+        store.insert_with_values(None, &[0, 1], &[&"Cargo.toml", &456]);
+        store.insert_with_values(None, &[0, 1], &[&"README.md", &1234]);
+        Model {
+            activated: String::new(),
+            store,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            RowActivated(path) => {
+                let indices: Vec<_> = path.get_indices().iter().map(ToString::to_string).collect();
+                self.model.activated = indices.join(":");
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="tree_view"]
+                gtk::TreeView {
+                    model: Some(&self.model.store),
+                    row_activated(_, path, _) => RowActivated(path.clone()),
+                    #[name="name_column"]
+                    gtk::TreeViewColumn {
+                        title: "Name",
+                        gtk::CellRendererText {
+                            text: (0),
+                        },
+                    },
+                    gtk::TreeViewColumn {
+                        title: "Size",
+                        gtk::CellRendererText {
+                            property_editable: false,
+                            text: (1),
+                        },
+                    },
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.activated,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{CellLayoutExt, LabelExt, TreePath, TreeViewColumnExt, TreeViewExt};
+
+    use gtk_test::assert_text;
+
+    use crate::Win;
+
+    #[test]
+    fn tree_view_columns() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let tree_view = &widgets.tree_view;
+        let name_column = &widgets.name_column;
+
+        let titles: Vec<_> = tree_view.get_columns().iter()
+            .map(|column| column.get_title().map(|title| title.to_string()))
+            .collect();
+        assert_eq!(titles, vec![Some("Name".to_string()), Some("Size".to_string())]);
+        assert_eq!(name_column.get_cells().len(), 1);
+        assert!(tree_view.get_model().is_some());
+
+        tree_view.row_activated(&TreePath::from_indicesv(&[1]), name_column);
+        assert_text!(widgets.label, "1");
+    }
+}
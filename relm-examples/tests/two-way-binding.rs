@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    EntryExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    SpinButtonExt,
+    SwitchExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    count: f64,
+    enabled: bool,
+    name: String,
+    updates: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    CountChanged(f64),
+    EnabledChanged(bool),
+    NameChanged(String),
+    Quit,
+    Reset,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            count: 1.0,
+            enabled: false,
+            name: "relm".to_string(),
+            updates: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            CountChanged(count) => {
+                self.model.count = count;
+                self.model.updates += 1;
+            },
+            EnabledChanged(enabled) => {
+                self.model.enabled = enabled;
+                self.model.updates += 1;
+            },
+            NameChanged(name) => {
+                self.model.name = name;
+                self.model.updates += 1;
+            },
+            Quit => gtk::main_quit(),
+            Reset => {
+                self.model.count = 0.0;
+                self.model.enabled = false;
+                self.model.name = String::new();
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="switch"]
+                gtk::Switch {
+                    active: bind self.model.enabled => EnabledChanged,
+                },
+                #[name="entry"]
+                gtk::Entry {
+                    text: bind &self.model.name => NameChanged,
+                },
+                #[name="spin_button"]
+                gtk::SpinButton {
+                    adjustment: &gtk::Adjustment::new(0.0, 0.0, 10.0, 1.0, 1.0, 0.0),
+                    value: bind self.model.count => CountChanged,
+                },
+                #[name="updates"]
+                gtk::Label {
+                    text: &self.model.updates.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{EntryExt, LabelExt, SpinButtonExt, SwitchExt};
+    use gtk_test::run_loop;
+
+    use crate::Msg::Reset;
+    use crate::Win;
+
+    #[test]
+    fn two_way_binding() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        assert!(!widgets.switch.get_active());
+        assert_eq!(widgets.entry.get_text(), "relm");
+        assert_eq!(widgets.spin_button.get_value(), 1.0);
+
+        widgets.switch.set_active(true);
+        widgets.entry.set_text("gtk");
+        widgets.spin_button.set_value(5.0);
+        run_loop();
+        assert_eq!(widgets.updates.get_text(), "3");
+
+        component.emit(Reset);
+        run_loop();
+        assert!(!widgets.switch.get_active());
+        assert_eq!(widgets.entry.get_text(), "");
+        assert_eq!(widgets.spin_button.get_value(), 0.0);
+        // The lock prevents the bindings from sending their message back.
+        assert_eq!(widgets.updates.get_text(), "3");
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::mem;
+
+use gtk::{
+    ButtonExt,
+    EditableSignals,
+    EntryExt,
+    Inhibit,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    redo: Vec<String>,
+    text: String,
+    undo: Vec<String>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Edit(String),
+    Quit,
+    Redo,
+    Undo,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            redo: vec![],
+            text: String::new(),
+            undo: vec![],
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            // Setting the text of the entry when restoring a snapshot emits this message again.
+            Edit(text) if text != self.model.text => {
+                let previous = mem::replace(&mut self.model.text, text);
+                self.model.undo.push(previous);
+                self.model.redo.clear();
+            },
+            Edit(_) => (),
+            Quit => gtk::main_quit(),
+            Redo => {
+                if let Some(text) = self.model.redo.pop() {
+                    let mut model = self.take_history();
+                    model.undo.push(mem::take(&mut self.model.text));
+                    model.text = text;
+                    self.replace_model(model);
+                }
+            },
+            Undo => {
+                if let Some(text) = self.model.undo.pop() {
+                    let mut model = self.take_history();
+                    model.redo.push(mem::take(&mut self.model.text));
+                    model.text = text;
+                    self.replace_model(model);
+                }
+            },
+        }
+    }
+
+    // Create a new model with the undo and redo stacks of the current one.
+    fn take_history(&mut self) -> Model {
+        Model {
+            redo: mem::take(&mut self.model.redo),
+            text: String::new(),
+            undo: mem::take(&mut self.model.undo),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    text: &self.model.text,
+                    changed(entry) => Edit(entry.get_text().to_string()),
+                },
+                #[name="undo_button"]
+                gtk::Button {
+                    clicked => Undo,
+                    label: "Undo",
+                    sensitive: !self.model.undo.is_empty(),
+                },
+                #[name="redo_button"]
+                gtk::Button {
+                    clicked => Redo,
+                    label: "Redo",
+                    sensitive: !self.model.redo.is_empty(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run().expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{EntryExt, WidgetExt};
+
+    use gtk_test::{assert_text, click};
+    use relm_test::enter_keys;
+
+    use crate::Win;
+
+    #[test]
+    fn undo_redo() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let undo_button = &widgets.undo_button;
+        let redo_button = &widgets.redo_button;
+
+        assert!(!undo_button.get_sensitive());
+        enter_keys(entry, "ab");
+        assert_text!(entry, "ab");
+        assert!(undo_button.get_sensitive());
+
+        click(undo_button);
+        assert_text!(entry, "a");
+        assert!(redo_button.get_sensitive());
+
+        click(undo_button);
+        assert_text!(entry, "");
+        assert!(!undo_button.get_sensitive());
+
+        click(redo_button);
+        click(redo_button);
+        assert_text!(entry, "ab");
+        assert!(!redo_button.get_sensitive());
+    }
+}
@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use relm_derive::Msg;
+use relm::{EventStream, Relm, Update, UpdateNew};
+use relm::test::{drive_update, new_component};
+
+use self::Msg::*;
+
+#[derive(Debug, Msg, PartialEq)]
+enum Msg {
+    Changed(i32),
+    Increment,
+    Started,
+}
+
+struct Counter {
+    relm: Relm<Counter>,
+    value: i32,
+}
+
+impl Update for Counter {
+    type Model = i32;
+    type ModelParam = i32;
+    type Msg = Msg;
+
+    fn model(relm: &Relm<Self>, value: i32) -> i32 {
+        relm.stream().emit(Started);
+        value
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Changed(_) | Started => (),
+            Increment => {
+                self.value += 1;
+                self.relm.stream().emit(Changed(self.value));
+            },
+        }
+    }
+}
+
+impl UpdateNew for Counter {
+    fn new(relm: &Relm<Self>, value: i32) -> Self {
+        Counter {
+            relm: relm.clone(),
+            value,
+        }
+    }
+}
+
+#[test]
+fn drive_counter() {
+    let (mut counter, stream) = new_component::<Counter>(10);
+    let emitted = drive_update(&mut counter, &stream, vec![Increment, Increment]);
+    assert_eq!(counter.value, 12);
+    assert_eq!(emitted, vec![Started, Changed(11), Changed(12)]);
+
+    let emitted = drive_update(&mut counter, &stream, vec![Changed(0)]);
+    assert_eq!(counter.value, 12);
+    assert!(emitted.is_empty());
+}
+
+#[test]
+fn detached_stream() {
+    let stream = EventStream::new_detached();
+    stream.emit(1);
+    stream.emit(2);
+    assert_eq!(stream.stream().len(), Some(2));
+    assert_eq!(stream.drain_pending(), vec![1, 2]);
+    assert_eq!(stream.stream().len(), Some(0));
+
+    let received = Rc::new(RefCell::new(vec![]));
+    let receiver = received.clone();
+    stream.set_callback(move |msg| receiver.borrow_mut().push(msg));
+    stream.emit(3);
+    assert_eq!(stream.dispatch_pending(), 1);
+    assert_eq!(*received.borrow(), vec![3]);
+}
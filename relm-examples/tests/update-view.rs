@@ -0,0 +1,110 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    counter: i32,
+    text: String,
+}
+
+fn increment(model: &mut Model) {
+    model.counter += 1;
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.model.text = "Initialized".to_string();
+    }
+
+    fn model() -> Model {
+        Model {
+            counter: 0,
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Increment => {
+                // This mutation is not tracked, so the view needs to be updated explicitly.
+                increment(&mut self.model);
+                self.update_view();
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="counter_label"]
+                gtk::Label {
+                    text: &self.model.counter.to_string(),
+                },
+                #[name="text_label"]
+                gtk::Label {
+                    text: &self.model.text,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+    use gtk_test::run_loop;
+
+    use crate::Msg::Increment;
+    use crate::Win;
+
+    #[test]
+    fn update_view() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        assert_eq!(widgets.text_label.get_text(), "Initialized");
+        assert_eq!(widgets.counter_label.get_text(), "0");
+
+        component.emit(Increment);
+        run_loop();
+        assert_eq!(widgets.counter_label.get_text(), "1");
+    }
+}
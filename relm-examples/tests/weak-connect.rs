@@ -0,0 +1,137 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    ContainerExt,
+    LabelExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{connect_weak, Relm, Update, Widget, WidgetTest};
+use relm_derive::Msg;
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum Msg {
+    Clicked(usize),
+}
+
+#[derive(Clone)]
+struct Widgets {
+    button: gtk::Button,
+    label: gtk::Label,
+    vbox: gtk::Box,
+}
+
+struct Counter {
+    widgets: Widgets,
+}
+
+impl Update for Counter {
+    type Model = ();
+    type ModelParam = ();
+    type Msg = Msg;
+
+    fn model(_: &Relm<Self>, _: ()) {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Clicked(children) => self.widgets.label.set_text(&children.to_string()),
+        }
+    }
+}
+
+impl Widget for Counter {
+    type Root = gtk::Box;
+
+    fn root(&self) -> Self::Root {
+        self.widgets.vbox.clone()
+    }
+
+    fn view(relm: &Relm<Self>, _model: Self::Model) -> Self {
+        let vbox = gtk::Box::new(Vertical, 0);
+        let label = gtk::Label::new(None);
+        vbox.add(&label);
+        let button = gtk::Button::with_label("Count");
+        vbox.add(&button);
+        // The button is in vbox, so a strong reference to vbox in its handler would be a cycle.
+        connect_weak!(relm, button, connect_clicked(_), [vbox], Clicked(vbox.get_children().len()));
+        vbox.show_all();
+
+        Counter {
+            widgets: Widgets {
+                button,
+                label,
+                vbox,
+            },
+        }
+    }
+}
+
+impl WidgetTest for Counter {
+    type Streams = ();
+
+    fn get_streams(&self) -> Self::Streams {
+    }
+
+    type Widgets = Widgets;
+
+    fn get_widgets(&self) -> Self::Widgets {
+        self.widgets.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+    use gtk::prelude::ObjectExt;
+
+    use gtk_test::{assert_text, run_loop};
+    use relm_test::click;
+
+    use crate::Counter;
+
+    #[test]
+    fn weak_connect() {
+        let (component, _, widgets) = relm::init_test::<Counter>(()).expect("init_test failed");
+        click(&widgets.button);
+        assert_text!(widgets.label, 2);
+        drop(widgets);
+        drop(component);
+
+        let objects: Vec<_> = (0..1000)
+            .map(|_| {
+                let component = relm::init::<Counter>(()).expect("counter");
+                let widgets = component.widgets();
+                (widgets.vbox.downgrade(), widgets.button.downgrade(), widgets.label.downgrade())
+            })
+            .collect();
+        run_loop();
+        let alive = objects.iter()
+            .filter(|(vbox, button, label)| vbox.upgrade().is_some() || button.upgrade().is_some() ||
+                label.upgrade().is_some())
+            .count();
+        assert_eq!(alive, 0);
+    }
+}
@@ -0,0 +1,124 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use gtk::{
+    ButtonExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Decrement,
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.add(-1),
+            Increment => self.add(Self::step()),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    // Helpers can be called from update() and from the bindings of the view.
+    fn add(&mut self, value: i32) {
+        self.model.counter += value;
+    }
+
+    /// Format the counter for the label.
+    #[must_use]
+    pub fn format_counter(counter: i32) -> String {
+        format!("Counter: {}", counter)
+    }
+
+    const fn step() -> i32 {
+        2
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="inc_button"]
+                gtk::Button {
+                    clicked => Increment,
+                    label: "+",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &Self::format_counter(self.model.counter),
+                },
+                #[name="dec_button"]
+                gtk::Button {
+                    clicked => Decrement,
+                    label: "-",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::LabelExt;
+
+    use gtk_test::{assert_text, click};
+
+    use crate::Win;
+
+    #[test]
+    fn helper_methods() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let inc_button = &widgets.inc_button;
+        let dec_button = &widgets.dec_button;
+        let label = &widgets.label;
+
+        assert_text!(label, "Counter: 0");
+        click(inc_button);
+        assert_text!(label, "Counter: 2");
+        click(dec_button);
+        assert_text!(label, "Counter: 1");
+        assert_eq!(Win::format_counter(3), "Counter: 3");
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    EntryExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum GreetingMsg {
+}
+
+#[widget]
+impl Widget for Greeting {
+    fn model() {
+    }
+
+    fn update(&mut self, _event: GreetingMsg) {
+    }
+
+    view! {
+        gtk::Label {
+            text: "Hello",
+        }
+    }
+}
+
+pub struct Model {
+    message: String,
+    user: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Login(String, String),
+    Quit,
+    Send(String),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            message: String::new(),
+            user: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Login(user, password) => {
+                if password == "secret" {
+                    self.model.user = user;
+                }
+            },
+            Quit => gtk::main_quit(),
+            Send(message) => self.model.message = message,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="username"]
+                gtk::Entry {
+                },
+                #[name="password"]
+                gtk::Entry {
+                    visibility: false,
+                },
+                #[name="login_button"]
+                gtk::Button {
+                    clicked => Login(self.widgets.username.get_text().to_string(),
+                        self.widgets.password.get_text().to_string()),
+                    label: "Log in",
+                },
+                #[name="user_label"]
+                gtk::Label {
+                    text: &self.model.user,
+                },
+                #[name="greeting"]
+                Greeting {
+                },
+                #[name="send_button"]
+                gtk::Button {
+                    clicked => Send(self.widgets.greeting.get_text().to_string()),
+                    label: "Send",
+                },
+                #[name="message_label"]
+                gtk::Label {
+                    text: &self.model.message,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{EntryExt, LabelExt};
+
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn widget_references() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+
+        widgets.username.set_text("alice");
+        widgets.password.set_text("wrong");
+        click(&widgets.login_button);
+        assert_eq!(widgets.user_label.get_text(), "");
+
+        widgets.password.set_text("secret");
+        click(&widgets.login_button);
+        assert_eq!(widgets.user_label.get_text(), "alice");
+
+        click(&widgets.send_button);
+        assert_eq!(widgets.message_label.get_text(), "Hello");
+    }
+}
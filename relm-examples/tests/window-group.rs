@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    ButtonExt,
+    GtkWindowExt,
+    Inhibit,
+    LabelExt,
+    OrientableExt,
+    WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget, WindowGroup};
+use relm_derive::{Msg, widget};
+
+use self::DocMsg::*;
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum DocMsg {
+    Close,
+}
+
+#[widget]
+impl Widget for DocWin {
+    fn model(_: &Relm<Self>, title: String) -> String {
+        title
+    }
+
+    fn update(&mut self, event: DocMsg) {
+        match event {
+            Close => (),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            title: &self.model,
+            delete_event(_, _) => (Close, Inhibit(false)),
+        }
+    }
+}
+
+pub struct Model {
+    count: usize,
+    windows: WindowGroup,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    NewDocument,
+    Quit,
+    WindowClosed(usize),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        let windows = WindowGroup::new();
+        let stream = relm.stream().clone();
+        windows.connect_window_closed(move |remaining| stream.emit(WindowClosed(remaining)));
+        Model {
+            count: 0,
+            windows,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            NewDocument => {
+                let title = format!("Document {}", self.model.count + 1);
+                self.model.windows.spawn_window::<DocWin>(title).expect("document window");
+                self.model.count = self.model.windows.active_windows();
+            },
+            Quit => gtk::main_quit(),
+            WindowClosed(remaining) => self.model.count = remaining,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="new_button"]
+                gtk::Button {
+                    clicked => NewDocument,
+                    label: "New document",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.count.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{GtkWindowExt, LabelExt};
+    use gtk::prelude::Cast;
+
+    use gtk_test::{assert_text, run_loop};
+    use relm_test::click;
+
+    use crate::Win;
+
+    fn find_window(title: &str) -> Option<gtk::Window> {
+        gtk::Window::list_toplevels().into_iter()
+            .filter_map(|window| window.downcast::<gtk::Window>().ok())
+            .find(|window| window.get_title().as_deref() == Some(title))
+    }
+
+    #[test]
+    fn window_group() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let new_button = &widgets.new_button;
+        let label = &widgets.label;
+
+        assert_text!(label, 0);
+        click(new_button);
+        assert_text!(label, 1);
+        click(new_button);
+        assert_text!(label, 2);
+
+        let document = find_window("Document 1").expect("first document");
+        document.close();
+        run_loop();
+        assert_text!(label, 1);
+        assert!(find_window("Document 1").is_none());
+        assert!(find_window("Document 2").is_some());
+    }
+}
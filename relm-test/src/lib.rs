@@ -20,16 +20,22 @@
  */
 
 use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use enigo::{Enigo, KeyboardControllable, MouseButton, MouseControllable};
-use gdk::keyval_to_unicode;
+use gdk::{EventType, ModifierType, keyval_to_unicode, unicode_to_keyval};
 use gdk::keys::Key;
 use gdk::keys::constants as key;
-use glib::{IsA, Object, object::Cast};
+use glib::{IsA, MainContext, Object, object::Cast};
 use gtk::{Inhibit, ToolButton, ToolButtonExt, Widget, WidgetExt};
-use gtk_test::{self, focus, mouse_move, run_loop, wait_for_draw};
-use relm::StreamHandle;
+use gtk_test::{self, mouse_move, run_loop, wait_for_draw};
+use relm::{ObserverHandle, StreamHandle};
+#[doc(hidden)]
+pub use relm::DisplayVariant;
 
 // TODO: should remove the signal after wait()?
 // FIXME: remove when it's in gtk-test.
@@ -54,6 +60,7 @@ macro_rules! gtk_observer_new {
 }
 
 pub struct Observer<MSG> {
+    _observer: ObserverHandle<MSG>,
     result: Rc<RefCell<Option<MSG>>>,
 }
 
@@ -61,12 +68,14 @@ impl<MSG: Clone + 'static> Observer<MSG> {
     pub fn new<F: Fn(&MSG) -> bool + 'static>(stream: StreamHandle<MSG>, predicate: F) -> Self {
         let result = Rc::new(RefCell::new(None));
         let res = result.clone();
-        stream.observe(move |msg| {
+        let observer = stream.observe(move |msg| {
             if predicate(msg) {
                 *res.borrow_mut() = Some(msg.clone());
             }
         });
         Self {
+            // Remove the observer when the test is done with it.
+            _observer: observer.remove_on_drop(),
             result,
         }
     }
@@ -85,6 +94,88 @@ impl<MSG: Clone + 'static> Observer<MSG> {
     }
 }
 
+/// Error returned by [`wait_for()`](fn.wait_for.html) when no matching message was emitted
+/// before the timeout.
+pub struct Timeout<MSG> {
+    /// The messages that were emitted on the stream while waiting.
+    pub observed: Vec<MSG>,
+    /// How long the main loop was iterated before giving up, i.e. the `timeout` given to
+    /// `wait_for()`.
+    pub timeout: Duration,
+}
+
+impl<MSG: Debug> Debug for Timeout<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.debug_struct("Timeout")
+            .field("observed", &self.observed)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl<MSG: Debug> Display for Timeout<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "no matching message after {:?}, observed messages: {:?}", self.timeout, self.observed)
+    }
+}
+
+impl<MSG: Debug> Error for Timeout<MSG> {
+}
+
+/// Iterate the main loop until a message matching `predicate` is emitted on `stream` and return
+/// it, or return an error holding the messages emitted in the meantime after `timeout`.
+pub fn wait_for<MSG, F>(stream: StreamHandle<MSG>, predicate: F, timeout: Duration) -> Result<MSG, Timeout<MSG>>
+where MSG: Clone + Debug + 'static,
+      F: Fn(&MSG) -> bool + 'static,
+{
+    let observed = Rc::new(RefCell::new(vec![]));
+    let matched = Rc::new(RefCell::new(None));
+    let _observer = {
+        let observed = observed.clone();
+        let matched = matched.clone();
+        stream.observe(move |msg| {
+            if matched.borrow().is_none() {
+                if predicate(msg) {
+                    *matched.borrow_mut() = Some(msg.clone());
+                }
+                else {
+                    observed.borrow_mut().push(msg.clone());
+                }
+            }
+        }).remove_on_drop()
+    };
+    iterate_until(timeout, || matched.borrow().is_some());
+    let msg = matched.borrow_mut().take();
+    msg.ok_or_else(|| Timeout {
+        observed: observed.replace(vec![]),
+        timeout,
+    })
+}
+
+/// Iterate the main loop during `duration` and return the messages emitted on `stream`, in order.
+pub fn collect_messages<MSG: Clone + 'static>(stream: StreamHandle<MSG>, duration: Duration) -> Vec<MSG> {
+    let messages = Rc::new(RefCell::new(vec![]));
+    let _observer = {
+        let messages = messages.clone();
+        stream.observe(move |msg| messages.borrow_mut().push(msg.clone()))
+            .remove_on_drop()
+    };
+    iterate_until(duration, || false);
+    messages.replace(vec![])
+}
+
+// Iterate the default main context until done() returns true or the timeout is reached.
+fn iterate_until<F: Fn() -> bool>(timeout: Duration, done: F) {
+    let context = MainContext::default();
+    let deadline = Instant::now() + timeout;
+    while !done() && Instant::now() < deadline {
+        // Don't block in the iteration, so that the deadline is respected when no event comes.
+        if !context.iteration(false) {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! relm_observer_new {
     ($component:expr, $pat:pat) => {
@@ -108,7 +199,7 @@ macro_rules! relm_observer_wait {
                 ($name1, $name2 $(, $rest)*)
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: received {}", $crate::DisplayVariant::display_variant(&msg));
             }
         };
     };
@@ -119,7 +210,7 @@ macro_rules! relm_observer_wait {
                 $name
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: received {}", $crate::DisplayVariant::display_variant(&msg));
             }
         };
     };
@@ -130,13 +221,14 @@ macro_rules! relm_observer_wait {
                 ()
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: received {}", $crate::DisplayVariant::display_variant(&msg));
             }
         };
     };
 }
 
-// FIXME: remove when it's in gtk-test.
+/// Click on `widget` by synthesizing a button press and release at its center, and wait until
+/// the resulting relm messages are dispatched.
 pub fn click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget: &W) {
     wait_for_draw(widget, || {
         let observer =
@@ -144,20 +236,24 @@ pub fn click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget:
                 gtk_observer_new!(tool_button, connect_clicked, |_|)
             }
             else {
-                gtk_observer_new!(widget, connect_button_press_event, |_, _| {
-                    Inhibit(false)
-                })
+                gtk_observer_new!(widget, connect_event_after, |_, _| {})
             };
         let allocation = widget.get_allocation();
-        mouse_move(widget, allocation.width / 2, allocation.height / 2);
-        let mut enigo = Enigo::new();
-        enigo.mouse_click(MouseButton::Left);
+        let (window, x, y) = toplevel_position(widget, allocation.width / 2, allocation.height / 2);
+        gdk::test_simulate_button(&window, x, y, 1, ModifierType::empty(), EventType::ButtonPress);
+        gdk::test_simulate_button(&window, x, y, 1, ModifierType::empty(), EventType::ButtonRelease);
         observer.wait();
 
         wait_for_relm_events();
     });
 }
 
+/// Give the keyboard focus to `widget` and wait until the resulting relm messages are dispatched.
+pub fn focus<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W) {
+    gtk_test::focus(widget);
+    wait_for_relm_events();
+}
+
 pub fn mouse_move_to<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget: &W) {
     wait_for_draw(widget, || {
         let allocation = widget.get_allocation();
@@ -188,41 +284,87 @@ pub fn double_click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W
     });
 }
 
-// FIXME: don't wait the observer for modifier keys like shift?
+/// Focus `widget` and synthesize a key press of `key`, then wait until the resulting relm
+/// messages are dispatched.
 pub fn key_press<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, key: Key) {
+    key_press_with_modifiers(widget, key, ModifierType::empty());
+}
+
+/// Like [`key_press()`](fn.key_press.html), with the `modifiers` held, like
+/// `ModifierType::CONTROL_MASK`.
+pub fn key_press_with_modifiers<W>(widget: &W, key: Key, modifiers: ModifierType)
+    where W: Clone + IsA<Object> + IsA<Widget> + WidgetExt,
+{
+    send_key(widget, key, modifiers, EventType::KeyPress);
+}
+
+/// Focus `widget` and synthesize a key release of `key`, then wait until the resulting relm
+/// messages are dispatched.
+pub fn key_release<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, key: Key) {
+    key_release_with_modifiers(widget, key, ModifierType::empty());
+}
+
+/// Like [`key_release()`](fn.key_release.html), with the `modifiers` held.
+pub fn key_release_with_modifiers<W>(widget: &W, key: Key, modifiers: ModifierType)
+    where W: Clone + IsA<Object> + IsA<Widget> + WidgetExt,
+{
+    send_key(widget, key, modifiers, EventType::KeyRelease);
+}
+
+/// Focus `widget` and type `text` by synthesizing a key press and release for each of its
+/// characters, then wait until the resulting relm messages are dispatched.
+pub fn enter_text<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, text: &str) {
     wait_for_draw(widget, || {
-        let observer = gtk_observer_new!(widget, connect_key_press_event, |_, _| {
-            Inhibit(false)
-        });
-        focus(widget);
-        let mut enigo = Enigo::new();
-        enigo.key_down(gdk_key_to_enigo_key(key));
-        observer.wait();
+        gtk_test::focus(widget);
+        for char in text.chars() {
+            let keyval = unicode_to_keyval(char as u32);
+            simulate_key(widget, keyval, ModifierType::empty(), EventType::KeyPress);
+            simulate_key(widget, keyval, ModifierType::empty(), EventType::KeyRelease);
+        }
 
         wait_for_relm_events();
     });
 }
 
-pub fn key_release<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, key: Key) {
+fn send_key<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, key: Key, modifiers: ModifierType,
+    event_type: EventType)
+{
     wait_for_draw(widget, || {
-        let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
-            Inhibit(false)
-        });
-        focus(widget);
-        let mut enigo = Enigo::new();
-        enigo.key_up(gdk_key_to_enigo_key(key));
-        observer.wait();
+        gtk_test::focus(widget);
+        simulate_key(widget, *key, modifiers, event_type);
 
         wait_for_relm_events();
     });
 }
 
+// Send the key event to the toplevel window, which forwards it to its focused widget, and wait
+// until it was handled.
+// The event-after signal is emitted even when a handler inhibits the event.
+fn simulate_key<W: IsA<Widget> + WidgetExt>(widget: &W, keyval: u32, modifiers: ModifierType, event_type: EventType) {
+    let observer = gtk_observer_new!(widget, connect_event_after, |_, _| {});
+    let (window, x, y) = toplevel_position(widget, 0, 0);
+    gdk::test_simulate_key(&window, x, y, keyval, modifiers, event_type);
+    observer.wait();
+}
+
+// Get the window of the toplevel of widget and the position (x, y) of widget in this window.
+// Panic when the event cannot be sent, instead of letting the test fail for an unrelated reason.
+fn toplevel_position<W: IsA<Widget> + WidgetExt>(widget: &W, x: i32, y: i32) -> (gdk::Window, i32, i32) {
+    widget.get_toplevel()
+        .and_then(|toplevel| {
+            let window = toplevel.get_window()?;
+            let (x, y) = widget.translate_coordinates(&toplevel, x, y)?;
+            Some((window, x, y))
+        })
+        .expect("Cannot send the event: the widget is not realized or has no toplevel window")
+}
+
 pub fn enter_key<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, key: Key) {
     wait_for_draw(widget, || {
         let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
             Inhibit(false)
         });
-        focus(widget);
+        gtk_test::focus(widget);
         let mut enigo = Enigo::new();
         enigo.key_click(gdk_key_to_enigo_key(key));
         observer.wait();
@@ -233,7 +375,7 @@ pub fn enter_key<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, k
 
 pub fn enter_keys<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, text: &str) {
     wait_for_draw(widget, || {
-        focus(widget);
+        gtk_test::focus(widget);
         let mut enigo = Enigo::new();
         for char in text.chars() {
             let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
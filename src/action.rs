@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gio::{ActionMapExt, SimpleAction, SimpleActionGroup};
+use glib::{IsA, Variant, VariantTy};
+use gtk::{GtkApplicationExt, WidgetExt};
+
+use crate::application::application;
+use crate::core::StreamHandle;
+
+/// Builder to create a group of actions emitting messages to a stream when activated, e.g. from
+/// a menu item whose `action_name` is `win.quit`:
+///
+/// ```ignore
+/// ActionGroupBuilder::new(relm.stream())
+///     .action("quit", Quit)
+///     .accels("quit", &["<Primary>q"])
+///     .insert("win", &window);
+/// ```
+#[must_use]
+pub struct ActionGroupBuilder<MSG> {
+    accels: Vec<(String, Vec<String>)>,
+    group: SimpleActionGroup,
+    stream: StreamHandle<MSG>,
+}
+
+impl<MSG: 'static> ActionGroupBuilder<MSG> {
+    /// Create a builder for actions emitting their messages to `stream`.
+    pub fn new(stream: &StreamHandle<MSG>) -> Self {
+        Self {
+            accels: vec![],
+            group: SimpleActionGroup::new(),
+            stream: stream.clone(),
+        }
+    }
+
+    /// Add an action emitting `msg` when activated.
+    pub fn action(self, name: &str, msg: MSG) -> Self
+        where MSG: Clone,
+    {
+        self.add(name, None, move |_| Some(msg.clone()))
+    }
+
+    /// Add an action with a parameter of type `parameter_type`, which emits the message
+    /// returned by `callback` when activated.
+    /// No message is emitted if `callback` returns `None`, e.g. when the parameter has
+    /// an unexpected value.
+    pub fn action_with_parameter<CALLBACK>(self, name: &str, parameter_type: &VariantTy, callback: CALLBACK) -> Self
+        where CALLBACK: Fn(&Variant) -> Option<MSG> + 'static,
+    {
+        self.add(name, Some(parameter_type), move |parameter| parameter.and_then(&callback))
+    }
+
+    /// Set the keyboard accelerators of the action `name`, e.g. `<Primary>q`.
+    /// The accelerators are registered to the application started by
+    /// [`run_application()`](fn.run_application.html) when the group is inserted.
+    pub fn accels(mut self, name: &str, accels: &[&str]) -> Self {
+        self.accels.push((name.to_string(), accels.iter().map(|accel| accel.to_string()).collect()));
+        self
+    }
+
+    /// Get the action group, without inserting it in a widget.
+    pub fn build(self) -> SimpleActionGroup {
+        self.group
+    }
+
+    /// Insert the action group in `widget` with the `prefix`, so that the actions are available
+    /// as `prefix.name` to this widget and its children.
+    /// Return the action group.
+    pub fn insert<WIDGET: IsA<gtk::Widget>>(self, prefix: &str, widget: &WIDGET) -> SimpleActionGroup {
+        widget.insert_action_group(prefix, Some(&self.group));
+        if !self.accels.is_empty() {
+            match application() {
+                Some(application) =>
+                    for (name, accels) in &self.accels {
+                        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+                        application.set_accels_for_action(&format!("{}.{}", prefix, name), &accels);
+                    },
+                None => log::warn!("Accelerators of the {} actions ignored: no application is running", prefix),
+            }
+        }
+        self.group
+    }
+
+    fn add<CALLBACK>(self, name: &str, parameter_type: Option<&VariantTy>, callback: CALLBACK) -> Self
+        where CALLBACK: Fn(Option<&Variant>) -> Option<MSG> + 'static,
+    {
+        let action = SimpleAction::new(name, parameter_type);
+        let stream = self.stream.clone();
+        let _ = action.connect_activate(move |_, parameter| {
+            if let Some(msg) = callback(parameter) {
+                // The actions can outlive the widget.
+                let _ = stream.try_emit(msg);
+            }
+        });
+        self.group.add_action(&action);
+        self
+    }
+}
@@ -0,0 +1,125 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use glib::{Continue, IsA, ObjectExt};
+use gtk::{AdjustmentExt, Orientation};
+
+use crate::StreamHandle;
+
+// Set on the adjustment while its value is changed by relm, so that this change is not reported
+// as a change made by the user.
+const PROGRAMMATIC_CHANGE_KEY: &str = "relm-programmatic-change";
+
+/// Get the horizontal or vertical adjustment of a scrollable widget, like a
+/// `gtk::ScrolledWindow`, a `gtk::TextView` or a `gtk::TreeView`.
+///
+/// Returns `None` if `widget` has no such adjustment.
+pub fn get_adjustment<WIDGET: IsA<glib::Object>>(widget: &WIDGET, orientation: Orientation)
+    -> Option<gtk::Adjustment>
+{
+    let property =
+        match orientation {
+            Orientation::Horizontal => "hadjustment",
+            _ => "vadjustment",
+        };
+    widget.get_property(property).ok()
+        .and_then(|value| value.get::<gtk::Adjustment>().ok())
+        .and_then(|adjustment| adjustment)
+}
+
+/// Set the value of the horizontal or vertical adjustment of `widget`, which is what the `value`
+/// of `hadjustment: { .. }` and `vadjustment: { .. }` in the `view!` macro does.
+///
+/// The handlers connected by
+/// [`connect_adjustment_value_changed()`](fn.connect_adjustment_value_changed.html) are not
+/// called for this change.
+pub fn set_adjustment_value<WIDGET: IsA<glib::Object>>(widget: &WIDGET, orientation: Orientation, value: f64) {
+    if let Some(adjustment) = get_adjustment(widget, orientation) {
+        set_value(&adjustment, value);
+    }
+}
+
+/// Send the message returned by `map` for the new value of the horizontal or vertical adjustment
+/// of `widget` to `stream` when it is changed by the user, which is what the `on_user_change` of
+/// `hadjustment: { .. }` and `vadjustment: { .. }` in the `view!` macro does.
+///
+/// The changes made by [`set_adjustment_value()`](fn.set_adjustment_value.html) and
+/// [`scroll_to_end()`](fn.scroll_to_end.html) don't send a message.
+///
+/// Since a scrollable widget gets the adjustments of the `gtk::ScrolledWindow` it is added to,
+/// this must be called after it is added.
+pub fn connect_adjustment_value_changed<WIDGET, MSG, CALLBACK>(widget: &WIDGET, orientation: Orientation,
+    stream: &StreamHandle<MSG>, map: CALLBACK)
+    where WIDGET: IsA<glib::Object>,
+          CALLBACK: Fn(f64) -> MSG + 'static,
+          MSG: 'static,
+{
+    if let Some(adjustment) = get_adjustment(widget, orientation) {
+        let stream = stream.clone();
+        let _ = adjustment.connect_value_changed(move |adjustment| {
+            // The data under this key is always set by this module, with this type.
+            let is_programmatic = unsafe { adjustment.get_data::<bool>(PROGRAMMATIC_CHANGE_KEY) }.is_some();
+            if !is_programmatic {
+                stream.emit(map(adjustment.get_value()));
+            }
+        });
+    }
+}
+
+/// Check whether the vertical adjustment of `widget` is at its end, e.g. to only scroll a log
+/// view to the new text when the user did not scroll up.
+pub fn is_scrolled_to_end<WIDGET: IsA<glib::Object>>(widget: &WIDGET) -> bool {
+    get_adjustment(widget, Orientation::Vertical)
+        .map(|adjustment| adjustment.get_value() >= end_value(&adjustment))
+        .unwrap_or(true)
+}
+
+/// Scroll `widget`, like a `gtk::ScrolledWindow`, to the end of its vertical adjustment.
+///
+/// Since the content added in the same update, like the text appended to a `gtk::TextView`, is
+/// only measured later, the end is set again when the main loop is idle.
+pub fn scroll_to_end<WIDGET: IsA<glib::Object>>(widget: &WIDGET) {
+    if let Some(adjustment) = get_adjustment(widget, Orientation::Vertical) {
+        set_value(&adjustment, end_value(&adjustment));
+        let adjustment = adjustment.downgrade();
+        let _ = glib::idle_add_local(move || {
+            if let Some(adjustment) = adjustment.upgrade() {
+                set_value(&adjustment, end_value(&adjustment));
+            }
+            Continue(false)
+        });
+    }
+}
+
+fn end_value(adjustment: &gtk::Adjustment) -> f64 {
+    adjustment.get_upper() - adjustment.get_page_size()
+}
+
+fn set_value(adjustment: &gtk::Adjustment, value: f64) {
+    unsafe {
+        adjustment.set_data(PROGRAMMATIC_CHANGE_KEY, true);
+    }
+    adjustment.set_value(value);
+    unsafe {
+        let _ = adjustment.steal_data::<bool>(PROGRAMMATIC_CHANGE_KEY);
+    }
+}
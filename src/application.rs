@@ -0,0 +1,111 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::env;
+use std::rc::Rc;
+
+use gio::{ApplicationExt, ApplicationFlags};
+use gio::prelude::ApplicationExtManual;
+use glib::Cast;
+use gtk::GtkWindowExt;
+use gtk::prelude::GtkWindowExtManual;
+
+use crate::{Component, Error, Widget, init};
+
+/// Get the application started by [`run_application()`](fn.run_application.html), e.g. to add
+/// actions to it from the `model()` method of the widget.
+/// Return `None` when not running an application.
+pub fn application() -> Option<gtk::Application> {
+    gio::Application::get_default()
+        .and_then(|application| application.downcast().ok())
+}
+
+/// Quit the application started by [`run_application()`](fn.run_application.html) or, when not
+/// running an application, the main loop started by [`run()`](fn.run.html).
+pub fn quit() {
+    match application() {
+        Some(application) => application.quit(),
+        None => gtk::main_quit(),
+    }
+}
+
+/// Create a `gtk::Application` with `application_id` and `flags` and run it.
+/// The `WIDGET` is created when the application is first activated and its root widget, which
+/// must be a `gtk::Window`, is added to the application: the application quits when this window
+/// is closed or when [`quit()`](fn.quit.html) is called.
+/// The command-line arguments of the process are given to the application.
+/// Return the exit status of the application.
+pub fn run_application<WIDGET>(application_id: &str, flags: ApplicationFlags, model_param: WIDGET::ModelParam)
+    -> Result<i32, Error>
+    where WIDGET: Widget + 'static,
+{
+    let application = gtk::Application::new(Some(application_id), flags)?;
+    application.set_default();
+
+    let component: Rc<RefCell<Option<Component<WIDGET>>>> = Rc::new(RefCell::new(None));
+    let error = Rc::new(RefCell::new(None));
+    let model_param = RefCell::new(Some(model_param));
+    {
+        let component = component.clone();
+        let error = error.clone();
+        let _ = application.connect_activate(move |application| {
+            // The application is activated again when another instance is started.
+            if let Some(ref component) = *component.borrow() {
+                if let Ok(window) = component.widget().clone().upcast::<gtk::Widget>().downcast::<gtk::Window>() {
+                    window.present();
+                }
+                return;
+            }
+            let model_param = match model_param.borrow_mut().take() {
+                Some(model_param) => model_param,
+                None => return,
+            };
+            match init::<WIDGET>(model_param) {
+                Ok(new_component) => {
+                    match new_component.widget().clone().upcast::<gtk::Widget>().downcast::<gtk::Window>() {
+                        Ok(window) => window.set_application(Some(application)),
+                        Err(_) => {
+                            *error.borrow_mut() =
+                                Some(glib::glib_bool_error!("The root widget of an application must be a gtk::Window").into());
+                            application.quit();
+                        },
+                    }
+                    *component.borrow_mut() = Some(new_component);
+                },
+                Err(init_error) => {
+                    *error.borrow_mut() = Some(init_error.into());
+                    application.quit();
+                },
+            }
+        });
+    }
+
+    let args: Vec<String> = env::args().collect();
+    let status = application.run(&args);
+    // Destroy the widget before the application.
+    let _ = component.borrow_mut().take();
+    let error = error.borrow_mut().take();
+    match error {
+        Some(error) => Err(error),
+        None => Ok(status),
+    }
+}
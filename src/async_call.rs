@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use fragile::Fragile;
+
+use crate::core::StreamHandle;
+
+/// Callback given to the asynchronous gio methods by [`connect_async()`](fn.connect_async.html).
+pub type AsyncCallback<T> = Box<dyn FnOnce(Result<T, glib::Error>) + Send + 'static>;
+
+/// Start an asynchronous gio call with `initiator` and emit the message returned by `success`, or
+/// by `failure` on error, when it completes.
+/// `initiator` receives the callback to give to the `*_async()` method:
+///
+/// ```ignore
+/// relm::connect_async(relm.stream(), |callback| file.load_contents_async(None::<&gio::Cancellable>, callback),
+///     FileRead, ReadError);
+/// ```
+///
+/// The result is discarded if the stream was dropped in the meantime.
+/// The callback must be called from the thread where the stream lives.
+pub fn connect_async<T, MSG, STREAM, INITIATOR, SUCCESS, FAILURE>(stream: &STREAM, initiator: INITIATOR,
+    success: SUCCESS, failure: FAILURE)
+    where INITIATOR: FnOnce(AsyncCallback<T>),
+          SUCCESS: FnOnce(T) -> MSG + 'static,
+          FAILURE: FnOnce(glib::Error) -> MSG + 'static,
+          MSG: 'static,
+          STREAM: AsRef<StreamHandle<MSG>>,
+          T: 'static,
+{
+    // The gio callbacks require Send, while they are called from the main thread.
+    let data = Fragile::new((stream.as_ref().clone(), success, failure));
+    initiator(Box::new(move |result| {
+        let (stream, success, failure) = data.into_inner();
+        let msg =
+            match result {
+                Ok(value) => success(value),
+                Err(error) => failure(error),
+            };
+        let _ = stream.try_emit(msg);
+    }));
+}
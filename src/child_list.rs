@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::any::Any;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use glib::{Cast, IsA};
+use gtk::{BoxExt, ContainerExt};
+
+struct Row {
+    data: Box<dyn Any>,
+    key: Box<dyn Any>,
+    widget: gtk::Widget,
+}
+
+/// Children of a container generated from a collection, like those created by a `for` loop in
+/// the `view!` macro.
+///
+/// Every row is identified by a key: rows whose key is still present in the collection are kept
+/// and updated when the list is synchronized, while the others are removed from the container.
+pub struct ChildList {
+    // Number of rows, shared with the lists placed after this one in the same container.
+    len: Rc<Cell<usize>>,
+    // Number of rows of the lists placed before this one in the same container.
+    previous_lens: Vec<Rc<Cell<usize>>>,
+    // Number of children placed before the rows, without the rows of the previous lists.
+    static_offset: i32,
+    rows: Vec<Row>,
+}
+
+impl ChildList {
+    /// Create an empty list of children.
+    /// In a `gtk::Box`, the rows are positioned after the children that `container` already has.
+    pub fn new<CONTAINER: IsA<gtk::Container>>(container: &CONTAINER) -> Self {
+        Self::with_previous_lens(container, vec![])
+    }
+
+    /// Create an empty list of children placed after the rows of `previous`, which is already a
+    /// list of `container`.
+    /// In a `gtk::Box`, the rows stay positioned after those of `previous` when the number of rows
+    /// of `previous` changes.
+    pub fn new_after<CONTAINER: IsA<gtk::Container>>(container: &CONTAINER, previous: &ChildList) -> Self {
+        let mut previous_lens = previous.previous_lens.clone();
+        previous_lens.push(previous.len.clone());
+        Self::with_previous_lens(container, previous_lens)
+    }
+
+    fn with_previous_lens<CONTAINER: IsA<gtk::Container>>(container: &CONTAINER, previous_lens: Vec<Rc<Cell<usize>>>)
+        -> Self
+    {
+        let children = container.as_ref().get_children().len();
+        let previous_rows: usize = previous_lens.iter().map(|len| len.get()).sum();
+        ChildList {
+            len: Rc::new(Cell::new(0)),
+            previous_lens,
+            static_offset: children.saturating_sub(previous_rows) as i32,
+            rows: vec![],
+        }
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check whether the list has no row.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Synchronize the rows with `items`.
+    ///
+    /// `create` is called for the new keys: it must add a widget to `container` and return it,
+    /// with the data to keep alongside it (its widgets and components).
+    /// It gets a copy of the key, so that it can be used in the messages of the row.
+    /// `update` is called with this data for the keys that were already present, and the rows
+    /// whose keys are absent from `items` are removed from `container`.
+    pub fn sync<CONTAINER, ITEMS, KEY, ITEM, DATA, CREATE, UPDATE>(&mut self, container: &CONTAINER, items: ITEMS,
+        mut create: CREATE, mut update: UPDATE)
+        where CONTAINER: IsA<gtk::Container>,
+              ITEMS: IntoIterator<Item=(KEY, ITEM)>,
+              KEY: Clone + PartialEq + 'static,
+              DATA: 'static,
+              CREATE: FnMut(KEY, ITEM) -> (gtk::Widget, DATA),
+              UPDATE: FnMut(&DATA, &KEY, ITEM),
+    {
+        let container = container.as_ref();
+        let mut old_rows: Vec<Option<Row>> = self.rows.drain(..).map(Some).collect();
+        let mut rows = vec![];
+        for (key, item) in items {
+            let position = old_rows.iter().position(|row| {
+                row.as_ref().and_then(|row| row.key.downcast_ref::<KEY>()) == Some(&key)
+            });
+            let row =
+                match position.and_then(|position| old_rows[position].take()) {
+                    Some(row) => {
+                        if let Some(data) = row.data.downcast_ref::<DATA>() {
+                            update(data, &key, item);
+                        }
+                        row
+                    },
+                    None => {
+                        let (widget, data) = create(key.clone(), item);
+                        Row {
+                            data: Box::new(data),
+                            key: Box::new(key),
+                            widget,
+                        }
+                    },
+                };
+            rows.push(row);
+        }
+        for row in old_rows.into_iter().flatten() {
+            container.remove(&row.widget);
+        }
+        self.rows = rows;
+        self.len.set(self.rows.len());
+        self.reorder(container);
+    }
+
+    fn reorder(&self, container: &gtk::Container) {
+        if let Some(gtk_box) = container.downcast_ref::<gtk::Box>() {
+            // The previous lists might have grown or shrunk since this list was created.
+            let previous_rows: usize = self.previous_lens.iter().map(|len| len.get()).sum();
+            let offset = self.static_offset + previous_rows as i32;
+            for (index, row) in self.rows.iter().enumerate() {
+                gtk_box.reorder_child(&row.widget, offset + index as i32);
+            }
+        }
+        else {
+            let children: Vec<_> = container.get_children().into_iter()
+                .filter(|child| self.rows.iter().any(|row| row.widget == *child))
+                .collect();
+            let in_order = children.iter().zip(&self.rows).all(|(child, row)| *child == row.widget);
+            if !in_order {
+                for row in &self.rows {
+                    container.remove(&row.widget);
+                    container.add(&row.widget);
+                }
+            }
+        }
+    }
+}
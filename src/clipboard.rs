@@ -0,0 +1,82 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Access the clipboard, receiving the pasted content as a message.
+//!
+//! The `request_*()` functions don't block: the message is sent when the clipboard content is
+//! available, which can take time when it comes from another application.
+
+use gdk::Atom;
+use gdk_pixbuf::Pixbuf;
+
+use crate::core::StreamHandle;
+
+fn clipboard() -> gtk::Clipboard {
+    gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD)
+}
+
+/// Request the text of the clipboard and send the message returned by `map` for it to `stream`.
+/// The text is `None` if the clipboard doesn't contain text.
+pub fn request_text<MSG, CALLBACK>(stream: &StreamHandle<MSG>, map: CALLBACK)
+    where CALLBACK: FnOnce(Option<String>) -> MSG + 'static,
+          MSG: 'static,
+{
+    let stream = stream.clone();
+    clipboard().request_text(move |_, text| {
+        let _ = stream.try_emit(map(text.map(ToString::to_string)));
+    });
+}
+
+/// Request the image of the clipboard and send the message returned by `map` for it to `stream`.
+/// The image is `None` if the clipboard doesn't contain an image.
+pub fn request_image<MSG, CALLBACK>(stream: &StreamHandle<MSG>, map: CALLBACK)
+    where CALLBACK: FnOnce(Option<Pixbuf>) -> MSG + 'static,
+          MSG: 'static,
+{
+    let stream = stream.clone();
+    clipboard().request_contents(&Atom::intern("image/png"), move |_, data| {
+        let _ = stream.try_emit(map(data.get_pixbuf()));
+    });
+}
+
+/// Request the URIs of the clipboard, like the files copied in a file manager, and send the
+/// message returned by `map` for them to `stream`.
+/// The list is empty if the clipboard doesn't contain URIs.
+pub fn request_uris<MSG, CALLBACK>(stream: &StreamHandle<MSG>, map: CALLBACK)
+    where CALLBACK: FnOnce(Vec<String>) -> MSG + 'static,
+          MSG: 'static,
+{
+    let stream = stream.clone();
+    clipboard().request_contents(&Atom::intern("text/uri-list"), move |_, data| {
+        let uris = data.get_uris().into_iter().map(Into::into).collect();
+        let _ = stream.try_emit(map(uris));
+    });
+}
+
+/// Set the text of the clipboard.
+pub fn set_text(text: &str) {
+    clipboard().set_text(text);
+}
+
+/// Set the image of the clipboard.
+pub fn set_image(image: &Pixbuf) {
+    clipboard().set_image(image);
+}
@@ -19,10 +19,17 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use glib::Cast;
+use gtk::{ContainerExt, WidgetExt};
+
 use super::{
     EventStream,
     StreamHandle,
     Widget,
+    WidgetTest,
 };
 
 /// Widget that was added by the `ContainerWidget::add_widget()` method.
@@ -35,6 +42,7 @@ use super::{
 /// [communication-attribute example](https://github.com/antoyo/relm/blob/master/relm-examples/tests/communication-attribute.rs)).
 #[must_use]
 pub struct Component<WIDGET: Widget> {
+    state: Weak<RefCell<WIDGET>>,
     stream: EventStream<WIDGET::Msg>,
     widget: WIDGET::Root,
 }
@@ -43,11 +51,35 @@ impl<WIDGET: Widget> Component<WIDGET> {
     #[doc(hidden)]
     pub fn new(stream: EventStream<WIDGET::Msg>, widget: WIDGET::Root) -> Self {
         Component {
+            state: Weak::new(),
             stream,
             widget,
         }
     }
 
+    // Keep a reference to the widget owned by the stream of the component.
+    pub(crate) fn set_state(&mut self, state: Weak<RefCell<WIDGET>>) {
+        self.state = state;
+    }
+
+    /// Destroy the component: [`on_destroy()`](trait.Update.html#method.on_destroy) is called,
+    /// then its event stream is closed, so that the widget doesn't process messages anymore, the
+    /// signal handlers connected by the `connect!` macros to send messages to this component are
+    /// disconnected, and its root widget is removed from its parent.
+    /// Handlers connected directly with the GTK+ `connect_*()` methods are not disconnected.
+    /// Dropping the component also closes its stream, but keeps the signal handlers and the root
+    /// widget.
+    pub fn destroy(self) {
+        self.stream.close();
+        self.stream.disconnect_signal_handlers();
+        let widget: gtk::Widget = self.widget.clone().upcast();
+        if let Some(parent) = widget.get_parent() {
+            if let Ok(container) = parent.downcast::<gtk::Container>() {
+                container.remove(&widget);
+            }
+        }
+    }
+
     /// Emit a message of the widget stream.
     pub fn emit(&self, msg: WIDGET::Msg) {
         self.stream.emit(msg);
@@ -70,3 +102,21 @@ impl<WIDGET: Widget> Component<WIDGET> {
         &self.widget
     }
 }
+
+impl<WIDGET: Widget + WidgetTest> Component<WIDGET> {
+    /// Get the widgets of the component, like those named in the `view!` macro.
+    ///
+    /// The returned structure holds new references to the widgets, so that no borrow of the
+    /// component is kept while the main loop runs.
+    ///
+    /// ## Panics
+    /// Panics if the component was dropped or if it is currently updating, i.e. when called from
+    /// the [`update()`](trait.Update.html#tymethod.update) method of this same component.
+    pub fn widgets(&self) -> WIDGET::Widgets {
+        let state = self.state.upgrade()
+            .expect("Trying to call widgets() on a dropped component");
+        let widget = state.try_borrow()
+            .unwrap_or_else(|_| panic!("Trying to call widgets() on a component while it is updating"));
+        widget.get_widgets()
+    }
+}
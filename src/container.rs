@@ -19,8 +19,9 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-use glib::{Cast, IsA, Object};
-use gtk::{ContainerExt, WidgetExt};
+use glib::{Cast, IsA, Object, ObjectExt, ToValue};
+use glib::translate::ToGlibPtr;
+use gtk::{ActionBarExt, BoxExt, ContainerExt, HeaderBarExt, PackType, WidgetExt};
 
 use crate::state::EventStream;
 use super::{Component, DisplayVariant, StreamHandle, create_widget, init_component};
@@ -56,10 +57,11 @@ impl<WIDGET: Container + Widget> ContainerComponent<WIDGET> {
         where CHILDWIDGET: Widget + 'static,
               WIDGET::Container: ContainerExt + IsA<gtk::Widget> + IsA<Object>,
     {
-        let (component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
+        let (mut component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
         let container = WIDGET::add_widget(self, &component);
         widget.on_add(container);
-        init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        let state = init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        component.set_state(state);
         component
     }
 
@@ -153,13 +155,14 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
               CHILDWIDGET::Msg: DisplayVariant + 'static,
               CHILDWIDGET::Root: IsA<gtk::Widget> + IsA<Object> + WidgetExt,
     {
-        let (component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
+        let (mut component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
         let container = widget.container().clone();
         let containers = widget.other_containers();
         let root = widget.root();
         self.add(&root);
         widget.on_add(self.clone());
-        init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        let state = init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        component.set_state(state);
         ContainerComponent::new(component, container, containers)
     }
 
@@ -169,10 +172,11 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
               CHILDWIDGET::Msg: DisplayVariant + 'static,
               CHILDWIDGET::Root: IsA<gtk::Widget> + IsA<Object> + WidgetExt,
     {
-        let (component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
+        let (mut component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
         self.add(component.widget());
         widget.on_add(self.clone());
-        init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        let state = init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+        component.set_state(state);
         component
     }
 
@@ -185,3 +189,60 @@ impl<W: Clone + ContainerExt + IsA<gtk::Widget> + IsA<Object>> ContainerWidget f
         self.remove(component.widget());
     }
 }
+
+/// Add `widget` at the start or the end of `container`, which must be a `gtk::Box`, a
+/// `gtk::HeaderBar` or a `gtk::ActionBar`.
+/// In a `gtk::Box`, the widget is packed with the default child properties: it is not expanded,
+/// but filled, and without padding.
+pub fn pack<CONTAINER, WIDGET>(container: &CONTAINER, widget: &WIDGET, pack_type: PackType)
+    where CONTAINER: IsA<gtk::Container>,
+          WIDGET: IsA<gtk::Widget>,
+{
+    let container = container.as_ref();
+    let end = pack_type == PackType::End;
+    if let Some(gtk_box) = container.downcast_ref::<gtk::Box>() {
+        if end {
+            gtk_box.pack_end(widget, false, true, 0);
+        }
+        else {
+            gtk_box.pack_start(widget, false, true, 0);
+        }
+    }
+    else if let Some(header_bar) = container.downcast_ref::<gtk::HeaderBar>() {
+        if end {
+            header_bar.pack_end(widget);
+        }
+        else {
+            header_bar.pack_start(widget);
+        }
+    }
+    else if let Some(action_bar) = container.downcast_ref::<gtk::ActionBar>() {
+        if end {
+            action_bar.pack_end(widget);
+        }
+        else {
+            action_bar.pack_start(widget);
+        }
+    }
+    else {
+        panic!("Cannot pack a widget in a {}, only in a gtk::Box, a gtk::HeaderBar or a gtk::ActionBar",
+            container.get_type());
+    }
+}
+
+/// Set the child property `name` of `child` in `container`.
+/// The value is converted to the type of the property when they differ, e.g. from `i32` to `u32`.
+pub fn set_child_property<CONTAINER, CHILD>(container: &CONTAINER, child: &CHILD, name: &str, value: &dyn ToValue)
+    where CONTAINER: IsA<gtk::Container>,
+          CHILD: IsA<gtk::Widget>,
+{
+    let value = value.to_value();
+    unsafe {
+        gtk_sys::gtk_container_child_set_property(
+            container.as_ref().to_glib_none().0,
+            child.as_ref().to_glib_none().0,
+            name.to_glib_none().0,
+            value.to_glib_none().0,
+        );
+    }
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gio::{Cancellable, CancellableExt};
+
+/// A token to tell worker threads to stop an operation, for instance because the component that
+/// started it was destroyed.
+///
+/// The token is cheap to clone and can be sent to other threads: the clones share the same state.
+/// Workers can poll [`is_cancelled()`](struct.CancellationToken.html#method.is_cancelled) or pass
+/// [`cancellable()`](struct.CancellationToken.html#method.cancellable) to the gio asynchronous
+/// functions.
+/// The senders of the channels, like [`Sender`](struct.Sender.html), can also be bound to a
+/// token so that nothing is sent after it is cancelled.
+#[derive(Clone)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    cancellable: Cancellable,
+    cancelled: AtomicBool,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        CancellationToken {
+            inner: Arc::new(Inner {
+                cancellable: Cancellable::new(),
+                cancelled: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Cancel the token and its `gio::Cancellable`.
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            self.inner.cancellable.cancel();
+        }
+    }
+
+    /// Get the `gio::Cancellable` that is cancelled along with this token.
+    pub fn cancellable(&self) -> &Cancellable {
+        &self.inner.cancellable
+    }
+
+    /// Check if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    // Check if this is the last clone of the token.
+    pub(crate) fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::atomic::Ordering;
+
+use glib::MainContext;
+
+use super::{CancellationToken, Channel, ChannelReceiver, ClosedFlag, SendWakers, is_cancelled};
+
+/// A wrapper over a `crossbeam_channel::Sender` to wakeup the glib event loop when sending a
+/// message.
+/// In contrast to `Sender`, it is `Sync`, so it can be shared between threads.
+pub struct CrossbeamSender<MSG> {
+    cancellation: Option<CancellationToken>,
+    closed: ClosedFlag,
+    context: MainContext,
+    sender: crossbeam_channel::Sender<MSG>,
+}
+
+impl<MSG> Clone for CrossbeamSender<MSG> {
+    fn clone(&self) -> Self {
+        Self {
+            cancellation: self.cancellation.clone(),
+            closed: self.closed.clone(),
+            context: self.context.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<MSG> CrossbeamSender<MSG> {
+    /// Send a message and wakeup the event loop.
+    /// Do nothing if the `CancellationToken` given to
+    /// [`with_cancellation()`](struct.CrossbeamSender.html#method.with_cancellation) was cancelled.
+    /// Return an error if the channel was closed.
+    pub fn send(&self, msg: MSG) -> Result<(), crossbeam_channel::SendError<MSG>> {
+        if is_cancelled(&self.cancellation) {
+            return Ok(());
+        }
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(crossbeam_channel::SendError(msg));
+        }
+        self.sender.send(msg)?;
+        self.context.wakeup();
+        Ok(())
+    }
+
+    /// Bind the sender to `token`, so that the messages sent after it is cancelled are dropped.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+}
+
+impl<MSG: 'static> Channel<MSG> {
+    /// Create a new channel based on crossbeam with a callback that will be called when a message
+    /// is received.
+    pub fn new_crossbeam<CALLBACK: FnMut(MSG) + 'static>(callback: CALLBACK) -> (Self, CrossbeamSender<MSG>) {
+        let context = MainContext::default();
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let channel = Self::from_receiver(&context, ChannelReceiver::Crossbeam(receiver), callback, None,
+            SendWakers::default());
+        let closed = channel.closed.clone();
+        (channel, CrossbeamSender {
+            cancellation: None,
+            closed,
+            context,
+            sender,
+        })
+    }
+}
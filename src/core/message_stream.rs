@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use futures::Stream;
+
+use super::{EventStream, ObserverHandle};
+
+struct Buffer<MSG> {
+    // None when unbounded.
+    capacity: Option<usize>,
+    closed: bool,
+    messages: VecDeque<MSG>,
+    waker: Option<Waker>,
+}
+
+impl<MSG> Buffer<MSG> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// Owned by the observer: closes the buffer when the observer is dropped, i.e. when the
+// `EventStream` is dropped.
+struct BufferWriter<MSG> {
+    buffer: Rc<RefCell<Buffer<MSG>>>,
+}
+
+impl<MSG> Drop for BufferWriter<MSG> {
+    fn drop(&mut self) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.closed = true;
+        buffer.wake();
+    }
+}
+
+/// A `futures::Stream` of the messages emitted on an `EventStream`.
+///
+/// Created by [`EventStream::observe_stream()`](struct.EventStream.html#method.observe_stream).
+/// The stream ends when the `EventStream` is dropped and dropping it removes its observer.
+pub struct MessageStream<MSG> {
+    buffer: Rc<RefCell<Buffer<MSG>>>,
+    _observer: ObserverHandle<MSG>,
+}
+
+impl<MSG: Clone + 'static> MessageStream<MSG> {
+    pub(super) fn new(stream: &EventStream<MSG>, capacity: Option<usize>) -> Self {
+        let buffer = Rc::new(RefCell::new(Buffer {
+            capacity,
+            closed: false,
+            messages: VecDeque::new(),
+            waker: None,
+        }));
+        let writer = BufferWriter {
+            buffer: buffer.clone(),
+        };
+        let observer = stream.observe(move |msg| {
+            let mut buffer = writer.buffer.borrow_mut();
+            if let Some(capacity) = buffer.capacity {
+                if buffer.messages.len() >= capacity {
+                    buffer.messages.pop_front();
+                }
+            }
+            buffer.messages.push_back(msg.clone());
+            buffer.wake();
+        });
+        Self {
+            buffer,
+            _observer: observer.remove_on_drop(),
+        }
+    }
+}
+
+impl<MSG> Stream for MessageStream<MSG> {
+    type Item = MSG;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<MSG>> {
+        let mut buffer = self.buffer.borrow_mut();
+        if let Some(msg) = buffer.messages.pop_front() {
+            Poll::Ready(Some(msg))
+        }
+        else if buffer.closed {
+            Poll::Ready(None)
+        }
+        else {
+            buffer.waker = Some(context.waker().clone());
+            Poll::Pending
+        }
+    }
+}
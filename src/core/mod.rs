@@ -32,27 +32,72 @@
     unused_qualifications,
 )]
 
-mod source;
+mod cancellation;
+#[cfg(feature = "crossbeam")]
+mod crossbeam;
+#[cfg(feature = "async")]
+mod message_stream;
+mod oneshot;
+mod progress;
+#[cfg(feature = "async")]
+mod sink;
+pub(crate) mod source;
 
-use std::cell::RefCell;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::marker::PhantomData;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::{Rc, Weak};
-use std::sync::mpsc::{self, Receiver, SendError};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SendError, TryRecvError, TrySendError};
+use std::task::Waker;
 
-use self::source::{SourceFuncs, new_source, source_get};
+pub use self::cancellation::CancellationToken;
+#[cfg(feature = "crossbeam")]
+pub use self::crossbeam::CrossbeamSender;
+#[cfg(feature = "async")]
+pub use self::message_stream::MessageStream;
+pub use self::oneshot::{OneshotReceiver, OneshotSender, oneshot, oneshot_with_context};
+pub use self::progress::{ProgressSender, progress_channel};
+#[cfg(feature = "async")]
+pub use self::sink::SyncSink;
+use self::source::{
+    SourceFuncs,
+    new_source_full,
+    set_name,
+    set_priority,
+    source_get,
+};
 
 use glib::{
     MainContext,
+    ObjectExt,
+    Priority,
+    SignalHandlerId,
     Source,
-    SourceId,
+    WeakRef,
 };
+use glib::translate::from_glib;
+
+#[cfg(feature = "trace")]
+use crate::trace::{StreamTracer, TraceKind};
 
 /// Handle to a EventStream to emit messages.
 pub struct StreamHandle<MSG> {
     stream: Weak<RefCell<_EventStream<MSG>>>,
 }
 
+impl<MSG> AsRef<StreamHandle<MSG>> for StreamHandle<MSG> {
+    fn as_ref(&self) -> &StreamHandle<MSG> {
+        self
+    }
+}
+
 impl<MSG> Clone for StreamHandle<MSG> {
     fn clone(&self) -> Self {
         Self {
@@ -61,6 +106,19 @@ impl<MSG> Clone for StreamHandle<MSG> {
     }
 }
 
+impl<MSG> Debug for StreamHandle<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.stream.upgrade() {
+            Some(stream) =>
+                match stream.try_borrow() {
+                    Ok(stream) => stream.fmt_debug("StreamHandle", formatter),
+                    Err(_) => formatter.write_str("StreamHandle { .. }"),
+                },
+            None => formatter.write_str("StreamHandle { dropped }"),
+        }
+    }
+}
+
 impl<MSG> StreamHandle<MSG> {
     fn new(stream: Weak<RefCell<_EventStream<MSG>>>) -> Self {
         Self {
@@ -73,77 +131,626 @@ impl<MSG> StreamHandle<MSG> {
         self.clone()
     }
 
+    /// Get the main context from which the messages are dispatched.
+    /// Return `None` if the stream is detached or was dropped.
+    pub fn context(&self) -> Option<MainContext> {
+        self.stream.upgrade().and_then(|stream| stream.borrow().context.clone())
+    }
+
+    /// Check if the `EventStream` is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.stream.strong_count() > 0
+    }
+
+    /// Get a strong handle, whose methods cannot fail while it exists.
+    /// Return `None` if the `EventStream` was dropped.
+    pub fn upgrade(&self) -> Option<StrongStreamHandle<MSG>> {
+        self.stream.upgrade().map(|stream| StrongStreamHandle {
+            stream,
+        })
+    }
+
     /// Send the `event` message to the stream and the observers.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    /// In signal handlers that might be called after the widget was destroyed, prefer
+    /// [`try_emit()`](struct.StreamHandle.html#method.try_emit).
     pub fn emit(&self, msg: MSG) {
+        if self.try_emit(msg).is_err() {
+            panic!("Trying to call emit() on a dropped EventStream");
+        }
+    }
+
+    /// Send the `event` message to the stream and the observers.
+    /// If the `EventStream` was dropped, the message is given back in the error.
+    pub fn try_emit(&self, msg: MSG) -> Result<(), EmitError<MSG>> {
+        self.try_emit_with_priority(msg, MessagePriority::Normal)
+    }
+
+    /// Send the `event` message to the stream and the observers, to be dispatched before the
+    /// messages of lower `priority`.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn emit_with_priority(&self, msg: MSG, priority: MessagePriority) {
+        if self.try_emit_with_priority(msg, priority).is_err() {
+            panic!("Trying to call emit_with_priority() on a dropped EventStream");
+        }
+    }
+
+    /// Send the `event` message to the stream and the observers, to be dispatched before the
+    /// messages of lower `priority`.
+    /// If the `EventStream` was dropped, the message is given back in the error.
+    pub fn try_emit_with_priority(&self, msg: MSG, priority: MessagePriority) -> Result<(), EmitError<MSG>> {
         if let Some(ref stream) = self.stream.upgrade() {
-            emit(stream, msg);
+            emit(stream, msg, priority);
+            Ok(())
         }
         else {
-            panic!("Trying to call emit() on a dropped EventStream");
+            Err(EmitError(msg))
         }
     }
 
+    /// Dispatch the messages waiting in the stream to its callback now, instead of waiting for
+    /// the next iteration of the main loop.
+    /// When called from the callback itself, the messages stay queued: they are dispatched right
+    /// after the callback returns.
+    /// Return the number of dispatched messages, 0 if the `EventStream` was dropped.
+    pub fn dispatch_pending(&self) -> usize {
+        self.stream.upgrade().map(|stream| dispatch_pending(&stream)).unwrap_or(0)
+    }
+
+    /// Get the number of messages waiting to be dispatched.
+    /// Return `None` if the `EventStream` was dropped.
+    pub fn len(&self) -> Option<usize> {
+        self.stream.upgrade().map(|stream| stream.borrow().len())
+    }
+
+    /// Check if there's no message waiting to be dispatched.
+    /// Return `None` if the `EventStream` was dropped.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
     /// Lock the stream (don't emit message) until the `Lock` goes out of scope.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
     pub fn lock(&self) -> Lock<MSG> {
+        self.try_lock()
+            .unwrap_or_else(|_| panic!("Trying to call lock() on a dropped EventStream"))
+    }
+
+    /// Lock the stream (don't emit message) until the `Lock` goes out of scope.
+    /// Return an error if the `EventStream` was dropped.
+    pub fn try_lock(&self) -> Result<Lock<MSG>, DroppedStreamError> {
         if let Some(ref stream) = self.stream.upgrade() {
-            stream.borrow_mut().locked = true;
-            Lock {
-                stream: self.clone(),
-            }
+            Ok(lock(stream, false))
         }
         else {
-            panic!("Trying to call lock() on a dropped EventStream");
+            Err(DroppedStreamError)
         }
     }
 
-    fn unlock(&self) {
+    /// Lock the stream until the `Lock` goes out of scope.
+    /// In contrast to `lock()`, the messages emitted while the stream is locked are not dropped:
+    /// they are emitted when the `Lock` is released.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn lock_queueing(&self) -> Lock<MSG> {
         if let Some(ref stream) = self.stream.upgrade() {
-            stream.borrow_mut().locked = false;
+            lock(stream, true)
         }
         else {
-            panic!("Trying to call unlock() on a dropped EventStream");
+            panic!("Trying to call lock_queueing() on a dropped EventStream");
+        }
+    }
+
+    fn unlock(&self, queue_events: bool) {
+        // The stream might have been dropped before the lock: there's nothing to unlock then.
+        if let Some(ref stream) = self.stream.upgrade() {
+            unlock(stream, queue_events);
         }
     }
 
     /// Add an observer to the event stream.
     /// This callback will be called every time a message is emmited.
-    pub fn observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK) {
+    /// The returned handle can be used to remove the observer.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG> {
+        self.try_observe(callback)
+            .unwrap_or_else(|_| panic!("Trying to call observe() on a dropped EventStream"))
+    }
+
+    /// Add an observer to the event stream.
+    /// This callback will be called every time a message is emmited.
+    /// Return an error if the `EventStream` was dropped.
+    pub fn try_observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK)
+        -> Result<ObserverHandle<MSG>, DroppedStreamError>
+    {
+        if let Some(ref stream) = self.stream.upgrade() {
+            Ok(observe(stream, callback))
+        }
+        else {
+            Err(DroppedStreamError)
+        }
+    }
+
+    /// Add an observer that is called for the next emitted message only and is then removed.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn observe_once<CALLBACK: FnOnce(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: 'static,
+    {
+        if let Some(ref stream) = self.stream.upgrade() {
+            observe_once(stream, callback)
+        }
+        else {
+            panic!("Trying to call observe_once() on a dropped EventStream");
+        }
+    }
+
+    /// Add an observer called in the specified `phase`: with `ObservePhase::AfterUpdate`, the
+    /// observer is called when the message is dispatched, after the callback returns.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn observe_in_phase<CALLBACK: Fn(&MSG) + 'static>(&self, phase: ObservePhase, callback: CALLBACK)
+        -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        if let Some(ref stream) = self.stream.upgrade() {
+            observe_in_phase(stream, phase, callback)
+        }
+        else {
+            panic!("Trying to call observe_in_phase() on a dropped EventStream");
+        }
+    }
+
+    /// Add an observer receiving its own clone of every emitted message, for instance to send it
+    /// to another thread.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn observe_owned<CALLBACK: Fn(MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        self.observe_filtered(|_| true, callback)
+    }
+
+    /// Add an observer receiving its own clone of the emitted messages for which `predicate`
+    /// returns `true`: the other messages are not cloned.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn observe_filtered<PREDICATE, CALLBACK>(&self, predicate: PREDICATE, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+          PREDICATE: Fn(&MSG) -> bool + 'static,
+          CALLBACK: Fn(MSG) + 'static,
+    {
         if let Some(ref stream) = self.stream.upgrade() {
-            stream.borrow_mut().observers.push(Rc::new(callback));
+            observe_filtered(stream, predicate, callback)
         }
         else {
-            panic!("Trying to call observe() on a dropped EventStream");
+            panic!("Trying to call observe_filtered() on a dropped EventStream");
+        }
+    }
+
+    /// Emit every message of this stream to the `other` stream.
+    /// Messages are ignored once `other` was dropped.
+    /// The returned handle can be used to stop forwarding.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn forward_to(&self, other: StreamHandle<MSG>) -> ObserverHandle<MSG>
+    where MSG: Clone + 'static,
+    {
+        self.map_forward_to(other, |msg| Some(msg.clone()))
+    }
+
+    /// Emit the messages of this stream for which `predicate` returns `true` to the `other`
+    /// stream.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn filter_forward_to<PREDICATE>(&self, other: StreamHandle<MSG>, predicate: PREDICATE) -> ObserverHandle<MSG>
+    where MSG: Clone + 'static,
+          PREDICATE: Fn(&MSG) -> bool + 'static,
+    {
+        self.map_forward_to(other, move |msg| {
+            if predicate(msg) {
+                Some(msg.clone())
+            }
+            else {
+                None
+            }
+        })
+    }
+
+    /// Convert the messages of this stream with `callback` and emit the result to the `other`
+    /// stream when it is `Some`.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn map_forward_to<OUT, CALLBACK>(&self, other: StreamHandle<OUT>, callback: CALLBACK) -> ObserverHandle<MSG>
+    where OUT: 'static,
+          CALLBACK: Fn(&MSG) -> Option<OUT> + 'static,
+    {
+        self.observe(move |msg| {
+            if let Some(msg) = callback(msg) {
+                // The other stream might be dropped before this one.
+                let _ = other.try_emit(msg);
+            }
+        })
+    }
+
+    /// Remove an observer previously added with `observe()`.
+    /// Does nothing if the observer belongs to another stream or was already removed.
+    pub fn remove_observer(&self, observer: ObserverHandle<MSG>) {
+        if Weak::ptr_eq(&self.stream, &observer.stream) {
+            observer.remove();
+        }
+    }
+
+    /// Record a signal handler connected on `object` that sends its messages to this stream, so
+    /// that it is disconnected by [`Component::destroy()`](../struct.Component.html#method.destroy).
+    /// This is used by the `connect!` macros.
+    #[doc(hidden)]
+    pub fn add_signal_handler<OBJECT: AsRef<glib::Object>>(&self, object: &OBJECT, handler_id: SignalHandlerId) {
+        if let Some(stream) = self.stream.upgrade() {
+            stream.borrow_mut().signal_handlers.push((object.as_ref().downgrade(), handler_id));
+        }
+    }
+}
+
+/// Strong handle to an `EventStream`, created by
+/// [`StreamHandle::upgrade()`](struct.StreamHandle.html#method.upgrade).
+///
+/// It keeps the messages and the observers alive, so its methods don't panic.
+/// Messages emitted after the `EventStream` was dropped are still sent to the observers, but are
+/// never dispatched to its callback.
+/// Don't store it in a component, as this would keep its stream alive forever.
+pub struct StrongStreamHandle<MSG> {
+    stream: Rc<RefCell<_EventStream<MSG>>>,
+}
+
+impl<MSG> Clone for StrongStreamHandle<MSG> {
+    fn clone(&self) -> Self {
+        Self {
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+impl<MSG> StrongStreamHandle<MSG> {
+    /// Get a weak handle to the stream.
+    pub fn downgrade(&self) -> StreamHandle<MSG> {
+        StreamHandle::new(Rc::downgrade(&self.stream))
+    }
+
+    /// Send the `event` message to the stream and the observers.
+    pub fn emit(&self, msg: MSG) {
+        emit(&self.stream, msg, MessagePriority::Normal);
+    }
+
+    /// Send the `event` message to the stream and the observers, to be dispatched before the
+    /// messages of lower `priority`.
+    pub fn emit_with_priority(&self, msg: MSG, priority: MessagePriority) {
+        emit(&self.stream, msg, priority);
+    }
+
+    /// Get the number of messages waiting to be dispatched.
+    pub fn len(&self) -> usize {
+        self.stream.borrow().len()
+    }
+
+    /// Check if there's no message waiting to be dispatched.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lock the stream (don't emit message) until the `Lock` goes out of scope.
+    pub fn lock(&self) -> Lock<MSG> {
+        lock(&self.stream, false)
+    }
+
+    /// Lock the stream until the `Lock` goes out of scope.
+    /// In contrast to `lock()`, the messages emitted while the stream is locked are emitted when
+    /// the `Lock` is released.
+    pub fn lock_queueing(&self) -> Lock<MSG> {
+        lock(&self.stream, true)
+    }
+
+    /// Add an observer to the event stream.
+    /// This callback will be called every time a message is emmited.
+    pub fn observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG> {
+        observe(&self.stream, callback)
+    }
+
+    /// Add an observer that is called for the next emitted message only and is then removed.
+    pub fn observe_once<CALLBACK: FnOnce(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: 'static,
+    {
+        observe_once(&self.stream, callback)
+    }
+
+    /// Add an observer called in the specified `phase`.
+    pub fn observe_in_phase<CALLBACK: Fn(&MSG) + 'static>(&self, phase: ObservePhase, callback: CALLBACK)
+        -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        observe_in_phase(&self.stream, phase, callback)
+    }
+
+    /// Add an observer receiving its own clone of every emitted message.
+    pub fn observe_owned<CALLBACK: Fn(MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        observe_filtered(&self.stream, |_| true, callback)
+    }
+
+    /// Add an observer receiving its own clone of the emitted messages for which `predicate`
+    /// returns `true`.
+    pub fn observe_filtered<PREDICATE, CALLBACK>(&self, predicate: PREDICATE, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+          PREDICATE: Fn(&MSG) -> bool + 'static,
+          CALLBACK: Fn(MSG) + 'static,
+    {
+        observe_filtered(&self.stream, predicate, callback)
+    }
+}
+
+/// Handle to an observer added with `observe()`.
+///
+/// Dropping the handle keeps the observer registered for as long as the stream lives, so that the
+/// handle can be ignored when the observer is never removed, which is the most common case.
+/// The observer is removed by [`remove()`](struct.ObserverHandle.html#method.remove), by
+/// [`StreamHandle::remove_observer()`](struct.StreamHandle.html#method.remove_observer), or when the
+/// handle is dropped after opting in with
+/// [`remove_on_drop()`](struct.ObserverHandle.html#method.remove_on_drop).
+/// The handle doesn't borrow the stream and an observer can be removed while a message is emitted:
+/// it is then not called for this message.
+pub struct ObserverHandle<MSG> {
+    id: usize,
+    remove_on_drop: bool,
+    stream: Weak<RefCell<_EventStream<MSG>>>,
+}
+
+impl<MSG> ObserverHandle<MSG> {
+    /// Remove the observer when this handle is dropped.
+    pub fn remove_on_drop(mut self) -> Self {
+        self.remove_on_drop = true;
+        self
+    }
+
+    /// Remove the observer from its stream.
+    pub fn remove(mut self) {
+        self.remove_on_drop = true;
+    }
+}
+
+impl<MSG> Drop for ObserverHandle<MSG> {
+    fn drop(&mut self) {
+        if self.remove_on_drop {
+            if let Some(stream) = self.stream.upgrade() {
+                // Avoid panicking in a destructor if the stream is currently borrowed.
+                if let Ok(mut stream) = stream.try_borrow_mut() {
+                    stream.remove_observer(self.id);
+                }
+            }
+        }
+    }
+}
+
+/// Error returned by [`StreamHandle::try_emit()`](struct.StreamHandle.html#method.try_emit) when
+/// the `EventStream` was dropped.
+/// It contains the message that could not be emitted.
+pub struct EmitError<MSG>(pub MSG);
+
+impl<MSG> EmitError<MSG> {
+    /// Get back the message that could not be emitted.
+    pub fn into_inner(self) -> MSG {
+        self.0
+    }
+}
+
+impl<MSG> Debug for EmitError<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("EmitError(..)")
+    }
+}
+
+impl<MSG> Display for EmitError<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("cannot emit a message on a dropped EventStream")
+    }
+}
+
+impl<MSG> Error for EmitError<MSG> {
+}
+
+/// Error returned when trying to use a `StreamHandle` whose `EventStream` was dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DroppedStreamError;
+
+impl Display for DroppedStreamError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("the EventStream was dropped")
+    }
+}
+
+impl Error for DroppedStreamError {
+}
+
+/// Error returned by [`EventStream::try_new()`](struct.EventStream.html#method.try_new).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamError {
+    /// Another thread owns the default main context, e.g. because it runs its main loop: the
+    /// messages of the stream would only be dispatched by this other thread.
+    ContextNotOwned,
+    /// GTK is not initialized and the current thread doesn't own the default main context: no main
+    /// loop is going to dispatch the messages of the stream.
+    NotInitialized,
+    /// GTK was initialized from another thread, which owns the default main context: the
+    /// messages of the stream would never be dispatched.
+    NotMainThread,
+}
+
+impl Display for StreamError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            StreamError::ContextNotOwned =>
+                formatter.write_str("the default main context is owned by another thread \
+                                     (use EventStream::with_context() to dispatch from another context)"),
+            StreamError::NotInitialized =>
+                formatter.write_str("GTK is not initialized (call gtk::init() first, or use \
+                                     EventStream::new_detached() to use the stream without a main loop)"),
+            StreamError::NotMainThread =>
+                formatter.write_str("an EventStream can only be created from the thread where GTK was initialized \
+                                     (use EventStream::with_context() in other threads)"),
         }
     }
 }
 
+impl Error for StreamError {
+}
+
+/// Priority of a message in an `EventStream`.
+///
+/// The messages of higher priority are dispatched first, while the messages of the same priority
+/// are dispatched in the order they were emitted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessagePriority {
+    /// Messages dispatched before all the others, like the echo of the user input.
+    High,
+    /// Priority of the messages sent with `emit()`.
+    Normal,
+    /// Messages dispatched after all the others, like bulk updates.
+    Low,
+}
+
+/// When an observer is called, relative to the callback of the stream, i.e. the `update()` of a
+/// component.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObservePhase {
+    /// The observer is called when the message is emitted, before it is queued: this is the
+    /// phase of `observe()`.
+    BeforeUpdate,
+    /// The observer is called when the message is dispatched, right after the callback returns,
+    /// for instance to check the state of the model after the update.
+    /// The observer receives a clone of the message, which may be the result of the coalescer.
+    AfterUpdate,
+}
+
+impl From<StreamError> for glib::BoolError {
+    fn from(error: StreamError) -> Self {
+        glib::glib_bool_error!("{}", error)
+    }
+}
+
 /// A lock is used to temporarily stop emitting messages.
+/// Locks can be nested: the stream is unlocked when all the `Lock`s are released.
 #[must_use]
 pub struct Lock<MSG> {
+    queue_events: bool,
     stream: StreamHandle<MSG>,
 }
 
 impl<MSG> Drop for Lock<MSG> {
     fn drop(&mut self) {
-        self.stream.unlock();
+        self.stream.unlock(self.queue_events);
+    }
+}
+
+const DEFAULT_CHANNEL_MAX_DISPATCH: usize = 64;
+
+type SendWakers = Arc<Mutex<Vec<Waker>>>;
+
+// Set by Channel::close(), so that the senders return an error even when the receiver cannot be
+// dropped yet.
+type ClosedFlag = Arc<AtomicBool>;
+
+fn wake_senders(send_wakers: &SendWakers) {
+    let wakers = mem::take(&mut *send_wakers.lock().expect("lock send wakers"));
+    for waker in wakers {
+        waker.wake();
+    }
+}
+
+enum ChannelReceiver<MSG> {
+    Std(Receiver<MSG>),
+    #[cfg(feature = "crossbeam")]
+    Crossbeam(crossbeam_channel::Receiver<MSG>),
+}
+
+impl<MSG> ChannelReceiver<MSG> {
+    fn try_recv(&self) -> Result<MSG, TryRecvError> {
+        match *self {
+            ChannelReceiver::Std(ref receiver) => receiver.try_recv(),
+            #[cfg(feature = "crossbeam")]
+            ChannelReceiver::Crossbeam(ref receiver) =>
+                receiver.try_recv().map_err(|error| match error {
+                    crossbeam_channel::TryRecvError::Empty => TryRecvError::Empty,
+                    crossbeam_channel::TryRecvError::Disconnected => TryRecvError::Disconnected,
+                }),
+        }
     }
 }
 
 struct ChannelData<MSG> {
     callback: Box<dyn FnMut(MSG)>,
+    closed: ClosedFlag,
+    // Set when all the senders were dropped and all the messages were received.
+    disconnected: bool,
+    max_dispatch: usize,
+    on_disconnect: Option<Box<dyn FnOnce()>>,
     peeked_value: Option<MSG>,
-    receiver: Receiver<MSG>,
+    // Taken when the channel is closed, so that sending a message returns an error.
+    receiver: Option<ChannelReceiver<MSG>>,
+    // Tasks waiting for the bounded channel to have room for a new message.
+    send_wakers: SendWakers,
+}
+
+impl<MSG> ChannelData<MSG> {
+    fn close(&mut self) {
+        self.peeked_value = None;
+        self.receiver = None;
+        wake_senders(&self.send_wakers);
+    }
+
+    fn try_recv(&mut self) -> Option<MSG> {
+        let receiver = self.receiver.as_ref()?;
+        match receiver.try_recv() {
+            Ok(msg) => Some(msg),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.disconnected = true;
+                None
+            },
+        }
+    }
 }
 
 /// A wrapper over a `std::sync::mpsc::Sender` to wakeup the glib event loop when sending a
 /// message.
 pub struct Sender<MSG> {
+    cancellation: Option<CancellationToken>,
+    closed: ClosedFlag,
+    context: MainContext,
     sender: mpsc::Sender<MSG>,
 }
 
 impl<MSG> Clone for Sender<MSG> {
     fn clone(&self) -> Self {
         Self {
+            cancellation: self.cancellation.clone(),
+            closed: self.closed.clone(),
+            context: self.context.clone(),
             sender: self.sender.clone(),
         }
     }
@@ -151,124 +758,912 @@ impl<MSG> Clone for Sender<MSG> {
 
 impl<MSG> Sender<MSG> {
     /// Send a message and wakeup the event loop.
+    /// Do nothing if the `CancellationToken` given to
+    /// [`with_cancellation()`](struct.Sender.html#method.with_cancellation) was cancelled.
+    /// Return an error if the channel was closed.
     pub fn send(&self, msg: MSG) -> Result<(), SendError<MSG>> {
+        if is_cancelled(&self.cancellation) {
+            return Ok(());
+        }
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(SendError(msg));
+        }
         let result = self.sender.send(msg);
-        let context = MainContext::default();
-        context.wakeup();
+        self.context.wakeup();
         result
     }
+
+    /// Bind the sender to `token`, so that the messages sent after it is cancelled are dropped.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
 }
 
-/// A channel to send a message to a relm widget from another thread.
-pub struct Channel<MSG> {
-    _source: Source,
-    _phantom: PhantomData<MSG>,
+fn is_cancelled(cancellation: &Option<CancellationToken>) -> bool {
+    cancellation.as_ref().map(CancellationToken::is_cancelled).unwrap_or(false)
 }
 
-impl<MSG> Channel<MSG> {
-    /// Create a new channel with a callback that will be called when a message is received.
-    pub fn new<CALLBACK: FnMut(MSG) + 'static>(callback: CALLBACK) -> (Self, Sender<MSG>) {
-        let (sender, receiver) = mpsc::channel();
-        let source = new_source(RefCell::new(ChannelData {
-            callback: Box::new(callback),
-            peeked_value: None,
-            receiver,
-        }));
-        let main_context = MainContext::default();
-        source.attach(Some(&main_context));
-        (Self {
-            _source: source,
-            _phantom: PhantomData,
-        }, Sender {
-            sender,
-        })
+/// A wrapper over a `std::sync::mpsc::SyncSender` to wakeup the glib event loop when sending a
+/// message to a bounded `Channel`.
+pub struct SyncSender<MSG> {
+    cancellation: Option<CancellationToken>,
+    closed: ClosedFlag,
+    context: MainContext,
+    send_wakers: SendWakers,
+    sender: mpsc::SyncSender<MSG>,
+}
+
+impl<MSG> Clone for SyncSender<MSG> {
+    fn clone(&self) -> Self {
+        Self {
+            cancellation: self.cancellation.clone(),
+            closed: self.closed.clone(),
+            context: self.context.clone(),
+            send_wakers: self.send_wakers.clone(),
+            sender: self.sender.clone(),
+        }
     }
 }
 
-impl<MSG> SourceFuncs for RefCell<ChannelData<MSG>> {
-    fn dispatch(&self) -> bool {
-        // TODO: show errors.
-        let msg = self.borrow_mut().peeked_value.take().or_else(|| {
-            self.borrow().receiver.try_recv().ok()
-        });
-        if let Some(msg) = msg {
-            let callback = &mut self.borrow_mut().callback;
-            callback(msg);
+impl<MSG> SyncSender<MSG> {
+    /// Send a message and wakeup the event loop.
+    /// Block while the channel is full.
+    /// Do nothing if the `CancellationToken` given to
+    /// [`with_cancellation()`](struct.SyncSender.html#method.with_cancellation) was cancelled.
+    /// Return an error if the channel was closed.
+    pub fn send(&self, msg: MSG) -> Result<(), SendError<MSG>> {
+        if is_cancelled(&self.cancellation) {
+            return Ok(());
         }
-        true
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(SendError(msg));
+        }
+        self.sender.send(msg)?;
+        self.context.wakeup();
+        Ok(())
     }
 
-    fn prepare(&self) -> (bool, Option<u32>) {
-        if self.borrow().peeked_value.is_some() {
-            return (true, None);
+    /// Send a message and wakeup the event loop.
+    /// Return an error instead of blocking if the channel is full.
+    /// Do nothing if the `CancellationToken` was cancelled.
+    pub fn try_send(&self, msg: MSG) -> Result<(), TrySendError<MSG>> {
+        if is_cancelled(&self.cancellation) {
+            return Ok(());
         }
-        let peek_val = self.borrow().receiver.try_recv().ok();
-        self.borrow_mut().peeked_value = peek_val;
-        (self.borrow().peeked_value.is_some(), None)
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(TrySendError::Disconnected(msg));
+        }
+        self.sender.try_send(msg)?;
+        self.context.wakeup();
+        Ok(())
     }
 
+    /// Bind the sender to `token`, so that the messages sent after it is cancelled are dropped.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
 }
 
-struct _EventStream<MSG> {
-    events: VecDeque<MSG>,
-    locked: bool,
-    // We use an Rc here to be able to clone the function to call it so that we don't borrow the
-    // stream while calling the function. Otherwise, calling an observer could trigger a
-    // borrow_mut() which would result in a panic.
-    observers: Vec<Rc<dyn Fn(&MSG)>>,
+/// A channel to send a message to a relm widget from another thread.
+pub struct Channel<MSG> {
+    closed: ClosedFlag,
+    source: Source,
+    _phantom: PhantomData<MSG>,
 }
 
-impl<MSG> SourceFuncs for SourceData<MSG> {
-    fn dispatch(&self) -> bool {
-        let event = self.stream.borrow_mut().events.pop_front();
-        if let (Some(event), Some(callback)) = (event, self.callback.borrow_mut().as_mut()) {
-            callback(event);
-        }
-        true
+/// A builder to create a `Channel` with non-default options.
+///
+/// ```
+/// # use relm::Channel;
+/// let (_channel, sender) = Channel::builder()
+///     .name("worker")
+///     .priority(glib::PRIORITY_LOW)
+///     .build(|num: i32| println!("{}", num));
+/// # sender.send(42).expect("send message");
+/// ```
+pub struct ChannelBuilder<MSG> {
+    can_recurse: bool,
+    context: MainContext,
+    name: Option<String>,
+    priority: Priority,
+    _phantom: PhantomData<MSG>,
+}
+
+impl<MSG: 'static> ChannelBuilder<MSG> {
+    /// Allow receiving messages from a nested main loop, e.g. while a dialog is run from the
+    /// callback.
+    pub fn can_recurse(mut self, can_recurse: bool) -> Self {
+        self.can_recurse = can_recurse;
+        self
     }
 
-    fn prepare(&self) -> (bool, Option<u32>) {
-        (!self.stream.borrow().events.is_empty(), None)
+    /// Receive the messages from the main loop of `context` instead of the default one.
+    pub fn context(mut self, context: &MainContext) -> Self {
+        self.context = context.clone();
+        self
     }
 
-}
+    /// Set the name of the glib source, shown by debugging and profiling tools.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
 
-type Callback<MSG> = Rc<RefCell<Option<Box<dyn FnMut(MSG)>>>>;
+    /// Set the priority at which the received messages are dispatched.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
 
-struct SourceData<MSG> {
-    callback: Callback<MSG>,
-    stream: Rc<RefCell<_EventStream<MSG>>>,
+    /// Create the channel with a callback that will be called when a message is received.
+    pub fn build<CALLBACK: FnMut(MSG) + 'static>(self, callback: CALLBACK) -> (Channel<MSG>, Sender<MSG>) {
+        let (sender, receiver) = mpsc::channel();
+        let source = new_channel_source(ChannelReceiver::Std(receiver), callback, None, SendWakers::default(),
+            self.priority, self.can_recurse);
+        if let Some(ref name) = self.name {
+            set_name(&source, name);
+        }
+        source.attach(Some(&self.context));
+        let channel = Channel::from_source(source);
+        let closed = channel.closed.clone();
+        (channel, Sender {
+            cancellation: None,
+            closed,
+            context: self.context,
+            sender,
+        })
+    }
 }
 
-fn emit<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, msg: MSG) {
-    if !stream.borrow().locked {
-        let len = stream.borrow().observers.len();
-        for i in 0..len {
-            let observer = stream.borrow().observers[i].clone();
-            observer(&msg);
+impl<MSG: 'static> Channel<MSG> {
+    /// Create a builder to configure the channel.
+    pub fn builder() -> ChannelBuilder<MSG> {
+        ChannelBuilder {
+            can_recurse: false,
+            context: MainContext::default(),
+            name: None,
+            priority: Priority::default(),
+            _phantom: PhantomData,
         }
-
-        stream.borrow_mut().events.push_back(msg);
     }
-}
 
-/// A stream of messages to be used for widget/signal communication and inter-widget communication.
+    /// Create a new channel with a callback that will be called when a message is received.
+    pub fn new<CALLBACK: FnMut(MSG) + 'static>(callback: CALLBACK) -> (Self, Sender<MSG>) {
+        Self::with_context(&MainContext::default(), callback)
+    }
+
+    /// Create a new channel with a callback that will be called when a message is received and
+    /// an `on_disconnect` callback that will be called once all the senders are dropped.
+    pub fn with_disconnect_handler<CALLBACK, DISCONNECT>(callback: CALLBACK, on_disconnect: DISCONNECT)
+        -> (Self, Sender<MSG>)
+    where CALLBACK: FnMut(MSG) + 'static,
+          DISCONNECT: FnOnce() + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let context = MainContext::default();
+        let channel = Self::from_receiver(&context, ChannelReceiver::Std(receiver), callback, Some(Box::new(on_disconnect)),
+            SendWakers::default());
+        let closed = channel.closed.clone();
+        (channel, Sender {
+            cancellation: None,
+            closed,
+            context,
+            sender,
+        })
+    }
+
+    /// Create a new channel that emits the messages converted by `map` to `stream`.
+    /// The messages are received from the main loop of the context of `stream`.
+    /// Once the `EventStream` of `stream` is dropped, the received messages are dropped.
+    pub fn forward_to_stream<WIDGETMSG, MAP, STREAM>(stream: &STREAM, map: MAP) -> (Self, Sender<MSG>)
+    where WIDGETMSG: 'static,
+          MAP: Fn(MSG) -> WIDGETMSG + 'static,
+          STREAM: AsRef<StreamHandle<WIDGETMSG>>,
+    {
+        let stream = stream.as_ref().clone();
+        let context = stream.context().unwrap_or_else(MainContext::default);
+        Self::with_context(&context, move |msg| {
+            let _ = stream.try_emit(map(msg));
+        })
+    }
+
+    /// Create a new channel whose glib source is named `name`, to identify it in debugging and
+    /// profiling tools.
+    pub fn new_named<CALLBACK: FnMut(MSG) + 'static>(name: &str, callback: CALLBACK) -> (Self, Sender<MSG>) {
+        let (channel, sender) = Self::new(callback);
+        set_name(&channel.source, name);
+        (channel, sender)
+    }
+
+    /// Create a new channel whose callback is called from the main loop of `context`.
+    /// The `Sender` will wake up this context instead of the default one.
+    pub fn with_context<CALLBACK: FnMut(MSG) + 'static>(context: &MainContext, callback: CALLBACK)
+        -> (Self, Sender<MSG>)
+    {
+        let (sender, receiver) = mpsc::channel();
+        let channel = Self::from_receiver(context, ChannelReceiver::Std(receiver), callback, None,
+            SendWakers::default());
+        let closed = channel.closed.clone();
+        (channel, Sender {
+            cancellation: None,
+            closed,
+            context: context.clone(),
+            sender,
+        })
+    }
+
+    /// Create a new channel that can hold at most `capacity` messages waiting to be received:
+    /// when it is full, `SyncSender::send()` blocks the sending thread.
+    pub fn new_bounded<CALLBACK: FnMut(MSG) + 'static>(capacity: usize, callback: CALLBACK)
+        -> (Self, SyncSender<MSG>)
+    {
+        Self::bounded_with_context(&MainContext::default(), capacity, callback)
+    }
+
+    /// Create a new bounded channel whose callback is called from the main loop of `context`.
+    pub fn bounded_with_context<CALLBACK: FnMut(MSG) + 'static>(context: &MainContext, capacity: usize,
+        callback: CALLBACK) -> (Self, SyncSender<MSG>)
+    {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let send_wakers = SendWakers::default();
+        let channel = Self::from_receiver(context, ChannelReceiver::Std(receiver), callback, None,
+            send_wakers.clone());
+        let closed = channel.closed.clone();
+        (channel, SyncSender {
+            cancellation: None,
+            closed,
+            context: context.clone(),
+            send_wakers,
+            sender,
+        })
+    }
+
+    fn from_receiver<CALLBACK: FnMut(MSG) + 'static>(context: &MainContext, receiver: ChannelReceiver<MSG>,
+        callback: CALLBACK, on_disconnect: Option<Box<dyn FnOnce()>>, send_wakers: SendWakers) -> Self
+    {
+        let source = attach_channel_source(context, receiver, callback, on_disconnect, send_wakers);
+        Self::from_source(source)
+    }
+
+    fn from_source(source: Source) -> Self {
+        let closed = source_get::<RefCell<ChannelData<MSG>>>(&source).borrow().closed.clone();
+        Self {
+            closed,
+            source,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn get_data(&self) -> &RefCell<ChannelData<MSG>> {
+        source_get::<RefCell<ChannelData<MSG>>>(&self.source)
+    }
+
+    /// Remove the channel from the main loop.
+    /// The messages that were not received yet are dropped and sending new messages returns an
+    /// error.
+    /// The channel is also closed when it is dropped.
+    pub fn close(&self) {
+        self.source.destroy();
+        self.closed.store(true, Ordering::SeqCst);
+        // When called from the callback, the receiver is dropped when the callback returns.
+        if let Ok(mut data) = self.get_data().try_borrow_mut() {
+            data.close();
+        }
+    }
+
+    /// Check if the channel was closed, either explicitly or because all the senders were
+    /// dropped.
+    pub fn is_closed(&self) -> bool {
+        self.source.is_destroyed()
+    }
+
+    /// Check if all the senders were dropped and all the messages were received.
+    pub fn is_disconnected(&self) -> bool {
+        self.get_data().borrow().disconnected
+    }
+
+    /// Set the maximum number of messages sent to the callback in a single iteration of the main
+    /// loop (64 by default).
+    /// Use `usize::MAX` to receive all the available messages at once.
+    pub fn set_max_messages_per_dispatch(&self, max_dispatch: usize) {
+        // Dispatching no message at all would make the main loop spin forever.
+        self.get_data().borrow_mut().max_dispatch = max_dispatch.max(1);
+    }
+
+    /// Get the priority at which the received messages are dispatched.
+    pub fn priority(&self) -> Priority {
+        from_glib(self.source.get_priority())
+    }
+
+    /// Set the priority at which the received messages are dispatched (`PRIORITY_DEFAULT` by
+    /// default).
+    pub fn set_priority(&self, priority: Priority) {
+        set_priority(&self.source, priority);
+    }
+}
+
+fn attach_channel_source<MSG: 'static, CALLBACK: FnMut(MSG) + 'static>(context: &MainContext, receiver: ChannelReceiver<MSG>,
+    callback: CALLBACK, on_disconnect: Option<Box<dyn FnOnce()>>, send_wakers: SendWakers) -> Source
+{
+    let source = new_channel_source(receiver, callback, on_disconnect, send_wakers, Priority::default(), false);
+    source.attach(Some(context));
+    source
+}
+
+fn new_channel_source<MSG: 'static, CALLBACK: FnMut(MSG) + 'static>(receiver: ChannelReceiver<MSG>, callback: CALLBACK,
+    on_disconnect: Option<Box<dyn FnOnce()>>, send_wakers: SendWakers, priority: Priority, can_recurse: bool) -> Source
+{
+    new_source_full(RefCell::new(ChannelData {
+        callback: Box::new(callback),
+        closed: ClosedFlag::default(),
+        disconnected: false,
+        max_dispatch: DEFAULT_CHANNEL_MAX_DISPATCH,
+        on_disconnect,
+        peeked_value: None,
+        receiver: Some(receiver),
+        send_wakers,
+    }), priority, can_recurse)
+}
+
+impl<MSG> Drop for Channel<MSG> {
+    fn drop(&mut self) {
+        self.source.destroy();
+    }
+}
+
+impl<MSG> SourceFuncs for RefCell<ChannelData<MSG>> {
+    fn dispatch(&self) -> bool {
+        let max_dispatch = self.borrow().max_dispatch;
+        for _ in 0..max_dispatch {
+            let msg = self.borrow_mut().peeked_value.take().or_else(|| {
+                self.borrow_mut().try_recv()
+            });
+            match msg {
+                Some(msg) => {
+                    let callback = &mut self.borrow_mut().callback;
+                    callback(msg);
+                },
+                None => break,
+            }
+            // The callback closed the channel.
+            if self.borrow().closed.load(Ordering::SeqCst) {
+                self.borrow_mut().close();
+                return false;
+            }
+        }
+        // Some room was made in the channel.
+        wake_senders(&self.borrow().send_wakers);
+        if self.borrow().disconnected {
+            // No message can be received anymore: remove the source from the main loop.
+            let on_disconnect = self.borrow_mut().on_disconnect.take();
+            if let Some(on_disconnect) = on_disconnect {
+                on_disconnect();
+            }
+            return false;
+        }
+        true
+    }
+
+    fn prepare(&self) -> (bool, Option<u32>) {
+        if self.borrow().peeked_value.is_some() {
+            return (true, None);
+        }
+        let peek_val = self.borrow_mut().try_recv();
+        self.borrow_mut().peeked_value = peek_val;
+        let data = self.borrow();
+        (data.peeked_value.is_some() || data.disconnected, None)
+    }
+
+}
+
+const DEFAULT_MAX_DISPATCH: usize = 32;
+
+struct Observer<MSG> {
+    // Set to false when the observer is removed, so that an emit() in progress doesn't call it
+    // anymore.
+    active: Cell<bool>,
+    callback: Box<dyn Fn(&MSG)>,
+    id: usize,
+    phase: ObservePhase,
+}
+
+struct _EventStream<MSG> {
+    // Weak so that the stream doesn't keep the callback (the component) alive.
+    callback: WeakCallback<MSG>,
+    coalescer: Option<Coalescer<MSG>>,
+    // The context the source is attached to, None for a detached stream.
+    context: Option<MainContext>,
+    events: Lanes<MSG>,
+    // Number of live locks.
+    locked: usize,
+    max_dispatch: usize,
+    name: Option<String>,
+    // Called once, right before the stream is closed.
+    on_close: Option<Box<dyn FnOnce()>>,
+    panic_handler: Option<PanicHandler>,
+    // Messages emitted while the stream is locked by lock_queueing().
+    locked_events: VecDeque<(MSG, MessagePriority)>,
+    // Number of live locks created by lock_queueing().
+    queueing_locks: usize,
+    next_observer_id: usize,
+    // We use an Rc here to be able to clone the observers to call them so that we don't borrow
+    // the stream while calling them. Otherwise, calling an observer could trigger a borrow_mut()
+    // which would result in a panic.
+    observers: Vec<Rc<Observer<MSG>>>,
+    // Set when an observer is added in the AfterUpdate phase, to keep the dispatched message for
+    // it: MSG is only required to be Clone in observe_in_phase().
+    clone_message: Option<fn(&MSG) -> MSG>,
+    // Last emitted messages, sent to the observers added later.
+    replay: Option<ReplayBuffer<MSG>>,
+    // Signal handlers connected by the connect! macros: weak references so that the stream doesn't
+    // keep the widgets alive.
+    signal_handlers: Vec<(WeakRef<glib::Object>, SignalHandlerId)>,
+    #[cfg(feature = "trace")]
+    tracer: Option<StreamTracer<MSG>>,
+}
+
+struct ReplayBuffer<MSG> {
+    capacity: usize,
+    // MSG is only required to be Clone in set_replay().
+    clone: fn(&MSG) -> MSG,
+    messages: VecDeque<MSG>,
+}
+
+impl<MSG> ReplayBuffer<MSG> {
+    // Return the message removed to make room for msg.
+    fn push(&mut self, msg: MSG) -> Option<MSG> {
+        let removed =
+            if self.messages.len() == self.capacity {
+                self.messages.pop_front()
+            }
+            else {
+                None
+            };
+        self.messages.push_back(msg);
+        removed
+    }
+
+    fn messages(&self) -> Vec<MSG> {
+        self.messages.iter().map(self.clone).collect()
+    }
+}
+
+// One queue per message priority.
+struct Lanes<MSG> {
+    high: VecDeque<MSG>,
+    normal: VecDeque<MSG>,
+    low: VecDeque<MSG>,
+}
+
+impl<MSG> Lanes<MSG> {
+    fn new() -> Self {
+        Lanes {
+            high: VecDeque::new(),
+            normal: VecDeque::new(),
+            low: VecDeque::new(),
+        }
+    }
+
+    // Remove all the messages, in the order they would be dispatched.
+    fn drain(&mut self) -> Vec<MSG> {
+        self.high.drain(..)
+            .chain(self.normal.drain(..))
+            .chain(self.low.drain(..))
+            .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lane(&self, priority: MessagePriority) -> &VecDeque<MSG> {
+        match priority {
+            MessagePriority::High => &self.high,
+            MessagePriority::Normal => &self.normal,
+            MessagePriority::Low => &self.low,
+        }
+    }
+
+    fn lane_mut(&mut self, priority: MessagePriority) -> &mut VecDeque<MSG> {
+        match priority {
+            MessagePriority::High => &mut self.high,
+            MessagePriority::Normal => &mut self.normal,
+            MessagePriority::Low => &mut self.low,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.high.len() + self.normal.len() + self.low.len()
+    }
+
+    fn pop_front(&mut self) -> Option<MSG> {
+        self.high.pop_front()
+            .or_else(|| self.normal.pop_front())
+            .or_else(|| self.low.pop_front())
+    }
+}
+
+impl<MSG> _EventStream<MSG> {
+    fn len(&self) -> usize {
+        self.events.len() + self.locked_events.len()
+    }
+
+    fn fmt_debug(&self, name: &str, formatter: &mut Formatter) -> fmt::Result {
+        formatter.debug_struct(name)
+            .field("name", &self.name)
+            .field("len", &self.len())
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+
+    fn remove_observer(&mut self, id: usize) {
+        self.observers.retain(|observer| {
+            if observer.id == id {
+                observer.active.set(false);
+            }
+            observer.id != id
+        });
+    }
+}
+
+impl<MSG> SourceFuncs for SourceData<MSG> {
+    fn dispatch(&self) -> bool {
+        let _ = dispatch_events(&self.stream, &self.callback);
+        true
+    }
+
+    fn prepare(&self) -> (bool, Option<u32>) {
+        (!self.stream.borrow().events.is_empty(), None)
+    }
+
+}
+
+type Callback<MSG> = Rc<RefCell<Option<Box<dyn FnMut(MSG)>>>>;
+
+type WeakCallback<MSG> = Weak<RefCell<Option<Box<dyn FnMut(MSG)>>>>;
+
+// Return the message replacing the last queued message and the new one, or give back the new one.
+type Coalescer<MSG> = Rc<dyn Fn(&MSG, MSG) -> Result<MSG, MSG>>;
+
+type PanicHandler = Rc<dyn Fn(Box<dyn Any + Send>)>;
+
+struct SourceData<MSG> {
+    callback: Callback<MSG>,
+    stream: Rc<RefCell<_EventStream<MSG>>>,
+}
+
+// Dispatch at most max_dispatch messages to the callback and return the number of dispatched
+// messages.
+fn dispatch_events<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, callback: &Callback<MSG>) -> usize {
+    let max_dispatch = stream.borrow().max_dispatch;
+    let mut dispatched = 0;
+    while dispatched < max_dispatch {
+        // The callback can emit new messages, so the stream must not be borrowed while
+        // calling it.
+        let event = stream.borrow_mut().events.pop_front();
+        match event {
+            Some(event) => {
+                #[cfg(feature = "trace")]
+                trace(stream, TraceKind::Dispatch, &event);
+                let after_update = after_update_observers(stream, &event);
+                if let Some(callback) = callback.borrow_mut().as_mut() {
+                    if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| callback(event))) {
+                        handle_panic(stream, error);
+                    }
+                }
+                if let Some((observers, event)) = after_update {
+                    call_observers(stream, &observers, &event);
+                }
+                dispatched += 1;
+            },
+            None => break,
+        }
+    }
+    dispatched
+}
+
+// Get the observers to call after the callback returns, with a clone of the message for them.
+fn after_update_observers<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, msg: &MSG)
+    -> Option<(Vec<Rc<Observer<MSG>>>, MSG)>
+{
+    let data = stream.borrow();
+    let clone_message = data.clone_message?;
+    let observers: Vec<_> = data.observers.iter()
+        .filter(|observer| observer.phase == ObservePhase::AfterUpdate)
+        .cloned()
+        .collect();
+    if observers.is_empty() {
+        None
+    }
+    else {
+        Some((observers, clone_message(msg)))
+    }
+}
+
+fn call_observers<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, observers: &[Rc<Observer<MSG>>], msg: &MSG) {
+    for observer in observers {
+        if observer.active.get() {
+            if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| (observer.callback)(msg))) {
+                handle_panic(stream, error);
+            }
+        }
+    }
+}
+
+fn dispatch_pending<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>) -> usize {
+    let callback = stream.borrow().callback.upgrade();
+    let callback =
+        match callback {
+            Some(callback) => callback,
+            None => return 0,
+        };
+    // The callback is borrowed when dispatch_pending() is called from the callback: the
+    // messages will be dispatched when it returns.
+    if callback.try_borrow_mut().is_err() {
+        return 0;
+    }
+    dispatch_events(stream, &callback)
+}
+
+fn emit<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, msg: MSG, priority: MessagePriority) {
+    {
+        let mut data = stream.borrow_mut();
+        if data.locked > 0 {
+            if data.queueing_locks > 0 {
+                data.locked_events.push_back((msg, priority));
+            }
+            return;
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    trace(stream, TraceKind::Emit, &msg);
+
+    // The messages are cloned and dropped while the stream is not mutably borrowed, so that their
+    // Clone and Drop implementations can close it.
+    let copy = stream.borrow().replay.as_ref().map(|replay| (replay.clone)(&msg));
+    if let Some(copy) = copy {
+        let removed = stream.borrow_mut().replay.as_mut().and_then(|replay| replay.push(copy));
+        drop(removed);
+    }
+
+    // Observers can add or remove observers and emit messages while they are called.
+    // The observers added during this emit() will only see the next messages.
+    let observers: Vec<_> = stream.borrow().observers.iter()
+        .filter(|observer| observer.phase == ObservePhase::BeforeUpdate)
+        .cloned()
+        .collect();
+    call_observers(stream, &observers, &msg);
+
+    // Only the messages of the same priority are coalesced.
+    let msg = {
+        let data = stream.borrow();
+        match (&data.coalescer, data.events.lane(priority).back()) {
+            (Some(coalescer), Some(last)) => coalescer(last, msg),
+            _ => Err(msg),
+        }
+    };
+    let replaced = {
+        let mut data = stream.borrow_mut();
+        let lane = data.events.lane_mut(priority);
+        match msg {
+            Ok(replacement) => lane.back_mut().map(|last| mem::replace(last, replacement)),
+            Err(msg) => {
+                lane.push_back(msg);
+                None
+            },
+        }
+    };
+    drop(replaced);
+}
+
+#[cfg(feature = "trace")]
+fn trace<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, kind: TraceKind, msg: &MSG) {
+    // The tracer can emit messages, so the stream must not be borrowed while calling it.
+    let tracer = stream.borrow().tracer.clone();
+    if let Some(tracer) = tracer {
+        tracer.trace(kind, msg);
+    }
+}
+
+fn handle_panic<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, error: Box<dyn Any + Send>) {
+    let panic_handler = stream.borrow().panic_handler.clone();
+    match panic_handler {
+        Some(panic_handler) => panic_handler(error),
+        None => panic::resume_unwind(error),
+    }
+}
+
+fn lock<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, queue_events: bool) -> Lock<MSG> {
+    let mut data = stream.borrow_mut();
+    data.locked += 1;
+    if queue_events {
+        data.queueing_locks += 1;
+    }
+    Lock {
+        queue_events,
+        stream: StreamHandle::new(Rc::downgrade(stream)),
+    }
+}
+
+fn unlock<MSG>(stream: &Rc<RefCell<_EventStream<MSG>>>, queue_events: bool) {
+    let locked_events = {
+        let mut data = stream.borrow_mut();
+        data.locked -= 1;
+        if queue_events {
+            data.queueing_locks -= 1;
+        }
+        if data.locked > 0 {
+            return;
+        }
+        mem::take(&mut data.locked_events)
+    };
+    for (msg, priority) in locked_events {
+        emit(stream, msg, priority);
+    }
+}
+
+fn observe<MSG, CALLBACK: Fn(&MSG) + 'static>(stream: &Rc<RefCell<_EventStream<MSG>>>, callback: CALLBACK)
+    -> ObserverHandle<MSG>
+{
+    add_observer(stream, ObservePhase::BeforeUpdate, callback)
+}
+
+fn observe_in_phase<MSG, CALLBACK>(stream: &Rc<RefCell<_EventStream<MSG>>>, phase: ObservePhase,
+    callback: CALLBACK) -> ObserverHandle<MSG>
+where MSG: Clone,
+      CALLBACK: Fn(&MSG) + 'static,
+{
+    if phase == ObservePhase::AfterUpdate {
+        stream.borrow_mut().clone_message = Some(MSG::clone);
+    }
+    add_observer(stream, phase, callback)
+}
+
+fn add_observer<MSG, CALLBACK: Fn(&MSG) + 'static>(stream: &Rc<RefCell<_EventStream<MSG>>>, phase: ObservePhase,
+    callback: CALLBACK) -> ObserverHandle<MSG>
+{
+    let observer = Rc::new(Observer {
+        active: Cell::new(true),
+        callback: Box::new(callback),
+        id: stream.borrow().next_observer_id,
+        phase,
+    });
+    let replayed = {
+        let mut data = stream.borrow_mut();
+        data.next_observer_id += 1;
+        data.observers.push(observer.clone());
+        data.replay.as_ref().map(ReplayBuffer::messages).unwrap_or_default()
+    };
+    // Like in emit(), the stream is not borrowed while calling the observer.
+    let observers = [observer.clone()];
+    for msg in replayed {
+        call_observers(stream, &observers, &msg);
+    }
+    let id = observer.id;
+    ObserverHandle {
+        id,
+        remove_on_drop: false,
+        stream: Rc::downgrade(stream),
+    }
+}
+
+fn observe_once<MSG: 'static, CALLBACK: FnOnce(&MSG) + 'static>(stream: &Rc<RefCell<_EventStream<MSG>>>,
+    callback: CALLBACK) -> ObserverHandle<MSG>
+{
+    let callback = Cell::new(Some(callback));
+    let id = stream.borrow().next_observer_id;
+    let weak_stream = Rc::downgrade(stream);
+    observe(stream, move |msg| {
+        // Taking the callback out makes sure it is only called once, even if a message
+        // is emitted reentrantly before the observer is removed.
+        if let Some(callback) = callback.take() {
+            if let Some(stream) = weak_stream.upgrade() {
+                stream.borrow_mut().remove_observer(id);
+            }
+            callback(msg);
+        }
+    })
+}
+
+// Each observer gets its own clone, only made when the predicate accepts the message.
+fn observe_filtered<MSG, PREDICATE, CALLBACK>(stream: &Rc<RefCell<_EventStream<MSG>>>, predicate: PREDICATE,
+    callback: CALLBACK) -> ObserverHandle<MSG>
+where MSG: Clone,
+      PREDICATE: Fn(&MSG) -> bool + 'static,
+      CALLBACK: Fn(MSG) + 'static,
+{
+    observe(stream, move |msg| {
+        if predicate(msg) {
+            callback(msg.clone());
+        }
+    })
+}
+
+/// A builder to create an `EventStream` with non-default options.
+pub struct EventStreamBuilder<MSG> {
+    can_recurse: bool,
+    context: MainContext,
+    name: Option<String>,
+    priority: Priority,
+    _phantom: PhantomData<*mut MSG>,
+}
+
+impl<MSG> EventStreamBuilder<MSG> {
+    /// Allow dispatching messages from a nested main loop, e.g. while a dialog is run from the
+    /// callback.
+    pub fn can_recurse(mut self, can_recurse: bool) -> Self {
+        self.can_recurse = can_recurse;
+        self
+    }
+
+    /// Dispatch the messages from the main loop of `context` instead of the default one.
+    pub fn context(mut self, context: &MainContext) -> Self {
+        self.context = context.clone();
+        self
+    }
+
+    /// Set the name of the glib source, shown by debugging and profiling tools.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Set the priority at which the messages are dispatched.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Create the event stream.
+    pub fn build(self) -> EventStream<MSG> {
+        let stream = EventStream::create(Some(&self.context), self.priority, self.can_recurse);
+        if let Some(ref name) = self.name {
+            stream.set_name(name);
+        }
+        stream
+    }
+}
+
+/// A stream of messages to be used for widget/signal communication and inter-widget communication.
 /// EventStream cannot be send to another thread. Use a `Channel` `Sender` instead.
-pub struct EventStream<MSG> {
+pub struct EventStream<MSG: 'static> {
+    // Set by close(), so that closing is idempotent and the source is only destroyed once.
+    closed: Cell<bool>,
     source: Source,
-    source_id: Option<SourceId>,
     _phantom: PhantomData<*mut MSG>,
 }
 
-impl<MSG> Drop for EventStream<MSG> {
+impl<MSG: 'static> Debug for EventStream<MSG> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.get_stream().try_borrow() {
+            Ok(stream) => stream.fmt_debug("EventStream", formatter),
+            Err(_) => formatter.write_str("EventStream { .. }"),
+        }
+    }
+}
+
+impl<MSG: 'static> Drop for EventStream<MSG> {
     fn drop(&mut self) {
-        // Ignore error since we're in a destructor.
-        let _ = Source::remove(self.source_id.take().expect("source id"));
+        if self.closed.get() {
+            return;
+        }
+        #[cfg(feature = "trace")]
+        {
+            let discarded = self.close();
+            if discarded > 0 {
+                let name = self.get_stream().try_borrow().ok().and_then(|stream| stream.name.clone());
+                log::debug!("EventStream {:?} dropped with {} pending messages", name, discarded);
+            }
+        }
+        #[cfg(not(feature = "trace"))]
         self.close();
     }
 }
 
-impl<MSG> EventStream<MSG> {
+impl<MSG: 'static> EventStream<MSG> {
     fn get_callback(&self) -> Callback<MSG> {
         source_get::<SourceData<MSG>>(&self.source).callback.clone()
     }
@@ -278,30 +1673,238 @@ impl<MSG> EventStream<MSG> {
     }
 }
 
-impl<MSG> EventStream<MSG> {
+impl<MSG: 'static> EventStream<MSG> {
     /// Create a new event stream.
+    ///
+    /// ## Panics
+    /// Panics if the messages could not be dispatched from this thread, see
+    /// [`try_new()`](struct.EventStream.html#method.try_new) to get an error instead.
     pub fn new() -> Self {
+        Self::try_new()
+            .unwrap_or_else(|error| panic!("Cannot create the EventStream: {}", error))
+    }
+
+    /// Create a new event stream, whose messages are dispatched from the default main context.
+    /// Return an error if GTK was initialized from another thread, if another thread owns the
+    /// default main context, or if GTK is not initialized and the current thread doesn't own the
+    /// default main context (by running its main loop or by acquiring it).
+    pub fn try_new() -> Result<Self, StreamError> {
+        if gtk::is_initialized() && !gtk::is_initialized_main_thread() {
+            return Err(StreamError::NotMainThread);
+        }
+        let context = MainContext::default();
+        if !context.is_owner() {
+            if !gtk::is_initialized() {
+                return Err(StreamError::NotInitialized);
+            }
+            // The main loop acquires the context when it starts: only check that no other thread
+            // holds it.
+            if !context.acquire() {
+                return Err(StreamError::ContextNotOwned);
+            }
+            context.release();
+        }
+        Ok(Self::with_context(&context))
+    }
+
+    /// Create a new event stream whose messages are dispatched from the main loop of `context`.
+    /// This is useful to use relm from a thread that runs its own `MainContext`.
+    pub fn with_context(context: &MainContext) -> Self {
+        Self::create(Some(context), Priority::default(), false)
+    }
+
+    /// Create a new event stream that is not attached to any main context: its messages are
+    /// only dispatched by [`dispatch_pending()`](struct.EventStream.html#method.dispatch_pending)
+    /// or taken by [`drain_pending()`](struct.EventStream.html#method.drain_pending).
+    /// This stream can be used without a main loop and without initializing GTK, for instance to
+    /// unit test the `update()` method of a component (see the [`test`](test/index.html) module).
+    pub fn new_detached() -> Self {
+        Self::create(None, Priority::default(), false)
+    }
+
+    /// Create a builder to configure the event stream.
+    pub fn builder() -> EventStreamBuilder<MSG> {
+        EventStreamBuilder {
+            can_recurse: false,
+            context: MainContext::default(),
+            name: None,
+            priority: Priority::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn create(context: Option<&MainContext>, priority: Priority, can_recurse: bool) -> Self {
+        let callback = Rc::new(RefCell::new(None));
         let event_stream: _EventStream<MSG> = _EventStream {
-            events: VecDeque::new(),
-            locked: false,
+            callback: Rc::downgrade(&callback),
+            coalescer: None,
+            context: context.cloned(),
+            events: Lanes::new(),
+            locked: 0,
+            max_dispatch: DEFAULT_MAX_DISPATCH,
+            name: None,
+            on_close: None,
+            panic_handler: None,
+            locked_events: VecDeque::new(),
+            queueing_locks: 0,
+            next_observer_id: 0,
+            clone_message: None,
             observers: vec![],
+            replay: None,
+            signal_handlers: vec![],
+            #[cfg(feature = "trace")]
+            tracer: None,
         };
-        let source = new_source(SourceData {
-            callback: Rc::new(RefCell::new(None)),
+        let source = new_source_full(SourceData {
+            callback,
             stream: Rc::new(RefCell::new(event_stream)),
-        });
-        let main_context = MainContext::default();
-        let source_id = Some(source.attach(Some(&main_context)));
+        }, priority, can_recurse);
+        if let Some(context) = context {
+            source.attach(Some(context));
+        }
         EventStream {
+            closed: Cell::new(false),
             source,
-            source_id,
             _phantom: PhantomData,
         }
     }
 
+    /// Get the number of messages waiting to be dispatched.
+    pub fn len(&self) -> usize {
+        self.get_stream().borrow().len()
+    }
+
+    /// Check if there's no message waiting to be dispatched.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discard all the messages waiting to be dispatched, without sending them to the callback.
+    pub fn clear(&self) {
+        // The messages are dropped after the stream is released, like in emit().
+        let messages = {
+            let mut stream = self.get_stream().borrow_mut();
+            (stream.events.drain(), mem::take(&mut stream.locked_events))
+        };
+        drop(messages);
+    }
+
     /// Close the event stream, i.e. stop processing messages.
-    pub fn close(&self) {
+    /// Return the number of messages that were waiting to be dispatched and are discarded.
+    ///
+    /// When the stream is borrowed, i.e. when it is closed from a coalescer, the messages cannot be
+    /// taken out of it: they are never dispatched and are dropped with the stream instead.
+    pub fn close(&self) -> usize {
+        match self.close_taking() {
+            Ok(pending) => pending.len(),
+            Err(discarded) => discarded,
+        }
+    }
+
+    /// Close the event stream like `close()`, but first send the messages waiting to be dispatched
+    /// to the callback, so that a message like a save on quit is not lost.
+    /// Return the number of messages that could not be dispatched and are discarded, which only
+    /// happens when called from the callback itself.
+    pub fn close_flushing(&self) -> usize {
+        // The callback can emit new messages while flushing.
+        loop {
+            if self.is_empty() {
+                break;
+            }
+            // Nothing is dispatched when called from the callback.
+            if self.dispatch_pending() == 0 {
+                break;
+            }
+        }
+        self.close()
+    }
+
+    /// Close the event stream like `close()` and return the messages that were waiting to be
+    /// dispatched, in the order they would be dispatched.
+    /// The messages queued by a [`lock_queueing()`](struct.StreamHandle.html#method.lock_queueing)
+    /// still held are included, after the other messages of the same priority.
+    ///
+    /// When the stream is borrowed (see [`close()`](#method.close)), the messages cannot be
+    /// returned: a warning with their number is logged instead.
+    pub fn close_draining(&self) -> Vec<MSG> {
+        match self.close_taking() {
+            Ok(pending) => pending,
+            Err(discarded) => {
+                log::warn!("EventStream {:?} closed while borrowed: {} pending messages are discarded",
+                    self.get_stream().try_borrow().ok().and_then(|stream| stream.name.clone()), discarded);
+                vec![]
+            },
+        }
+    }
+
+    /*
+     * Close the stream and take the messages waiting to be dispatched.
+     * If the stream is borrowed, only the number of these messages is returned. They are never
+     * dispatched since the source is destroyed and the callback removed, and are dropped with the
+     * stream.
+     */
+    fn close_taking(&self) -> Result<Vec<MSG>, usize> {
+        if self.closed.replace(true) {
+            return Ok(vec![]);
+        }
+        self.run_on_close();
+        let pending =
+            if let Ok(mut stream) = self.get_stream().try_borrow_mut() {
+                stream.callback = Weak::new();
+                let locked_events = mem::take(&mut stream.locked_events);
+                for (msg, priority) in locked_events {
+                    stream.events.lane_mut(priority).push_back(msg);
+                }
+                Ok(stream.events.drain())
+            }
+            else {
+                // Not dispatching through the handles either, unless closed from the callback
+                // itself which doesn't borrow the stream.
+                if let Ok(mut callback) = self.get_callback().try_borrow_mut() {
+                    *callback = None;
+                }
+                // The messages are not cloned nor dropped while the stream is mutably borrowed, so
+                // it can only be borrowed immutably here, e.g. by a coalescer.
+                Err(self.get_stream().try_borrow().map(|stream| stream.len()).unwrap_or_default())
+            };
         self.source.destroy();
+        pending
+    }
+
+    /// Dispatch the messages waiting in the stream to its callback now, instead of waiting for
+    /// the next iteration of the main loop.
+    /// See [`StreamHandle::dispatch_pending()`](struct.StreamHandle.html#method.dispatch_pending).
+    pub fn dispatch_pending(&self) -> usize {
+        dispatch_pending(self.get_stream())
+    }
+
+    /// Remove the messages waiting to be dispatched and return them, in the order they would be
+    /// dispatched.
+    pub fn drain_pending(&self) -> Vec<MSG> {
+        self.get_stream().borrow_mut().events.drain()
+    }
+
+    /// Set a function called once, right before the stream is closed or dropped.
+    pub fn on_close<CALLBACK: FnOnce() + 'static>(&self, callback: CALLBACK) {
+        self.get_stream().borrow_mut().on_close = Some(Box::new(callback));
+    }
+
+    fn run_on_close(&self) {
+        // Don't panic if the stream is borrowed since this is called from the destructor.
+        let on_close = self.get_stream().try_borrow_mut().ok().and_then(|mut stream| stream.on_close.take());
+        if let Some(on_close) = on_close {
+            on_close();
+        }
+    }
+
+    // Disconnect the signal handlers recorded by add_signal_handler() whose object is still alive.
+    pub(crate) fn disconnect_signal_handlers(&self) {
+        let signal_handlers = mem::take(&mut self.get_stream().borrow_mut().signal_handlers);
+        for (object, handler_id) in signal_handlers {
+            if let Some(object) = object.upgrade() {
+                object.disconnect(handler_id);
+            }
+        }
     }
 
     /// Synonym for downgrade().
@@ -317,23 +1920,194 @@ impl<MSG> EventStream<MSG> {
     /// Send the `event` message to the stream and the observers.
     pub fn emit(&self, event: MSG) {
         let stream = self.get_stream();
-        emit(stream, event)
+        emit(stream, event, MessagePriority::Normal)
+    }
+
+    /// Send the `event` message to the stream and the observers, to be dispatched before the
+    /// messages of lower `priority`.
+    /// Messages of the same priority are dispatched in the order they were emitted.
+    pub fn emit_with_priority(&self, event: MSG, priority: MessagePriority) {
+        emit(self.get_stream(), event, priority)
     }
 
     /// Lock the stream (don't emit message) until the `Lock` goes out of scope.
     pub fn lock(&self) -> Lock<MSG> {
-        let stream = self.get_stream();
-        stream.borrow_mut().locked = true;
-        Lock {
-            stream: self.stream(),
-        }
+        lock(self.get_stream(), false)
+    }
+
+    /// Lock the stream until the `Lock` goes out of scope.
+    /// In contrast to `lock()`, the messages emitted while the stream is locked are not dropped:
+    /// they are emitted when the `Lock` is released.
+    pub fn lock_queueing(&self) -> Lock<MSG> {
+        lock(self.get_stream(), true)
     }
 
     /// Add an observer to the event stream.
     /// This callback will be called every time a message is emmited.
-    pub fn observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK) {
-        let stream = self.get_stream();
-        stream.borrow_mut().observers.push(Rc::new(callback));
+    /// The returned handle can be used to remove the observer.
+    pub fn observe<CALLBACK: Fn(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG> {
+        observe(self.get_stream(), callback)
+    }
+
+    /// Add an observer that is called for the next emitted message only and is then removed.
+    pub fn observe_once<CALLBACK: FnOnce(&MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: 'static,
+    {
+        observe_once(self.get_stream(), callback)
+    }
+
+    /// Add an observer called in the specified `phase`: with `ObservePhase::AfterUpdate`, the
+    /// observer is called when the message is dispatched, after the callback returns.
+    /// The returned handle can be used to remove the observer.
+    pub fn observe_in_phase<CALLBACK: Fn(&MSG) + 'static>(&self, phase: ObservePhase, callback: CALLBACK)
+        -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        observe_in_phase(self.get_stream(), phase, callback)
+    }
+
+    /// Add an observer receiving its own clone of every emitted message, for instance to send it
+    /// to another thread.
+    /// The returned handle can be used to remove the observer.
+    pub fn observe_owned<CALLBACK: Fn(MSG) + 'static>(&self, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+    {
+        observe_filtered(self.get_stream(), |_| true, callback)
+    }
+
+    /// Add an observer receiving its own clone of the emitted messages for which `predicate`
+    /// returns `true`: the other messages are not cloned.
+    /// The returned handle can be used to remove the observer.
+    pub fn observe_filtered<PREDICATE, CALLBACK>(&self, predicate: PREDICATE, callback: CALLBACK) -> ObserverHandle<MSG>
+    where MSG: Clone,
+          PREDICATE: Fn(&MSG) -> bool + 'static,
+          CALLBACK: Fn(MSG) + 'static,
+    {
+        observe_filtered(self.get_stream(), predicate, callback)
+    }
+
+    /// Get a `futures::Stream` of the messages emitted from now on.
+    /// The messages are buffered until they are polled, without limit.
+    #[cfg(feature = "async")]
+    pub fn observe_stream(&self) -> MessageStream<MSG>
+    where MSG: Clone + 'static,
+    {
+        MessageStream::new(self, None)
+    }
+
+    /// Get a `futures::Stream` of the messages emitted from now on.
+    /// At most `capacity` messages are buffered: when the buffer is full, the oldest message is
+    /// discarded.
+    ///
+    /// ## Panics
+    /// Panics if `capacity` is 0.
+    #[cfg(feature = "async")]
+    pub fn observe_stream_bounded(&self, capacity: usize) -> MessageStream<MSG>
+    where MSG: Clone + 'static,
+    {
+        assert!(capacity > 0, "the capacity of a MessageStream must not be 0");
+        MessageStream::new(self, Some(capacity))
+    }
+
+    /// Remove an observer previously added with `observe()`.
+    /// Does nothing if the observer belongs to another stream or was already removed.
+    pub fn remove_observer(&self, observer: ObserverHandle<MSG>) {
+        self.downgrade().remove_observer(observer);
+    }
+
+    /// Set the maximum number of messages sent to the callback in a single iteration of the main
+    /// loop (32 by default).
+    /// Use `usize::MAX` to send all the queued messages at once.
+    pub fn set_max_dispatch(&self, max_dispatch: usize) {
+        // Dispatching no message at all would make the main loop spin forever.
+        self.get_stream().borrow_mut().max_dispatch = max_dispatch.max(1);
+    }
+
+    /// Set a function called with the panic payload when the callback or an observer panics.
+    /// The panic is then considered handled and the next messages are still dispatched.
+    /// Without a handler, the panic is propagated and the process is aborted if it reaches the
+    /// main loop.
+    pub fn set_panic_handler<HANDLER: Fn(Box<dyn Any + Send>) + 'static>(&self, handler: HANDLER) {
+        self.get_stream().borrow_mut().panic_handler = Some(Rc::new(handler));
+    }
+
+    /// Set a function merging a new message into the last message waiting to be dispatched.
+    /// It receives the waiting message and the new one and returns the message replacing both,
+    /// or `None` to queue the new message as usual.
+    /// The observers still receive every message.
+    ///
+    /// The function is called while the stream is borrowed, so it must not emit messages to it.
+    pub fn set_coalescer<COALESCER>(&self, coalescer: COALESCER)
+        where COALESCER: Fn(&MSG, &MSG) -> Option<MSG> + 'static,
+    {
+        self.get_stream().borrow_mut().coalescer = Some(Rc::new(move |last, msg| {
+            coalescer(last, &msg).ok_or(msg)
+        }));
+    }
+
+    /// Replace the last message waiting to be dispatched by the new one when `predicate` returns
+    /// `true` for them, so that only the latest of them is dispatched.
+    /// For instance, use `|last, msg| mem::discriminant(last) == mem::discriminant(msg)` to keep
+    /// only the latest message of each variant.
+    pub fn coalesce_matching<PREDICATE>(&self, predicate: PREDICATE)
+        where PREDICATE: Fn(&MSG, &MSG) -> bool + 'static,
+    {
+        self.get_stream().borrow_mut().coalescer = Some(Rc::new(move |last, msg| {
+            if predicate(last, &msg) {
+                Ok(msg)
+            }
+            else {
+                Err(msg)
+            }
+        }));
+    }
+
+    /// Keep the last `count` emitted messages to send them to the observers added afterwards, for
+    /// instance so that a child component created later still receives the configuration
+    /// loaded at startup.
+    /// The messages are sent synchronously when the observer is added, including by
+    /// `forward_to()` and the other forwarding methods.
+    /// A `count` of 0, the default, disables the buffer.
+    pub fn set_replay(&self, count: usize)
+        where MSG: Clone,
+    {
+        let mut stream = self.get_stream().borrow_mut();
+        if count == 0 {
+            stream.replay = None;
+            return;
+        }
+        let replay = stream.replay.get_or_insert_with(|| ReplayBuffer {
+            capacity: count,
+            clone: MSG::clone,
+            messages: VecDeque::with_capacity(count),
+        });
+        replay.capacity = count;
+        while replay.messages.len() > count {
+            replay.messages.pop_front();
+        }
+    }
+
+    /// Set the name of the underlying glib source, shown by debugging and profiling tools.
+    pub fn set_name(&self, name: &str) {
+        set_name(&self.source, name);
+        self.get_stream().borrow_mut().name = Some(name.to_string());
+    }
+
+    // Trace the messages of the component owning this stream.
+    #[cfg(feature = "trace")]
+    pub(crate) fn set_tracer(&self, tracer: StreamTracer<MSG>) {
+        self.get_stream().borrow_mut().tracer = Some(tracer);
+    }
+
+    /// Get the priority at which the messages are dispatched.
+    pub fn priority(&self) -> Priority {
+        from_glib(self.source.get_priority())
+    }
+
+    /// Set the priority at which the messages are dispatched (`PRIORITY_DEFAULT` by default).
+    /// Use `PRIORITY_LOW` for streams that should not delay the redraws of GTK.
+    pub fn set_priority(&self, priority: Priority) {
+        set_priority(&self.source, priority);
     }
 
     /// Add a callback to the event stream.
@@ -0,0 +1,87 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::mpsc::{self, Receiver, SendError};
+
+use glib::MainContext;
+
+use super::{ChannelReceiver, SendWakers, StreamHandle, attach_channel_source};
+
+/// Create a channel to send a single value to a relm widget from another thread.
+pub fn oneshot<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    oneshot_with_context(&MainContext::default())
+}
+
+/// Create a oneshot channel whose value is received from the main loop of `context`.
+pub fn oneshot_with_context<T>(context: &MainContext) -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let (sender, receiver) = mpsc::channel();
+    (OneshotSender {
+        context: context.clone(),
+        sender,
+    }, OneshotReceiver {
+        context: context.clone(),
+        receiver,
+    })
+}
+
+/// The sending half of a channel created by [`oneshot()`](fn.oneshot.html).
+pub struct OneshotSender<T> {
+    context: MainContext,
+    sender: mpsc::Sender<T>,
+}
+
+impl<T> OneshotSender<T> {
+    /// Send the value and wakeup the event loop.
+    /// Return an error if the `OneshotReceiver` or the source created by `on_receive()` was
+    /// dropped.
+    pub fn send(self, value: T) -> Result<(), SendError<T>> {
+        self.sender.send(value)?;
+        self.context.wakeup();
+        Ok(())
+    }
+}
+
+/// The receiving half of a channel created by [`oneshot()`](fn.oneshot.html).
+pub struct OneshotReceiver<T> {
+    context: MainContext,
+    receiver: Receiver<T>,
+}
+
+impl<T: 'static> OneshotReceiver<T> {
+    /// Emit the message returned by `callback` to `stream` when the value is received.
+    /// The source used to receive the value is removed from the main loop after that, or when
+    /// the `OneshotSender` is dropped without sending a value.
+    pub fn on_receive<MSG, CALLBACK, STREAM>(self, stream: &STREAM, callback: CALLBACK)
+    where MSG: 'static,
+          CALLBACK: FnOnce(T) -> MSG + 'static,
+          STREAM: AsRef<StreamHandle<MSG>>,
+    {
+        let stream = stream.as_ref().clone();
+        let mut callback = Some(callback);
+        // The main context keeps the source alive until the sender is dropped.
+        let _source = attach_channel_source(&self.context, ChannelReceiver::Std(self.receiver), move |value| {
+            if let Some(callback) = callback.take() {
+                // The widget might have been destroyed in the meantime.
+                let _ = stream.try_emit(callback(value));
+            }
+        }, None, SendWakers::default());
+    }
+}
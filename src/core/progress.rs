@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SendError};
+
+use glib::{MainContext, Source};
+
+use super::{CancellationToken, ChannelReceiver, SendWakers, StreamHandle, attach_channel_source, is_cancelled};
+
+enum Progress<PROGRESS, RESULT> {
+    Tick(PROGRESS),
+    Done(RESULT),
+}
+
+/// Create a channel to report the progress of a task running in another thread, and then its
+/// result.
+/// The messages returned by `map_progress` for each progress and by `map_done` for the result are
+/// emitted to `stream`.
+/// The source used to receive the values is removed from the main loop after the result is
+/// received, or when all the `ProgressSender`s are dropped.
+pub fn progress_channel<PROGRESS, RESULT, MSG, STREAM, MAP, DONE>(stream: &STREAM, map_progress: MAP, map_done: DONE)
+    -> ProgressSender<PROGRESS, RESULT>
+where PROGRESS: 'static,
+      RESULT: 'static,
+      MSG: 'static,
+      STREAM: AsRef<StreamHandle<MSG>>,
+      MAP: Fn(PROGRESS) -> MSG + 'static,
+      DONE: FnOnce(RESULT) -> MSG + 'static,
+{
+    let context = MainContext::default();
+    let (sender, receiver) = mpsc::channel();
+    let stream = stream.as_ref().clone();
+    let mut map_done = Some(map_done);
+    // Set after the source is attached, so that it can remove itself after receiving the result.
+    let own_source: Rc<RefCell<Option<Source>>> = Rc::new(RefCell::new(None));
+    // Break the reference cycle between the source and its callback when the senders are dropped.
+    let on_disconnect = {
+        let own_source = own_source.clone();
+        Box::new(move || {
+            let _ = own_source.borrow_mut().take();
+        })
+    };
+    let source = {
+        let own_source = own_source.clone();
+        attach_channel_source(&context, ChannelReceiver::Std(receiver), move |value| {
+            match value {
+                Progress::Tick(progress) => {
+                    let _ = stream.try_emit(map_progress(progress));
+                },
+                Progress::Done(result) => {
+                    if let Some(map_done) = map_done.take() {
+                        // The widget might have been destroyed in the meantime.
+                        let _ = stream.try_emit(map_done(result));
+                    }
+                    if let Some(source) = own_source.borrow_mut().take() {
+                        source.destroy();
+                    }
+                },
+            }
+        }, Some(on_disconnect), SendWakers::default())
+    };
+    *own_source.borrow_mut() = Some(source);
+    ProgressSender {
+        cancellation: None,
+        context,
+        finished: Arc::new(AtomicBool::new(false)),
+        sender,
+    }
+}
+
+/// The sending half of a channel created by [`progress_channel()`](fn.progress_channel.html).
+/// It can be cloned to report the progress from several threads.
+pub struct ProgressSender<PROGRESS, RESULT> {
+    cancellation: Option<CancellationToken>,
+    context: MainContext,
+    // Shared by the clones, so that only one result is sent.
+    finished: Arc<AtomicBool>,
+    sender: mpsc::Sender<Progress<PROGRESS, RESULT>>,
+}
+
+impl<PROGRESS, RESULT> Clone for ProgressSender<PROGRESS, RESULT> {
+    fn clone(&self) -> Self {
+        Self {
+            cancellation: self.cancellation.clone(),
+            context: self.context.clone(),
+            finished: self.finished.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<PROGRESS, RESULT> ProgressSender<PROGRESS, RESULT> {
+    /// Send a progress value and wakeup the event loop.
+    /// Do nothing if the result was already sent by [`finish()`](struct.ProgressSender.html#method.finish)
+    /// or if the `CancellationToken` given to
+    /// [`with_cancellation()`](struct.ProgressSender.html#method.with_cancellation) was cancelled.
+    /// Return an error if the source receiving the values was removed.
+    pub fn progress(&self, progress: PROGRESS) -> Result<(), SendError<PROGRESS>> {
+        if self.finished.load(Ordering::SeqCst) || is_cancelled(&self.cancellation) {
+            return Ok(());
+        }
+        self.send(Progress::Tick(progress))
+            .map_err(|error| match error.0 {
+                Progress::Tick(progress) => SendError(progress),
+                Progress::Done(_) => unreachable!(),
+            })
+    }
+
+    /// Send the result and wakeup the event loop.
+    /// Only the first result is sent, from any of the clones of this sender: the next calls to
+    /// `finish()` and [`progress()`](struct.ProgressSender.html#method.progress) do nothing.
+    /// Nothing is sent either if the `CancellationToken` was cancelled.
+    /// Return an error if the source receiving the values was removed.
+    pub fn finish(&self, result: RESULT) -> Result<(), SendError<RESULT>> {
+        if self.finished.swap(true, Ordering::SeqCst) || is_cancelled(&self.cancellation) {
+            return Ok(());
+        }
+        self.send(Progress::Done(result))
+            .map_err(|error| match error.0 {
+                Progress::Done(result) => SendError(result),
+                Progress::Tick(_) => unreachable!(),
+            })
+    }
+
+    /// Bind the sender to `token`, so that the progress and result sent after it is cancelled are
+    /// dropped.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
+    fn send(&self, value: Progress<PROGRESS, RESULT>) -> Result<(), SendError<Progress<PROGRESS, RESULT>>> {
+        self.sender.send(value)?;
+        self.context.wakeup();
+        Ok(())
+    }
+}
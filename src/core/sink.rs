@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::pin::Pin;
+use std::sync::mpsc::{SendError, TrySendError};
+use std::task::{Context, Poll};
+
+use futures::Sink;
+use futures::future::poll_fn;
+
+use super::{Sender, SyncSender};
+
+impl<MSG> Sender<MSG> {
+    /// Send a message and wakeup the event loop.
+    /// As the channel is unbounded, this never waits.
+    pub async fn send_async(&self, msg: MSG) -> Result<(), SendError<MSG>> {
+        self.send(msg)
+    }
+}
+
+impl<MSG> Sink<MSG> for Sender<MSG> {
+    type Error = SendError<MSG>;
+
+    fn poll_ready(self: Pin<&mut Self>, _context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: MSG) -> Result<(), Self::Error> {
+        self.send(msg)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<MSG> SyncSender<MSG> {
+    /// Send a message and wakeup the event loop.
+    /// Wait without blocking the thread while the channel is full.
+    pub async fn send_async(&self, msg: MSG) -> Result<(), SendError<MSG>> {
+        let mut msg = Some(msg);
+        poll_fn(|context| {
+            let pending_msg = msg.take().expect("message");
+            match self.poll_send(pending_msg, context) {
+                Ok(None) => Poll::Ready(Ok(())),
+                Ok(Some(pending_msg)) => {
+                    msg = Some(pending_msg);
+                    Poll::Pending
+                },
+                Err(error) => Poll::Ready(Err(error)),
+            }
+        }).await
+    }
+
+    /// Convert to a `futures::Sink` which waits without blocking the thread while the channel is
+    /// full.
+    pub fn into_sink(self) -> SyncSink<MSG> {
+        SyncSink {
+            pending: None,
+            sender: self,
+        }
+    }
+
+    // Return the message back if the channel is full: the task will be woken up when there's
+    // room for it.
+    fn poll_send(&self, msg: MSG, context: &mut Context) -> Result<Option<MSG>, SendError<MSG>> {
+        match self.try_send(msg) {
+            Ok(()) => Ok(None),
+            Err(TrySendError::Full(msg)) => {
+                self.send_wakers.lock().expect("lock send wakers").push(context.waker().clone());
+                // Try again in case the channel was emptied before the waker was registered.
+                match self.try_send(msg) {
+                    Ok(()) => Ok(None),
+                    Err(TrySendError::Full(msg)) => Ok(Some(msg)),
+                    Err(TrySendError::Disconnected(msg)) => Err(SendError(msg)),
+                }
+            },
+            Err(TrySendError::Disconnected(msg)) => Err(SendError(msg)),
+        }
+    }
+}
+
+/// A `futures::Sink` sending messages to a bounded `Channel`, created by
+/// [`SyncSender::into_sink()`](struct.SyncSender.html#method.into_sink).
+pub struct SyncSink<MSG> {
+    // The message waiting for room in the channel.
+    pending: Option<MSG>,
+    sender: SyncSender<MSG>,
+}
+
+// The pending message is never pinned.
+impl<MSG> Unpin for SyncSink<MSG> {
+}
+
+impl<MSG> SyncSink<MSG> {
+    /// Get back the `SyncSender`.
+    /// A message that was not sent yet is dropped.
+    pub fn into_inner(self) -> SyncSender<MSG> {
+        self.sender
+    }
+}
+
+impl<MSG> Sink<MSG> for SyncSink<MSG> {
+    type Error = SendError<MSG>;
+
+    fn poll_ready(self: Pin<&mut Self>, context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if let Some(msg) = this.pending.take() {
+            match this.sender.poll_send(msg, context) {
+                Ok(None) => (),
+                Ok(Some(msg)) => {
+                    this.pending = Some(msg);
+                    return Poll::Pending;
+                },
+                Err(error) => return Poll::Ready(Err(error)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: MSG) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        match this.sender.try_send(msg) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(msg)) => {
+                this.pending = Some(msg);
+                Ok(())
+            },
+            Err(TrySendError::Disconnected(msg)) => Err(SendError(msg)),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(context)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, context: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(context)
+    }
+}
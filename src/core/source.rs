@@ -19,13 +19,23 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+use std::any::TypeId;
 use std::mem;
 use std::os::raw::c_int;
+use std::process;
 use std::ptr;
 
-use glib::Source;
-use glib::translate::{ToGlibPtr, from_glib_full};
-use glib_sys::{GSource, GSourceFunc, GSourceFuncs, g_source_new};
+use glib::{Priority, Source};
+use glib::translate::{ToGlib, ToGlibPtr, from_glib_full};
+use glib_sys::{
+    GSource,
+    GSourceFunc,
+    GSourceFuncs,
+    g_source_new,
+    g_source_set_can_recurse,
+    g_source_set_name,
+    g_source_set_priority,
+};
 
 pub trait SourceFuncs {
     fn check(&self) -> bool {
@@ -36,14 +46,36 @@ pub trait SourceFuncs {
     fn prepare(&self) -> (bool, Option<u32>);
 }
 
+/// Abort the process if dropped: used to avoid unwinding a panic through the C code of glib,
+/// which is undefined behavior.
+struct Bomb;
+
+impl Bomb {
+    fn defuse(self) {
+        mem::forget(self);
+    }
+}
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        process::abort();
+    }
+}
+
+// repr(C) so that the GSource is first and the type tag is at the same offset for every T.
+#[repr(C)]
 struct SourceData<T> {
     _source: GSource,
     funcs: Box<GSourceFuncs>,
+    // Type of the payload, to check the casts.
+    type_id: TypeId,
     data: T,
 }
 
-pub fn new_source<T: SourceFuncs>(data: T) -> Source {
-    unsafe {
+/// Create a source with the given `priority` which can be dispatched recursively from a nested
+/// main loop if `can_recurse` is true.
+pub fn new_source_full<T: SourceFuncs + 'static>(data: T, priority: Priority, can_recurse: bool) -> Source {
+    let source = unsafe {
         let mut funcs: GSourceFuncs = mem::zeroed();
         funcs.prepare = Some(prepare::<T>);
         funcs.check = Some(check::<T>);
@@ -53,36 +85,86 @@ pub fn new_source<T: SourceFuncs>(data: T) -> Source {
         let source = g_source_new(&mut *funcs, mem::size_of::<SourceData<T>>() as u32);
         ptr::write(&mut (*(source as *mut SourceData<T>)).data, data);
         ptr::write(&mut (*(source as *mut SourceData<T>)).funcs, funcs);
+        ptr::write(&mut (*(source as *mut SourceData<T>)).type_id, TypeId::of::<T>());
         from_glib_full(source)
+    };
+    set_priority(&source, priority);
+    set_can_recurse(&source, can_recurse);
+    source
+}
+
+/// Get the payload of a source created by `new_source_full()`.
+///
+/// ## Panics
+/// In debug builds, panics if the payload is not of type `T`.
+pub fn source_get<T: SourceFuncs + 'static>(source: &Source) -> &T {
+    if cfg!(debug_assertions) {
+        try_source_get(source).expect("the source payload has another type")
+    }
+    else {
+        unsafe { &(*(source.to_glib_none().0 as *const SourceData<T>)).data }
+    }
+}
+
+/// Get the payload of a source created by `new_source_full()`.
+/// Return `None` if the payload is not of type `T`.
+pub fn try_source_get<T: SourceFuncs + 'static>(source: &Source) -> Option<&T> {
+    let source = source.to_glib_none().0 as *const SourceData<T>;
+    unsafe {
+        // The type tag is at the same offset for every T, so it can be read before checking T.
+        if (*source).type_id == TypeId::of::<T>() {
+            Some(&(*source).data)
+        }
+        else {
+            None
+        }
     }
 }
 
-pub fn source_get<T: SourceFuncs>(source: &Source) -> &T {
-    unsafe { &( *(source.to_glib_none().0 as *const SourceData<T>) ).data }
+pub fn set_can_recurse(source: &Source, can_recurse: bool) {
+    unsafe { g_source_set_can_recurse(source.to_glib_none().0, can_recurse.to_glib()) };
+}
+
+pub fn set_name(source: &Source, name: &str) {
+    unsafe { g_source_set_name(source.to_glib_none().0, name.to_glib_none().0) };
+}
+
+pub fn set_priority(source: &Source, priority: Priority) {
+    unsafe { g_source_set_priority(source.to_glib_none().0, priority.to_glib()) };
 }
 
 unsafe extern "C" fn check<T: SourceFuncs>(source: *mut GSource) -> c_int {
+    let bomb = Bomb;
     let object = source as *mut SourceData<T>;
-    bool_to_int((*object).data.check())
+    let result = (*object).data.check();
+    bomb.defuse();
+    bool_to_int(result)
 }
 
 unsafe extern "C" fn dispatch<T: SourceFuncs>(source: *mut GSource, _callback: GSourceFunc, _user_data: *mut libc::c_void)
     -> c_int
 {
+    let bomb = Bomb;
     let object = source as *mut SourceData<T>;
-    bool_to_int((*object).data.dispatch())
+    let result = (*object).data.dispatch();
+    bomb.defuse();
+    bool_to_int(result)
 }
 
 unsafe extern "C" fn finalize<T: SourceFuncs>(source: *mut GSource) {
-    // TODO: needs a bomb to abort on panic
+    // The destructor of the data could panic.
+    let bomb = Bomb;
     let source = source as *mut SourceData<T>;
     ptr::read(&(*source).funcs);
     ptr::read(&(*source).data);
+    bomb.defuse();
 }
 
 extern "C" fn prepare<T: SourceFuncs>(source: *mut GSource, timeout: *mut c_int) -> c_int {
+    let bomb = Bomb;
     let object = source as *mut SourceData<T>;
     let (result, source_timeout) = unsafe { (*object).data.prepare() };
+    bomb.defuse();
     if let Some(source_timeout) = source_timeout {
         unsafe { *timeout = source_timeout as i32; }
     }
@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Show a relm widget as a modal dialog without blocking in `gtk::Dialog::run()`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use glib::{Cast, Continue, IsA};
+use gtk::{GtkWindowExt, WidgetExt};
+use gtk::prelude::WidgetExtManual;
+
+use crate::core::{StreamError, StreamHandle};
+use crate::state::{DisplayVariant, init_component};
+use crate::widget::Widget;
+use crate::try_create_widget;
+
+/// A relm widget shown as a modal dialog by [`show()`](fn.show.html).
+pub trait Dialog: Widget {
+    /// The value produced by the dialog when it is closed.
+    type Response;
+
+    /// Get the response carried by `msg` if this message terminates the dialog, like the
+    /// messages sent by the OK and Cancel buttons.
+    /// The dialog should also send such a message when its window is closed, otherwise it
+    /// stays alive.
+    fn response(msg: &Self::Msg) -> Option<Self::Response>;
+}
+
+/// Show the dialog `DIALOG` on top of the `parent` window.
+///
+/// When the dialog emits a message for which [`Dialog::response()`](trait.Dialog.html#tymethod.response)
+/// returns a response, the dialog is destroyed and the message returned by `map` is emitted to
+/// `stream`.
+/// This happens from the main loop, so the update of the dialog always completes before the
+/// parent receives its message.
+pub fn show<DIALOG, WINDOW, MSG, CALLBACK>(parent: &WINDOW, stream: &StreamHandle<MSG>,
+    model_param: DIALOG::ModelParam, map: CALLBACK) -> Result<(), StreamError>
+    where CALLBACK: FnOnce(DIALOG::Response) -> MSG + 'static,
+          DIALOG: Dialog + 'static,
+          DIALOG::Msg: DisplayVariant + 'static,
+          DIALOG::Response: 'static,
+          DIALOG::Root: IsA<gtk::Window>,
+          MSG: 'static,
+          WINDOW: IsA<gtk::Window>,
+{
+    let (mut component, widget, relm) = try_create_widget::<DIALOG>(model_param)?;
+    let state = init_component::<DIALOG>(component.owned_stream(), widget, &relm);
+    component.set_state(state);
+
+    let window: gtk::Window = component.widget().clone().upcast();
+    window.set_transient_for(Some(parent));
+    window.set_modal(true);
+    window.show();
+
+    let dialog_stream = component.stream();
+    let stream = stream.clone();
+    // The component is kept alive by the observer until the response is received.
+    let component = Rc::new(RefCell::new(Some((component, map))));
+    dialog_stream.observe(move |msg| {
+        if let Some(response) = DIALOG::response(msg) {
+            if let Some((component, map)) = component.borrow_mut().take() {
+                let stream = stream.clone();
+                let mut response = Some((component, map, response));
+                let _ = glib::idle_add_local(move || {
+                    if let Some((component, map, response)) = response.take() {
+                        let window: gtk::Window = component.widget().clone().upcast();
+                        component.destroy();
+                        // The stream of the dialog is closed, so nothing uses the window anymore.
+                        unsafe {
+                            window.destroy();
+                        }
+                        let _ = stream.try_emit(map(response));
+                    }
+                    Continue(false)
+                });
+            }
+        }
+    });
+    Ok(())
+}
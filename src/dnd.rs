@@ -0,0 +1,179 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Drag and drop of typed data, sending the dropped data to a relm widget as a message.
+//!
+//! In the `view!` macro, the `drop` and `drag_source` properties call
+//! [`enable_drop()`](fn.enable_drop.html) and [`enable_drag_source()`](fn.enable_drag_source.html):
+//!
+//! ```ignore
+//! gtk::Label {
+//!     drag_source: || "Some text".to_string(),
+//!     drop: |uris: Uris, _x, _y| Msg::Open(uris.0),
+//! }
+//! ```
+
+use gdk::{DragAction, ModifierType};
+use glib::IsA;
+use gtk::{DestDefaults, TargetEntry, TargetFlags, WidgetExt};
+use gtk::prelude::WidgetExtManual;
+
+use crate::core::StreamHandle;
+
+/// A type that can be created from the data of a drop.
+pub trait FromSelectionData: Sized {
+    /// Get the targets accepted by the drop destination.
+    fn targets() -> Vec<TargetEntry>;
+
+    /// Convert the dropped data, or return `None` if it is not valid.
+    fn from_selection_data(data: &gtk::SelectionData) -> Option<Self>;
+}
+
+/// A type that can be provided as the data of a drag.
+pub trait ToSelectionData {
+    /// Get the targets offered by the drag source.
+    fn targets() -> Vec<TargetEntry>;
+
+    /// Set this value as the dragged data.
+    fn to_selection_data(&self, data: &gtk::SelectionData);
+}
+
+fn text_targets() -> Vec<TargetEntry> {
+    ["UTF8_STRING", "text/plain;charset=utf-8", "text/plain", "STRING"].iter()
+        .enumerate()
+        .map(|(info, target)| TargetEntry::new(target, TargetFlags::empty(), info as u32))
+        .collect()
+}
+
+impl FromSelectionData for String {
+    fn targets() -> Vec<TargetEntry> {
+        text_targets()
+    }
+
+    fn from_selection_data(data: &gtk::SelectionData) -> Option<Self> {
+        data.get_text().map(Into::into)
+    }
+}
+
+impl ToSelectionData for String {
+    fn targets() -> Vec<TargetEntry> {
+        text_targets()
+    }
+
+    fn to_selection_data(&self, data: &gtk::SelectionData) {
+        let _ = data.set_text(self);
+    }
+}
+
+/// A list of URIs, like the files dragged from a file manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Uris(pub Vec<String>);
+
+impl FromSelectionData for Uris {
+    fn targets() -> Vec<TargetEntry> {
+        vec![TargetEntry::new("text/uri-list", TargetFlags::empty(), 0)]
+    }
+
+    fn from_selection_data(data: &gtk::SelectionData) -> Option<Self> {
+        let uris = data.get_uris();
+        if uris.is_empty() {
+            None
+        }
+        else {
+            Some(Uris(uris.into_iter().map(Into::into).collect()))
+        }
+    }
+}
+
+impl ToSelectionData for Uris {
+    fn targets() -> Vec<TargetEntry> {
+        <Self as FromSelectionData>::targets()
+    }
+
+    fn to_selection_data(&self, data: &gtk::SelectionData) {
+        let uris: Vec<&str> = self.0.iter().map(String::as_str).collect();
+        let _ = data.set_uris(&uris);
+    }
+}
+
+/// Bytes dragged between the widgets of the application.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bytes(pub Vec<u8>);
+
+const BYTES_TARGET: &str = "application/x-relm-bytes";
+
+impl FromSelectionData for Bytes {
+    fn targets() -> Vec<TargetEntry> {
+        vec![TargetEntry::new(BYTES_TARGET, TargetFlags::SAME_APP, 0)]
+    }
+
+    fn from_selection_data(data: &gtk::SelectionData) -> Option<Self> {
+        if data.get_length() < 0 {
+            None
+        }
+        else {
+            Some(Bytes(data.get_data()))
+        }
+    }
+}
+
+impl ToSelectionData for Bytes {
+    fn targets() -> Vec<TargetEntry> {
+        <Self as FromSelectionData>::targets()
+    }
+
+    fn to_selection_data(&self, data: &gtk::SelectionData) {
+        data.set(&data.get_target(), 8, &self.0);
+    }
+}
+
+/// Make `widget` a drop destination for `DATA`.
+///
+/// When valid data is dropped, the message returned by `map` for this data and the position of
+/// the drop in `widget` is sent to `stream`.
+pub fn enable_drop<DATA, WIDGET, MSG, CALLBACK>(widget: &WIDGET, stream: &StreamHandle<MSG>, map: CALLBACK)
+    where DATA: FromSelectionData,
+          WIDGET: IsA<gtk::Widget>,
+          CALLBACK: Fn(DATA, f64, f64) -> MSG + 'static,
+          MSG: 'static,
+{
+    // With DestDefaults::ALL, GTK+ gets the data on drop and finishes the drag after the handler.
+    widget.drag_dest_set(DestDefaults::ALL, &DATA::targets(), DragAction::COPY | DragAction::MOVE);
+    let stream = stream.clone();
+    let _ = widget.connect_drag_data_received(move |_, _, x, y, data, _, _| {
+        if let Some(value) = DATA::from_selection_data(data) {
+            stream.emit(map(value, f64::from(x), f64::from(y)));
+        }
+    });
+}
+
+/// Make `widget` a drag source, dragging the value returned by `data` when the drag starts with
+/// the primary button.
+pub fn enable_drag_source<DATA, WIDGET, CALLBACK>(widget: &WIDGET, data: CALLBACK)
+    where DATA: ToSelectionData,
+          WIDGET: IsA<gtk::Widget>,
+          CALLBACK: Fn() -> DATA + 'static,
+{
+    widget.drag_source_set(ModifierType::BUTTON1_MASK, &DATA::targets(), DragAction::COPY);
+    let _ = widget.connect_drag_data_get(move |_, _, selection_data, _, _| {
+        data().to_selection_data(selection_data);
+    });
+}
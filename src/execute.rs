@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Run `Send` futures on a background tokio runtime and send their outputs to streams, for
+//! instance for heavy computations or for I/O using tokio.
+//!
+//! This module requires the `tokio` feature.
+//! The runtime is owned by relm: it is created by the first call to [`spawn()`](fn.spawn.html)
+//! and stopped by [`shutdown()`](fn.shutdown.html).
+
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lazy_static::lazy_static;
+use tokio_crate::runtime::{Builder, Runtime};
+
+use crate::core::{StreamHandle, oneshot};
+
+lazy_static! {
+    static ref RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+}
+
+fn runtime() -> MutexGuard<'static, Option<Runtime>> {
+    // A panic while holding the lock cannot leave the runtime in an inconsistent state.
+    RUNTIME.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Spawn `future` on the background runtime and emit the message returned by `map` with its
+/// output to `stream`.
+/// `map` is called from the main loop of the default main context.
+/// The output is discarded if the stream was dropped in the meantime.
+///
+/// ## Panics
+/// Panics if the runtime cannot be created.
+pub fn spawn<F, MAP, MSG>(future: F, stream: StreamHandle<MSG>, map: MAP)
+where F: Future + Send + 'static,
+      F::Output: Send + 'static,
+      MAP: FnOnce(F::Output) -> MSG + 'static,
+      MSG: 'static,
+{
+    let (sender, receiver) = oneshot();
+    receiver.on_receive(&stream, map);
+    let mut runtime = runtime();
+    let runtime = runtime.get_or_insert_with(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("relm-executor")
+            .build()
+            .expect("Cannot create the tokio runtime")
+    });
+    let task = runtime.spawn(async move {
+        let output = future.await;
+        // The source created by on_receive() stays alive until the value is sent.
+        let _ = sender.send(output);
+    });
+    // Dropping the handle detaches the task.
+    drop(task);
+}
+
+/// Stop the background runtime, for instance before exiting the application: the futures that
+/// are still running are cancelled and their outputs are never sent.
+/// This waits for the threads of the runtime to stop.
+/// A later call to [`spawn()`](fn.spawn.html) starts a new runtime.
+///
+/// ## Panics
+/// Panics if called from a future running on the runtime.
+pub fn shutdown() {
+    // Don't hold the lock while the runtime stops, so that its threads can call spawn().
+    let runtime = runtime().take();
+    drop(runtime);
+}
@@ -95,13 +95,47 @@
  * TODO: optionnaly multi-threaded.
  */
 
+mod action;
+mod adjustment;
+mod application;
+mod async_call;
+mod child_list;
+pub mod clipboard;
 mod component;
 mod container;
 mod core;
+pub mod dialog;
+pub mod dnd;
 mod drawing;
+mod error;
+#[cfg(feature = "tokio")]
+pub mod execute;
+#[cfg(feature = "bind-model")]
+mod list_box_binding;
 mod macros;
+mod monitor;
+mod popover;
+#[cfg(feature = "serde")]
+pub mod recorder;
+mod sent_values;
+pub mod settings;
+mod sources;
 mod state;
+mod state_machine;
+mod style;
+mod subscription;
+pub mod test;
+#[cfg(feature = "trace")]
+mod trace;
 mod widget;
+mod window_group;
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use glib::MainContext;
+use glib::translate::FromGlibPtrFull;
 
 #[doc(hidden)]
 pub use fragile::Fragile;
@@ -111,6 +145,7 @@ pub use glib::{
     Cast,
     IsA,
     Object,
+    ObjectExt,
     StaticType,
     ToValue,
     Value,
@@ -119,24 +154,87 @@ pub use glib::{
 pub use glib::translate::{FromGlibPtrNone, ToGlib, ToGlibPtr};
 #[doc(hidden)]
 pub use gobject_sys::{GParameter, g_object_newv};
-use glib::Continue;
 
-pub use crate::core::{Channel, EventStream, Sender, StreamHandle};
+pub use crate::core::{
+    CancellationToken,
+    Channel,
+    ChannelBuilder,
+    DroppedStreamError,
+    EmitError,
+    EventStream,
+    EventStreamBuilder,
+    MessagePriority,
+    ObservePhase,
+    ObserverHandle,
+    OneshotReceiver,
+    OneshotSender,
+    ProgressSender,
+    Sender,
+    StreamHandle,
+    StreamError,
+    StrongStreamHandle,
+    SyncSender,
+    oneshot,
+    oneshot_with_context,
+    progress_channel,
+};
+#[cfg(feature = "crossbeam")]
+pub use crate::core::CrossbeamSender;
+#[cfg(feature = "async")]
+pub use crate::core::{MessageStream, SyncSink};
 pub use crate::state::{
     DisplayVariant,
     IntoOption,
     IntoPair,
+    MessageVariant,
     Relm,
+    RelmHandle,
     Update,
     UpdateNew,
     execute,
 };
+#[doc(hidden)]
+pub use crate::state::{DebugMessage, DebuggableMessage, UndebuggableMessage};
 use state::init_component;
 
+pub use action::ActionGroupBuilder;
+pub use adjustment::{
+    connect_adjustment_value_changed,
+    get_adjustment,
+    is_scrolled_to_end,
+    scroll_to_end,
+    set_adjustment_value,
+};
+pub use application::{application, quit, run_application};
+pub use async_call::{AsyncCallback, connect_async};
+pub use child_list::ChildList;
 pub use component::Component;
-pub use container::{Container, ContainerComponent, ContainerWidget};
+pub use container::{Container, ContainerComponent, ContainerWidget, pack, set_child_property};
 pub use drawing::DrawHandler;
+pub use error::Error;
+#[cfg(feature = "bind-model")]
+pub use list_box_binding::ListBoxBinding;
+pub use monitor::{MonitorHandle, file_monitor};
+pub use popover::set_popover_visible;
+pub use sent_values::SentValues;
+#[doc(hidden)]
+pub use sent_values::{
+    BorrowedPropertyValue,
+    OptionBorrowedPropertyValue,
+    OwnedPropertyValue,
+    PropertyValue,
+    UncomparablePropertyValue,
+};
+pub use sources::{SourceHandle, child_watch, debounce, interval_cancellable, throttle, timeout_cancellable};
+#[cfg(unix)]
+pub use sources::{io_watch, unix_signal};
+pub use state_machine::StateMachine;
+pub use style::{set_css, set_style_classes};
+pub use subscription::Subscription;
+#[cfg(feature = "trace")]
+pub use trace::{TraceEvent, TraceKind, remove_tracer, set_tracer};
 pub use widget::{Widget, WidgetTest};
+pub use window_group::WindowGroup;
 
 /// Dummy macro to be used with `#[derive(Widget)]`.
 #[macro_export]
@@ -154,15 +252,16 @@ macro_rules! use_impl_self_type {
     };
 }
 
-fn create_widget_test<WIDGET>(model_param: WIDGET::ModelParam) -> (Component<WIDGET>, WIDGET::Streams, WIDGET::Widgets)
+fn create_widget_test<WIDGET>(model_param: WIDGET::ModelParam) -> Result<InitTestComponents<WIDGET>, StreamError>
     where WIDGET: Widget + WidgetTest + 'static,
           WIDGET::Msg: DisplayVariant + 'static,
 {
-    let (component, widget, relm) = create_widget::<WIDGET>(model_param);
+    let (mut component, widget, relm) = try_create_widget::<WIDGET>(model_param)?;
     let widgets = widget.get_widgets();
     let streams = widget.get_streams();
-    init_component::<WIDGET>(component.owned_stream(), widget, &relm);
-    (component, streams, widgets)
+    let state = init_component::<WIDGET>(component.owned_stream(), widget, &relm);
+    component.set_state(state);
+    Ok((component, streams, widgets))
 }
 
 /// Create a new relm widget without adding it to an existing widget.
@@ -172,8 +271,9 @@ pub fn create_component<CHILDWIDGET>(model_param: CHILDWIDGET::ModelParam)
     where CHILDWIDGET: Widget + 'static,
           CHILDWIDGET::Msg: DisplayVariant + 'static,
 {
-    let (component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
-    init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+    let (mut component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
+    let state = init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+    component.set_state(state);
     component
 }
 
@@ -184,10 +284,11 @@ pub fn create_container<CHILDWIDGET>(model_param: CHILDWIDGET::ModelParam)
     where CHILDWIDGET: Container + Widget + 'static,
           CHILDWIDGET::Msg: DisplayVariant + 'static,
 {
-    let (component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
+    let (mut component, widget, child_relm) = create_widget::<CHILDWIDGET>(model_param);
     let container = widget.container().clone();
     let containers = widget.other_containers();
-    init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+    let state = init_component::<CHILDWIDGET>(component.owned_stream(), widget, &child_relm);
+    component.set_state(state);
     ContainerComponent::new(component, container, containers)
 }
 
@@ -197,7 +298,19 @@ fn create_widget<WIDGET>(model_param: WIDGET::ModelParam)
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static,
 {
-    let stream = EventStream::new();
+    try_create_widget(model_param)
+        .unwrap_or_else(|error| panic!("Cannot create the widget: {}", error))
+}
+
+/// Create a new relm widget with `model_param` as initialization value.
+/// Return an error if the event stream of the widget cannot be created.
+fn try_create_widget<WIDGET>(model_param: WIDGET::ModelParam)
+    -> Result<(Component<WIDGET>, WIDGET, Relm<WIDGET>), StreamError>
+    where WIDGET: Widget + 'static,
+          WIDGET::Msg: DisplayVariant + 'static,
+{
+    let stream = EventStream::try_new()?;
+    stream.set_name(std::any::type_name::<WIDGET>());
 
     let relm = Relm::new(&stream);
     let model = WIDGET::model(&relm, model_param);
@@ -205,7 +318,7 @@ fn create_widget<WIDGET>(model_param: WIDGET::ModelParam)
     widget.init_view();
 
     let root = widget.root();
-    (Component::new(stream, root), widget, relm)
+    Ok((Component::new(stream, root), widget, relm))
 }
 
 type InitTestComponents<WIDGET> = (Component<WIDGET>, <WIDGET as WidgetTest>::Streams, <WIDGET as WidgetTest>::Widgets);
@@ -273,7 +386,7 @@ pub fn init_test<WIDGET>(model_param: WIDGET::ModelParam) ->
           WIDGET::Msg: DisplayVariant + 'static,
 {
     gtk::init()?;
-    let component = create_widget_test::<WIDGET>(model_param);
+    let component = create_widget_test::<WIDGET>(model_param)?;
     Ok(component)
 }
 
@@ -282,11 +395,33 @@ pub fn init<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>
     where WIDGET: Widget + 'static,
           WIDGET::Msg: DisplayVariant + 'static
 {
-    let (component, widget, relm) = create_widget::<WIDGET>(model_param);
-    init_component::<WIDGET>(component.owned_stream(), widget, &relm);
+    let (mut component, widget, relm) = try_create_widget::<WIDGET>(model_param)?;
+    let state = init_component::<WIDGET>(component.owned_stream(), widget, &relm);
+    component.set_state(state);
     Ok(component)
 }
 
+/// Call `callback` with `object` every time its property `property` changes.
+///
+/// The handler doesn't keep a reference to `object`, since it gets it from the signal.
+/// This is what `notify(property)` does in the `view!` macro.
+pub fn connect_notify<OBJECT, CALLBACK>(object: &OBJECT, property: &str, callback: CALLBACK) -> glib::SignalHandlerId
+    where OBJECT: IsA<Object>,
+          CALLBACK: Fn(&OBJECT) + 'static,
+{
+    let signal = format!("notify::{}", property.replace('_', "-"));
+    object.connect_local(signal.as_str(), false, move |values| {
+        let object = values[0].get::<Object>().ok()
+            .and_then(|object| object)
+            .and_then(|object| object.downcast::<OBJECT>().ok());
+        if let Some(object) = object {
+            callback(&object);
+        }
+        None
+    })
+    .unwrap_or_else(|error| panic!("Cannot connect to the notify signal of {}: {}", property, error))
+}
+
 /// Create the specified relm `Widget` and run the main event loops.
 ///
 /// ```
@@ -333,34 +468,79 @@ pub fn init<WIDGET>(model_param: WIDGET::ModelParam) -> Result<Component<WIDGET>
 /// #
 /// # fn run() {
 /// /// `Win` is a relm `Widget`.
-/// Win::run(()).expect("Win::run failed");
+/// Win::run().expect("Win::run failed");
 /// # }
 /// ```
-pub fn run<WIDGET>(model_param: WIDGET::ModelParam) -> Result<(), glib::BoolError>
+pub fn run<WIDGET>(model_param: WIDGET::ModelParam) -> Result<(), Error>
     where WIDGET: Widget + 'static,
 {
-    gtk::init()?;
+    run_with_args::<WIDGET>(model_param, &[])
+}
+
+/// Like [`run()`](fn.run.html), but initialize GTK with the command-line `args`, including the
+/// program name, e.g. the ones returned by `std::env::args()`.
+/// GTK handles its own options, like `--display`.
+pub fn run_with_args<WIDGET>(model_param: WIDGET::ModelParam, args: &[String]) -> Result<(), Error>
+    where WIDGET: Widget + 'static,
+{
+    init_gtk_with_args(args)?;
     let _component = init::<WIDGET>(model_param)?;
     gtk::main();
     Ok(())
 }
 
+fn init_gtk_with_args(args: &[String]) -> Result<(), Error> {
+    if args.is_empty() || gtk::is_initialized_main_thread() {
+        return gtk::init().map_err(Error::from);
+    }
+    else if gtk::is_initialized() {
+        return Err(StreamError::NotMainThread.into());
+    }
+    let args = args.iter()
+        .map(|arg| CString::new(arg.as_str()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| glib::glib_bool_error!("A command-line argument contains a nul byte"))?;
+    let mut argv: Vec<*mut c_char> = args.iter()
+        .map(|arg| arg.as_ptr() as *mut c_char)
+        .collect();
+    let mut argc = argv.len() as c_int;
+    argv.push(ptr::null_mut());
+    let mut argv_ptr = argv.as_mut_ptr();
+    let mut error = ptr::null_mut();
+    unsafe {
+        let initialized = gtk_sys::gtk_init_with_args(&mut argc, &mut argv_ptr, ptr::null(), ptr::null(), ptr::null(),
+            &mut error);
+        if !error.is_null() {
+            return Err(glib::Error::from_glib_full(error).into());
+        }
+        if initialized == glib_sys::GFALSE {
+            return Err(glib::glib_bool_error!("Failed to initialize GTK").into());
+        }
+        if !MainContext::default().acquire() {
+            return Err(glib::glib_bool_error!("Failed to acquire default main context").into());
+        }
+        gtk::set_initialized();
+    }
+    Ok(())
+}
+
 /// Emit the `msg` every `duration` ms.
-pub fn interval<F: Fn() -> MSG + 'static, MSG: 'static>(stream: &StreamHandle<MSG>, duration: u32, constructor: F) {
-    let stream = stream.clone();
-    glib::timeout_add_local(duration, move || {
-        let msg = constructor();
-        stream.emit(msg);
-        Continue(true)
-    });
+/// Stop when the stream is dropped.
+/// Use [`interval_cancellable()`](fn.interval_cancellable.html) to be able to stop it earlier.
+pub fn interval<F, MSG, STREAM>(stream: &STREAM, duration: u32, constructor: F)
+where F: Fn() -> MSG + 'static,
+      MSG: 'static,
+      STREAM: AsRef<StreamHandle<MSG>>,
+{
+    interval_cancellable(stream, duration, constructor).forget();
 }
 
 /// After `duration` ms, emit `msg`.
-pub fn timeout<F: Fn() -> MSG + 'static, MSG: 'static>(stream: &StreamHandle<MSG>, duration: u32, constructor: F) {
-    let stream = stream.clone();
-    glib::timeout_add_local(duration, move || {
-        let msg = constructor();
-        stream.emit(msg);
-        Continue(false)
-    });
+/// Use [`timeout_cancellable()`](fn.timeout_cancellable.html) to be able to cancel it.
+pub fn timeout<F, MSG, STREAM>(stream: &STREAM, duration: u32, constructor: F)
+where F: Fn() -> MSG + 'static,
+      MSG: 'static,
+      STREAM: AsRef<StreamHandle<MSG>>,
+{
+    timeout_cancellable(stream, duration, constructor).forget();
 }
@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+use gio::ListStoreExt;
+use glib::{Cast, StaticType};
+use gtk::ListBoxExt;
+
+use crate::component::Component;
+use crate::core::StreamHandle;
+use crate::state::DisplayVariant;
+use crate::widget::Widget;
+use crate::init;
+
+struct Row<ROW: Widget> {
+    component: Component<ROW>,
+    // Object inserted in the store for this row, used to find its position.
+    object: glib::Object,
+}
+
+type Forward<MSG> = Rc<dyn Fn(usize, &MSG)>;
+type Rows<ROW> = Rc<RefCell<Vec<Row<ROW>>>>;
+
+/// Rows of a `gtk::ListBox` bound to a `gio::ListStore` that mirrors a collection of items.
+///
+/// Every item is shown by a `ROW` component created with the item as its model parameter.
+/// The messages of the rows are sent to the parent widget after being mapped with the current
+/// position of the row, which is kept up to date when rows are inserted or removed before it.
+pub struct ListBoxBinding<ITEM, ROW: Widget> {
+    forward: Forward<ROW::Msg>,
+    items: Vec<ITEM>,
+    rows: Rows<ROW>,
+    store: gio::ListStore,
+}
+
+impl<ITEM, ROW> ListBoxBinding<ITEM, ROW>
+    where ITEM: Clone,
+          ROW: Widget<ModelParam=ITEM> + 'static,
+          ROW::Msg: DisplayVariant + 'static,
+{
+    /// Create an empty binding.
+    /// The messages of the rows for which `map` returns a message are sent to `stream`.
+    pub fn new<MSG, CALLBACK>(stream: &StreamHandle<MSG>, map: CALLBACK) -> Self
+        where CALLBACK: Fn(usize, &ROW::Msg) -> Option<MSG> + 'static,
+              MSG: 'static,
+    {
+        let stream = stream.clone();
+        ListBoxBinding {
+            forward: Rc::new(move |index, msg| {
+                if let Some(msg) = map(index, msg) {
+                    stream.emit(msg);
+                }
+            }),
+            items: vec![],
+            rows: Rc::new(RefCell::new(vec![])),
+            store: gio::ListStore::new(glib::Object::static_type()),
+        }
+    }
+
+    /// Show the rows in `list_box`, which is typically called in `init_view()`.
+    pub fn bind(&self, list_box: &gtk::ListBox) {
+        // The list box owns the factory, so only keep a weak reference to the rows.
+        let rows = Rc::downgrade(&self.rows);
+        list_box.bind_model(Some(&self.store), move |object| {
+            let rows = rows.upgrade().expect("rows of the list box binding");
+            let rows = rows.borrow();
+            let row = rows.iter()
+                .find(|row| row.object == *object)
+                .expect("row of the list box binding");
+            row.component.widget().clone().upcast()
+        });
+    }
+
+    /// Get the items shown by the rows.
+    pub fn items(&self) -> &[ITEM] {
+        &self.items
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check whether the binding has no row.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get the component of the row at `index`.
+    pub fn row(&self, index: usize) -> Option<Ref<'_, Component<ROW>>> {
+        let rows = self.rows.borrow();
+        if index < rows.len() {
+            Some(Ref::map(rows, |rows| &rows[index].component))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Insert a row for `item` at `index`.
+    ///
+    /// ## Panics
+    /// Panics if `index > len` or if the row component cannot be created.
+    pub fn insert(&mut self, index: usize, item: ITEM) {
+        assert!(index <= self.items.len(), "insertion index (is {}) should be <= len (is {})", index,
+            self.items.len());
+        let component = init::<ROW>(item.clone()).expect("row of the list box binding");
+        let object = glib::Object::new(glib::Object::static_type(), &[])
+            .expect("object of the list box binding");
+        let rows = Rc::downgrade(&self.rows);
+        let forward = self.forward.clone();
+        let row_object = object.clone();
+        component.stream().observe(move |msg| {
+            if let Some(index) = position(&rows, &row_object) {
+                forward(index, msg);
+            }
+        });
+        self.items.insert(index, item);
+        self.rows.borrow_mut().insert(index, Row {
+            component,
+            object: object.clone(),
+        });
+        // The rows must not be borrowed here, since the list box calls the factory.
+        self.store.insert(index as u32, &object);
+    }
+
+    /// Add a row for `item` after the others.
+    pub fn push(&mut self, item: ITEM) {
+        self.insert(self.items.len(), item);
+    }
+
+    /// Remove the row at `index` and return its item.
+    /// The component of the row is dropped, so that it doesn't process messages anymore.
+    ///
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> ITEM {
+        let item = self.items.remove(index);
+        // The row is removed from the list box before its component is dropped.
+        self.store.remove(index as u32);
+        let row = self.rows.borrow_mut().remove(index);
+        drop(row);
+        item
+    }
+
+    /// Replace the item at `index` by `item`: the row is recreated with the new item.
+    ///
+    /// ## Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update(&mut self, index: usize, item: ITEM) {
+        let _ = self.remove(index);
+        self.insert(index, item);
+    }
+}
+
+fn position<ROW: Widget>(rows: &Weak<RefCell<Vec<Row<ROW>>>>, object: &glib::Object) -> Option<usize> {
+    let rows = rows.upgrade()?;
+    let rows = rows.try_borrow().ok()?;
+    rows.iter().position(|row| row.object == *object)
+}
@@ -33,6 +33,9 @@
 /// 3. Send `$msg` when the GTK+ `$event` is emitted on `$widget`.
 ///
 /// 4. Send `$msg` to `$widget` when the `$message` is received on `$stream`.
+///
+/// The GTK+ signal handlers are disconnected when the component receiving the messages is destroyed
+/// with [`Component::destroy()`](struct.Component.html#method.destroy).
 #[macro_export]
 macro_rules! connect {
     // Connect to a GTK+ widget event, sending a message to another widget.
@@ -51,12 +54,15 @@ macro_rules! connect {
     // Connect to a GTK+ widget event.
     ($relm:expr, $widget:expr, $event:ident($($args:pat),*), $msg:expr) => {{
         let stream = $relm.stream().clone();
-        let _ = $widget.$event(move |$($args),*| {
+        let signal_handlers = stream.clone();
+        let widget = &$widget;
+        let handler_id = widget.$event(move |$($args),*| {
             let msg: Option<_> = $crate::IntoOption::into_option($msg);
             if let Some(msg) = msg {
                 stream.emit(msg);
             }
         });
+        signal_handlers.add_signal_handler(widget, handler_id);
     }};
 
     // Connect to a message reception.
@@ -67,6 +73,72 @@ macro_rules! connect {
     };
 }
 
+/// Connect events to sending a message, like `connect!`, with weak references to the widgets used
+/// in the message.
+///
+/// The handler is owned by `$widget`, so a widget of the component used in `$msg`, like the parent
+/// of `$widget`, would never be freed if the handler kept a strong reference to it.
+/// The widgets listed between brackets are downgraded to weak references and upgraded again,
+/// under the same name, when the event is emitted: nothing is done if one of them was freed.
+/// The stream of the component is already a weak reference.
+///
+/// ## Rules
+/// 1. Optionally send `$msg.0` when the GTK+ `$event` is emitted on `$widget`.
+///    Return `$msg.1` in the GTK+ callback, or the default value of its type if a widget was freed.
+///
+/// 2. Send `$msg` when the GTK+ `$event` is emitted on `$widget`.
+///
+/// ## Example
+/// ```ignore
+/// connect_weak!(relm, button, connect_clicked(_), [entry], Msg::Send(entry.get_text().to_string()));
+/// ```
+#[macro_export]
+macro_rules! connect_weak {
+    ($relm:expr, $widget:expr, $event:ident($($args:pat),*), [$($weak:ident),*], return $msg:expr) => {{
+        let stream = $relm.stream().clone();
+        let signal_handlers = stream.clone();
+        $(let $weak = $crate::ObjectExt::downgrade(&$weak);)*
+        let widget = &$widget;
+        let handler_id = widget.$event(move |$($args),*| {
+            $(
+                let $weak =
+                    match $weak.upgrade() {
+                        Some(widget) => widget,
+                        None => return Default::default(),
+                    };
+            )*
+            let (msg, return_value) = $crate::IntoPair::into_pair($msg);
+            let msg: Option<_> = $crate::IntoOption::into_option(msg);
+            if let Some(msg) = msg {
+                stream.emit(msg);
+            }
+            return_value
+        });
+        signal_handlers.add_signal_handler(widget, handler_id);
+    }};
+
+    ($relm:expr, $widget:expr, $event:ident($($args:pat),*), [$($weak:ident),*], $msg:expr) => {{
+        let stream = $relm.stream().clone();
+        let signal_handlers = stream.clone();
+        $(let $weak = $crate::ObjectExt::downgrade(&$weak);)*
+        let widget = &$widget;
+        let handler_id = widget.$event(move |$($args),*| {
+            $(
+                let $weak =
+                    match $weak.upgrade() {
+                        Some(widget) => widget,
+                        None => return,
+                    };
+            )*
+            let msg: Option<_> = $crate::IntoOption::into_option($msg);
+            if let Some(msg) = msg {
+                stream.emit(msg);
+            }
+        });
+        signal_handlers.add_signal_handler(widget, handler_id);
+    }};
+}
+
 /// Connect events to sending a message.
 /// Similar to `connect!` but wants a stream instead of a component.
 ///
@@ -82,7 +154,9 @@ macro_rules! connect_stream {
     // Option<MSG> can be None if no message needs to be emitted.
     (return $stream:expr, $widget:expr, $event:ident($($args:pat),*), $msg:expr) => {{
         let stream = $stream.stream().clone();
-        let _ = $widget.$event(move |$($args),*| {
+        let signal_handlers = stream.clone();
+        let widget = &$widget;
+        let handler_id = widget.$event(move |$($args),*| {
             let (msg, return_value) = $crate::IntoPair::into_pair($msg);
             let msg: Option<_> = $crate::IntoOption::into_option(msg);
             if let Some(msg) = msg {
@@ -90,17 +164,21 @@ macro_rules! connect_stream {
             }
             return_value
         });
+        signal_handlers.add_signal_handler(widget, handler_id);
     }};
 
     // Connect to a GTK+ widget event, sending a message to another widget.
     ($widget:expr, $event:ident($($args:pat),*), $other_stream:expr, $msg:expr) => {
         let stream = $other_stream.stream().clone();
-        let _ = $widget.$event(move |$($args),*| {
+        let signal_handlers = stream.clone();
+        let widget = &$widget;
+        let handler_id = widget.$event(move |$($args),*| {
             let msg: Option<_> = $crate::IntoOption::into_option($msg);
             if let Some(msg) = msg {
                 stream.emit(msg);
             }
         });
+        signal_handlers.add_signal_handler(widget, handler_id);
     };
 
     // Connect to a message reception.
@@ -125,6 +203,7 @@ macro_rules! connect_stream {
 /// Connect an asynchronous method call to send a message.
 /// The variants with `$fail_msg` will send this message when there's an error.
 /// Those without this argument will ignore the error.
+/// See [`connect_async()`](fn.connect_async.html) for the function equivalent.
 #[macro_export]
 macro_rules! connect_async {
     ($object:expr, $async_method:ident, $relm:expr, $msg:expr) => {
@@ -143,14 +222,9 @@ macro_rules! connect_async {
         connect_async!($object, $async_method(), $relm, $msg, $fail_msg)
     };
     ($object:expr, $async_method:ident ( $($args:expr),* ), $relm:expr, $msg:expr, $fail_msg:expr) => {{
-        let event_stream = $crate::Fragile::new($relm.stream().clone());
-        let fail_event_stream = $crate::Fragile::new($relm.stream().clone());
-        $object.$async_method($($args,)* None::<&gio::Cancellable>, move |result| {
-            match result {
-                Ok(value) => event_stream.into_inner().emit($msg(value)),
-                Err(error) => fail_event_stream.into_inner().emit($fail_msg(error)),
-            }
-        });
+        $crate::connect_async(&$relm.stream().clone(), |callback| {
+            $object.$async_method($($args,)* None::<&gio::Cancellable>, callback)
+        }, $msg, $fail_msg);
     }};
 }
 
@@ -174,15 +248,9 @@ macro_rules! connect_async_func {
         connect_async_func!($class::$async_func(), $relm, $msg, $fail_msg)
     };
     ($class:ident :: $async_func:ident ( $($args:expr),* ), $relm:expr, $msg:expr, $fail_msg:expr) => {{
-        let event_stream = $crate::Fragile::new($relm.stream().clone());
-        let fail_event_stream = $crate::Fragile::new($relm.stream().clone());
-        $class::$async_func($($args,)* None::<&gio::Cancellable>, move |result| {
-            match result {
-                Ok(value) => event_stream.into_inner().emit($msg(value)),
-                Err(error) => fail_event_stream.into_inner().emit($fail_msg(error)),
-            }
-        });
-
+        $crate::connect_async(&$relm.stream().clone(), |callback| {
+            $class::$async_func($($args,)* None::<&gio::Cancellable>, callback)
+        }, $msg, $fail_msg);
     }};
 }
 
@@ -0,0 +1,84 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gio::{Cancellable, File, FileExt, FileMonitor, FileMonitorEvent, FileMonitorExt, FileMonitorFlags};
+
+use crate::core::StreamHandle;
+
+/// Handle to a file monitor emitting messages to a stream.
+/// The monitor is cancelled when the handle is dropped or cancelled.
+#[must_use]
+pub struct MonitorHandle {
+    monitor: FileMonitor,
+}
+
+impl MonitorHandle {
+    /// Cancel the monitor: no message will be emitted anymore.
+    pub fn cancel(self) {
+    }
+
+    /// Check if the monitor was cancelled, either explicitly or because its stream was dropped.
+    pub fn is_cancelled(&self) -> bool {
+        self.monitor.is_cancelled()
+    }
+}
+
+impl Drop for MonitorHandle {
+    fn drop(&mut self) {
+        self.monitor.cancel();
+    }
+}
+
+/// Emit the message returned by `constructor` every time `file`, or a file of the directory
+/// `file`, changes, until the returned handle is dropped or the stream is dropped.
+/// The `constructor` receives the event and, for a move or a rename, the other file.
+///
+/// Since editors often write a file many times when saving it, the `Changed` events are emitted at
+/// most once every `rate_limit` ms, while the other events are always emitted.
+pub fn file_monitor<F, MSG>(file: &File, rate_limit: u32, stream: &StreamHandle<MSG>, constructor: F)
+    -> Result<MonitorHandle, glib::Error>
+where F: Fn(FileMonitorEvent, Option<File>) -> MSG + 'static,
+      MSG: 'static,
+{
+    let monitor = create_monitor(file, rate_limit)?;
+    Ok(connect_monitor(monitor, stream, constructor))
+}
+
+pub(crate) fn create_monitor(file: &File, rate_limit: u32) -> Result<FileMonitor, glib::Error> {
+    let monitor = file.monitor(FileMonitorFlags::NONE, None::<&Cancellable>)?;
+    monitor.set_rate_limit(rate_limit as i32);
+    Ok(monitor)
+}
+
+pub(crate) fn connect_monitor<F, MSG>(monitor: FileMonitor, stream: &StreamHandle<MSG>, constructor: F) -> MonitorHandle
+where F: Fn(FileMonitorEvent, Option<File>) -> MSG + 'static,
+      MSG: 'static,
+{
+    let stream = stream.clone();
+    monitor.connect_changed(move |monitor, _file, other_file, event| {
+        if stream.try_emit(constructor(event, other_file.cloned())).is_err() {
+            monitor.cancel();
+        }
+    });
+    MonitorHandle {
+        monitor,
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use glib::IsA;
+#[cfg(feature = "popover-transitions")]
+use gtk::PopoverExt;
+#[cfg(not(feature = "popover-transitions"))]
+use gtk::WidgetExt;
+
+/// Show or hide `popover`, which is what the `visible` property of a `gtk::Popover` declared in
+/// the `view!` macro does.
+///
+/// With the `popover-transitions` feature (which requires GTK 3.22), the popover is shown with
+/// `popup()` and hidden with `popdown()`, so that it is animated. Otherwise, its visibility is set
+/// without transition.
+///
+/// GTK hides the popover itself when it is closed by the user, e.g. with `Escape`: connect the
+/// `closed` signal to update the model accordingly.
+pub fn set_popover_visible<POPOVER: IsA<gtk::Popover>>(popover: &POPOVER, visible: bool) {
+    #[cfg(feature = "popover-transitions")]
+    {
+        if visible {
+            popover.popup();
+        }
+        else {
+            popover.popdown();
+        }
+    }
+    #[cfg(not(feature = "popover-transitions"))]
+    popover.as_ref().set_visible(visible);
+}
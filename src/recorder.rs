@@ -0,0 +1,212 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Record the messages of a stream and replay them, for instance to reproduce a bug in a test.
+//!
+//! This module requires the `serde` feature.
+//! The messages are saved as JSON, one message per line along with the number of milliseconds
+//! elapsed since the recording started.
+//! The variants that cannot be serialized, like those containing a widget, can be skipped with the
+//! `#[msg(skip_record)]` attribute, along with `#[serde(skip)]`:
+//!
+//! ```ignore
+//! #[derive(Msg, Deserialize, Serialize)]
+//! enum Msg {
+//!     Increment(i32),
+//!     #[msg(skip_record)]
+//!     #[serde(skip)]
+//!     Selected(gtk::TreePath),
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::mem;
+use std::rc::Rc;
+use std::time::Instant;
+
+use glib::{Continue, SourceId};
+use serde_crate::Serialize;
+use serde_crate::de::DeserializeOwned;
+
+use crate::core::{ObserverHandle, StreamHandle};
+use crate::state::MessageVariant;
+
+/// Handle to the recording of the messages of a stream.
+/// The recording stops when the handle is dropped or finished.
+#[must_use]
+pub struct Recorder<MSG, WRITER> {
+    _observer: ObserverHandle<MSG>,
+    state: Rc<RefCell<RecorderState<WRITER>>>,
+}
+
+struct RecorderState<WRITER> {
+    error: Option<io::Error>,
+    start: Instant,
+    writer: Option<WRITER>,
+}
+
+impl<MSG, WRITER: Write> Recorder<MSG, WRITER> {
+    /// Stop the recording and flush the writer.
+    /// Return the writer or the first error that occurred while writing the messages.
+    pub fn finish(self) -> io::Result<WRITER> {
+        let mut state = self.state.borrow_mut();
+        if let Some(error) = state.error.take() {
+            return Err(error);
+        }
+        let mut writer = state.writer.take()
+            .expect("writer");
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+/// Save the messages emitted on `stream` to `writer` until the returned recorder is dropped or
+/// finished.
+/// The messages for which [`MessageVariant::is_recorded()`](../trait.MessageVariant.html#method.is_recorded)
+/// returns `false` are skipped.
+pub fn attach<MSG, WRITER>(stream: &StreamHandle<MSG>, writer: WRITER) -> Recorder<MSG, WRITER>
+where MSG: MessageVariant + Serialize + 'static,
+      WRITER: Write + 'static,
+{
+    let state = Rc::new(RefCell::new(RecorderState {
+        error: None,
+        start: Instant::now(),
+        writer: Some(writer),
+    }));
+    let observer = {
+        let state = state.clone();
+        stream.observe(move |msg: &MSG| {
+            if !msg.is_recorded() {
+                return;
+            }
+            let mut state = state.borrow_mut();
+            let state = &mut *state;
+            if state.error.is_some() {
+                return;
+            }
+            if let Some(ref mut writer) = state.writer {
+                let elapsed = state.start.elapsed().as_millis() as u64;
+                let result = serde_json::to_writer(&mut *writer, &(elapsed, msg))
+                    .map_err(io::Error::from)
+                    .and_then(|()| writer.write_all(b"\n"));
+                state.error = result.err();
+            }
+        })
+    };
+    Recorder {
+        _observer: observer.remove_on_drop(),
+        state,
+    }
+}
+
+/// The timing of the messages sent by [`replay()`](fn.replay.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplaySpeed {
+    /// Emit all the messages right away.
+    Instant,
+    /// Emit the messages with the recorded delays between them, divided by the factor: `1.0`
+    /// replays at the recorded speed, `2.0` twice as fast.
+    Recorded(f64),
+}
+
+/// Handle to the replay of messages.
+/// The remaining messages are not emitted after the handle is dropped or cancelled.
+#[must_use]
+pub struct Replay {
+    // The timeout emitting the next message.
+    source: Rc<RefCell<Option<SourceId>>>,
+}
+
+impl Replay {
+    /// Stop the replay: no message will be emitted anymore.
+    pub fn cancel(self) {
+    }
+
+    /// Check if all the messages were emitted, or if the replay stopped because the stream was
+    /// dropped.
+    pub fn is_finished(&self) -> bool {
+        self.source.borrow().is_none()
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.borrow_mut().take() {
+            glib::source_remove(source);
+        }
+    }
+}
+
+/// Read the messages recorded by [`attach()`](fn.attach.html) from `reader` and emit them on
+/// `stream`, at the given `speed`.
+/// An error is returned, before any message is emitted, if the recording cannot be read.
+pub fn replay<MSG, READER>(stream: &StreamHandle<MSG>, reader: READER, speed: ReplaySpeed) -> io::Result<Replay>
+where MSG: DeserializeOwned + 'static,
+      READER: BufRead,
+{
+    let mut records = VecDeque::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            let record: (u64, MSG) = serde_json::from_str(&line)?;
+            records.push_back(record);
+        }
+    }
+
+    let replay = Replay {
+        source: Rc::new(RefCell::new(None)),
+    };
+    match speed {
+        ReplaySpeed::Instant => {
+            for (_, msg) in records {
+                if stream.try_emit(msg).is_err() {
+                    break;
+                }
+            }
+        },
+        ReplaySpeed::Recorded(factor) => schedule_next(stream.clone(), records, 0, factor, replay.source.clone()),
+    }
+    Ok(replay)
+}
+
+// Add a timeout emitting the next record, after its delay since the previous one.
+fn schedule_next<MSG: 'static>(stream: StreamHandle<MSG>, mut records: VecDeque<(u64, MSG)>, previous: u64,
+    factor: f64, source: Rc<RefCell<Option<SourceId>>>)
+{
+    let delay =
+        match records.front() {
+            Some(&(elapsed, _)) => (elapsed.saturating_sub(previous) as f64 / factor) as u32,
+            None => return,
+        };
+    let current_source = source.clone();
+    let source_id = glib::timeout_add_local(delay, move || {
+        *current_source.borrow_mut() = None;
+        if let Some((elapsed, msg)) = records.pop_front() {
+            if stream.try_emit(msg).is_ok() {
+                schedule_next(stream.clone(), mem::take(&mut records), elapsed, factor, current_source.clone());
+            }
+        }
+        Continue(false)
+    });
+    *source.borrow_mut() = Some(source_id);
+}
@@ -0,0 +1,153 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::any::Any;
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Last values sent in the messages bound to the model in the `view!` macro, and last values set
+/// to the properties bound to the model.
+///
+/// This is used to only send a message to a child component, or to call the setter of a property,
+/// when its value actually changed.
+#[derive(Default)]
+pub struct SentValues {
+    values: RefCell<HashMap<&'static str, Box<dyn Any>>>,
+}
+
+impl SentValues {
+    /// Create an empty set of values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget all the values sent, so that the next values are considered changed.
+    pub fn clear(&self) {
+        self.values.borrow_mut().clear();
+    }
+
+    /// Record `value` as the last value sent for `key` and return whether it is different from
+    /// the previous one.
+    pub fn changed<VALUE: Clone + PartialEq + 'static>(&self, key: &'static str, value: &VALUE) -> bool {
+        self.changed_with(key, |previous: Option<&VALUE>| previous == Some(value), || value.clone())
+    }
+
+    /// Same as [`changed()`](#method.changed), for a borrowed value like a `&str`: its owned
+    /// version is recorded.
+    pub fn changed_borrowed<VALUE>(&self, key: &'static str, value: &VALUE) -> bool
+        where VALUE: ToOwned + PartialEq + ?Sized,
+              VALUE::Owned: 'static,
+    {
+        let is_equal = |previous: Option<&VALUE::Owned>| previous.map(Borrow::borrow) == Some(value);
+        self.changed_with(key, is_equal, || value.to_owned())
+    }
+
+    /// Same as [`changed()`](#method.changed), for an optional borrowed value like an
+    /// `Option<&str>`.
+    pub fn changed_option_borrowed<VALUE>(&self, key: &'static str, value: Option<&VALUE>) -> bool
+        where VALUE: ToOwned + PartialEq + ?Sized,
+              VALUE::Owned: 'static,
+    {
+        let is_equal = |previous: Option<&Option<VALUE::Owned>>| {
+            previous.map(|previous| previous.as_ref().map(Borrow::borrow)) == Some(value)
+        };
+        self.changed_with(key, is_equal, || value.map(ToOwned::to_owned))
+    }
+
+    fn changed_with<OWNED, EQUAL, CREATE>(&self, key: &'static str, is_equal: EQUAL, create: CREATE) -> bool
+        where OWNED: 'static,
+              EQUAL: FnOnce(Option<&OWNED>) -> bool,
+              CREATE: FnOnce() -> OWNED,
+    {
+        let mut values = self.values.borrow_mut();
+        let previous = values.get(key)
+            .and_then(|previous| previous.downcast_ref::<OWNED>());
+        if is_equal(previous) {
+            return false;
+        }
+        let _ = values.insert(key, Box::new(create()));
+        true
+    }
+}
+
+/*
+ * The setter of a property bound to the model is only called when the value changed, if its type
+ * can be compared with the last value set.
+ * Since this is only known when the code generated by the view! macro is type-checked, the
+ * comparison is chosen by method resolution: the first of these traits implemented for the type
+ * of the value is used, by calling the method on &&&PropertyValue(&value).
+ * The references are checked first since method resolution ignores the lifetimes: a borrowed
+ * value would otherwise be required to be 'static to be recorded as is.
+ */
+
+#[doc(hidden)]
+pub struct PropertyValue<'a, VALUE>(pub &'a VALUE);
+
+#[doc(hidden)]
+pub trait BorrowedPropertyValue {
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool;
+}
+
+impl<'a, 'b, VALUE> BorrowedPropertyValue for &&PropertyValue<'a, &'b VALUE>
+    where VALUE: ToOwned + PartialEq + ?Sized,
+          VALUE::Owned: 'static,
+{
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool {
+        values.changed_borrowed(key, *self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait OptionBorrowedPropertyValue {
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool;
+}
+
+impl<'a, 'b, VALUE> OptionBorrowedPropertyValue for &&PropertyValue<'a, Option<&'b VALUE>>
+    where VALUE: ToOwned + PartialEq + ?Sized,
+          VALUE::Owned: 'static,
+{
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool {
+        values.changed_option_borrowed(key, *self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait OwnedPropertyValue {
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool;
+}
+
+impl<'a, VALUE: Clone + PartialEq + 'static> OwnedPropertyValue for &PropertyValue<'a, VALUE> {
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool {
+        values.changed(key, self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait UncomparablePropertyValue {
+    fn changed(&self, values: &SentValues, key: &'static str) -> bool;
+}
+
+impl<'a, VALUE> UncomparablePropertyValue for PropertyValue<'a, VALUE> {
+    fn changed(&self, _values: &SentValues, _key: &'static str) -> bool {
+        true
+    }
+}
@@ -0,0 +1,79 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Synchronize the model with `gio::Settings` keys.
+//!
+//! [`bind()`](fn.bind.html) sends a message when a key changes, for instance to update the model,
+//! and [`set_from_model()`](fn.set_from_model.html) writes a value from the model back to the
+//! settings, without this change being sent back as a message.
+
+use gio::{Settings, SettingsExt};
+use glib::{ObjectExt, SignalHandlerId, ToVariant, Variant};
+
+use crate::core::StreamHandle;
+
+fn writing_key(key: &str) -> String {
+    format!("relm-settings-writing::{}", key)
+}
+
+fn is_writing(settings: &Settings, key: &str) -> bool {
+    // The data under this key is always set by set_from_model(), with this type.
+    unsafe { settings.get_data::<bool>(&writing_key(key)) }
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Send the message returned by `map` for the new value of `key` to `stream` whenever this key of
+/// `settings` changes.
+///
+/// The changes done by [`set_from_model()`](fn.set_from_model.html) are not sent.
+/// Disconnect the returned handler to stop receiving the changes.
+pub fn bind<MSG, CALLBACK>(settings: &Settings, key: &str, stream: &StreamHandle<MSG>, map: CALLBACK)
+    -> SignalHandlerId
+    where CALLBACK: Fn(Variant) -> MSG + 'static,
+          MSG: 'static,
+{
+    let key = key.to_string();
+    let stream = stream.clone();
+    settings.connect_changed(move |settings, changed_key| {
+        if changed_key == key && !is_writing(settings, &key) {
+            let _ = stream.try_emit(map(settings.get_value(&key)));
+        }
+    })
+}
+
+/// Write `value` to `key` of `settings`, typically from `update()` after the model changed.
+///
+/// The handlers connected with [`bind()`](fn.bind.html) are not called for this change, so that
+/// it doesn't come back as a message.
+pub fn set_from_model<VALUE>(settings: &Settings, key: &str, value: &VALUE) -> Result<(), glib::BoolError>
+    where VALUE: ToVariant + ?Sized,
+{
+    let writing_key = writing_key(key);
+    unsafe {
+        settings.set_data(&writing_key, true);
+    }
+    let result = settings.set_value(key, &value.to_variant());
+    unsafe {
+        settings.set_data(&writing_key, false);
+    }
+    result
+}
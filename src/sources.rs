@@ -0,0 +1,254 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::rc::{Rc, Weak};
+
+#[cfg(unix)]
+use glib::IOCondition;
+use glib::{Continue, MainContext, Pid, Source, SourceId};
+
+use crate::core::{EventStream, StreamHandle};
+use crate::core::source::set_priority;
+
+/// Handle to a glib source emitting messages to a stream.
+/// The source is removed from the main loop when the handle is dropped or cancelled.
+#[must_use]
+pub struct SourceHandle {
+    source: Option<Source>,
+}
+
+impl SourceHandle {
+    fn new(source_id: SourceId) -> Self {
+        Self {
+            source: MainContext::default().find_source_by_id(&source_id),
+        }
+    }
+
+    /// Remove the source from the main loop: no message will be emitted anymore.
+    pub fn cancel(self) {
+    }
+
+    /// Check if the source was removed from the main loop, either because it was cancelled, its
+    /// stream was dropped or it won't emit any more message.
+    pub fn is_cancelled(&self) -> bool {
+        self.source.as_ref().map(|source| source.is_destroyed()).unwrap_or(true)
+    }
+
+    /// Keep the source in the main loop after the handle is dropped.
+    pub fn forget(mut self) {
+        self.source = None;
+    }
+}
+
+impl Drop for SourceHandle {
+    fn drop(&mut self) {
+        if let Some(ref source) = self.source {
+            source.destroy();
+        }
+    }
+}
+
+impl<MSG: 'static> StreamHandle<MSG> {
+    /// Emit `msg` once the main loop is idle, i.e. after GTK finished the pending allocations and
+    /// redraws, for instance to scroll to a row that was just added.
+    ///
+    /// Dropping the returned handle before the message is emitted cancels it: call
+    /// [`forget()`](struct.SourceHandle.html#method.forget) on it to keep the message.
+    /// Nothing is emitted if the `EventStream` is dropped before.
+    pub fn emit_idle(&self, msg: MSG) -> SourceHandle {
+        let stream = self.clone();
+        let mut msg = Some(msg);
+        let handle = SourceHandle::new(glib::idle_add_local(move || {
+            if let Some(msg) = msg.take() {
+                let _ = stream.try_emit(msg);
+            }
+            Continue(false)
+        }));
+        // The source cannot be dispatched before its priority is set since the default context is
+        // owned by this thread.
+        if let Some(ref source) = handle.source {
+            set_priority(source, glib::PRIORITY_LOW);
+        }
+        handle
+    }
+
+    /// Emit `msg` after `duration` ms.
+    ///
+    /// Dropping the returned handle before the message is emitted cancels it: call
+    /// [`forget()`](struct.SourceHandle.html#method.forget) on it to keep the message.
+    /// Nothing is emitted if the `EventStream` is dropped before.
+    pub fn emit_timeout(&self, msg: MSG, duration: u32) -> SourceHandle {
+        let stream = self.clone();
+        let mut msg = Some(msg);
+        SourceHandle::new(glib::timeout_add_local(duration, move || {
+            if let Some(msg) = msg.take() {
+                let _ = stream.try_emit(msg);
+            }
+            Continue(false)
+        }))
+    }
+}
+
+/// Emit the message returned by `constructor` every `duration` ms, until the returned handle is
+/// dropped or the stream is dropped.
+pub fn interval_cancellable<F, MSG, STREAM>(stream: &STREAM, duration: u32, constructor: F) -> SourceHandle
+where F: Fn() -> MSG + 'static,
+      MSG: 'static,
+      STREAM: AsRef<StreamHandle<MSG>>,
+{
+    let stream = stream.as_ref().clone();
+    SourceHandle::new(glib::timeout_add_local(duration, move || {
+        Continue(stream.try_emit(constructor()).is_ok())
+    }))
+}
+
+/// After `duration` ms, emit the message returned by `constructor`, unless the returned handle
+/// was dropped before.
+pub fn timeout_cancellable<F, MSG, STREAM>(stream: &STREAM, duration: u32, constructor: F) -> SourceHandle
+where F: Fn() -> MSG + 'static,
+      MSG: 'static,
+      STREAM: AsRef<StreamHandle<MSG>>,
+{
+    let stream = stream.as_ref().clone();
+    SourceHandle::new(glib::timeout_add_local(duration, move || {
+        let _ = stream.try_emit(constructor());
+        Continue(false)
+    }))
+}
+
+/// Emit the message returned by `constructor` every time the file descriptor `fd` satisfies
+/// `condition`, e.g. when it becomes readable, until the returned handle is dropped or the stream
+/// is dropped.
+/// The `constructor` receives the conditions that are satisfied.
+#[cfg(unix)]
+pub fn io_watch<F, MSG>(fd: RawFd, condition: IOCondition, stream: &StreamHandle<MSG>, constructor: F) -> SourceHandle
+where F: Fn(IOCondition) -> MSG + 'static,
+      MSG: 'static,
+{
+    let stream = stream.clone();
+    SourceHandle::new(glib::unix_fd_add_local(fd, condition, move |_fd, condition| {
+        Continue(stream.try_emit(constructor(condition)).is_ok())
+    }))
+}
+
+/// Emit `msg` every time the process receives the unix signal `signum`, until the returned handle
+/// is dropped or the stream is dropped.
+/// Only `SIGHUP`, `SIGINT`, `SIGTERM`, `SIGUSR1`, `SIGUSR2` and `SIGWINCH` are supported.
+#[cfg(unix)]
+pub fn unix_signal<MSG: Clone + 'static>(signum: i32, stream: &StreamHandle<MSG>, msg: MSG) -> SourceHandle {
+    let stream = stream.clone();
+    SourceHandle::new(glib::unix_signal_add_local(signum, move || {
+        Continue(stream.try_emit(msg.clone()).is_ok())
+    }))
+}
+
+/// Emit the message returned by `constructor` when the child process `pid` exits, unless the
+/// returned handle was dropped before.
+/// The `constructor` receives the exit status, as returned by `waitpid()` on unix.
+pub fn child_watch<F, MSG>(pid: Pid, stream: &StreamHandle<MSG>, constructor: F) -> SourceHandle
+where F: FnOnce(i32) -> MSG + 'static,
+      MSG: 'static,
+{
+    let stream = stream.clone();
+    let mut constructor = Some(constructor);
+    // The source is removed by glib after the child exited.
+    SourceHandle::new(glib::child_watch_add_local(pid, move |_pid, status| {
+        if let Some(constructor) = constructor.take() {
+            let _ = stream.try_emit(constructor(status));
+        }
+    }))
+}
+
+/// Get a stream whose messages are sent to `stream` only after no message was emitted to it for
+/// `delay` ms: only the last message of a burst is sent, for instance to search once the user
+/// stopped typing.
+///
+/// Keep the returned stream as long as it is used: dropping it cancels the pending message.
+pub fn debounce<MSG: 'static>(stream: &StreamHandle<MSG>, delay: u32) -> EventStream<MSG> {
+    let target = stream.clone();
+    let debounced = EventStream::with_context(&MainContext::default());
+    let mut pending: Option<SourceHandle> = None;
+    debounced.set_callback(move |msg| {
+        // Dropping the handle removes the timeout of the previous message.
+        pending.take();
+        if target.is_alive() {
+            let target = target.clone();
+            let mut msg = Some(msg);
+            pending.replace(SourceHandle::new(glib::timeout_add_local(delay, move || {
+                if let Some(msg) = msg.take() {
+                    let _ = target.try_emit(msg);
+                }
+                Continue(false)
+            })));
+        }
+    });
+    debounced
+}
+
+struct Throttle<MSG> {
+    pending: Option<MSG>,
+    source: Option<SourceHandle>,
+}
+
+/// Get a stream whose messages are sent to `stream` at most once every `interval` ms: the first
+/// message is sent right away, then the last message emitted during each interval is sent at its
+/// end.
+///
+/// Keep the returned stream as long as it is used: dropping it cancels the pending message.
+pub fn throttle<MSG: 'static>(stream: &StreamHandle<MSG>, interval: u32) -> EventStream<MSG> {
+    let target = stream.clone();
+    let throttled = EventStream::with_context(&MainContext::default());
+    let state = Rc::new(RefCell::new(Throttle {
+        pending: None,
+        source: None,
+    }));
+    throttled.set_callback(move |msg| {
+        if !target.is_alive() {
+            state.borrow_mut().source = None;
+            return;
+        }
+        let mut throttle = state.borrow_mut();
+        if throttle.source.as_ref().map(|source| !source.is_cancelled()).unwrap_or(false) {
+            throttle.pending = Some(msg);
+            return;
+        }
+        let _ = target.try_emit(msg);
+        let target = target.clone();
+        let state = Rc::downgrade(&state);
+        throttle.source = Some(SourceHandle::new(glib::timeout_add_local(interval, move || {
+            Continue(emit_pending(&state, &target))
+        })));
+    });
+    throttled
+}
+
+// Send the message emitted during the last interval, if any, and return whether the interval must
+// be restarted.
+fn emit_pending<MSG>(state: &Weak<RefCell<Throttle<MSG>>>, target: &StreamHandle<MSG>) -> bool {
+    let msg = state.upgrade().and_then(|state| state.borrow_mut().pending.take());
+    match msg {
+        Some(msg) => target.try_emit(msg).is_ok(),
+        None => false,
+    }
+}
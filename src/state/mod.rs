@@ -35,20 +35,36 @@
 mod into;
 mod macros;
 
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::future::Future;
+use std::rc::{Rc, Weak};
 use std::time::SystemTime;
 
+use glib::MainContext;
+
 pub use crate::core::{EventStream, StreamHandle};
+use crate::core::CancellationToken;
+use crate::subscription::Subscription;
+#[cfg(feature = "trace")]
+use crate::trace::StreamTracer;
 
 pub use self::into::{IntoOption, IntoPair};
 
 /// Handle event stream to send messages to the [`update()`](trait.Update.html#tymethod.update) method.
 pub struct Relm<UPDATE: Update> {
+    // The tokens to cancel when the component is destroyed, shared by the clones.
+    cancellations: Rc<RefCell<Vec<CancellationToken>>>,
+    // Set when the component is initialized, shared by the clones.
+    state: Rc<RefCell<Weak<RefCell<UPDATE>>>>,
     stream: StreamHandle<UPDATE::Msg>,
 }
 
 impl<UPDATE: Update> Clone for Relm<UPDATE> {
     fn clone(&self) -> Self {
         Relm {
+            cancellations: self.cancellations.clone(),
+            state: self.state.clone(),
             stream: self.stream.clone(),
         }
     }
@@ -58,15 +74,128 @@ impl<UPDATE: Update> Relm<UPDATE> {
     /// Create a new relm stream handler.
     pub fn new(stream: &EventStream<UPDATE::Msg>) -> Self {
         Relm {
+            cancellations: Rc::new(RefCell::new(vec![])),
+            state: Rc::new(RefCell::new(Weak::new())),
             stream: stream.downgrade(),
         }
     }
 
+    /// Create a `CancellationToken` that is cancelled when the component is destroyed, i.e. when
+    /// its stream is closed or dropped, to stop the operations it started in other threads.
+    pub fn cancellation(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        let mut cancellations = self.cancellations.borrow_mut();
+        // Forget the tokens that nobody else uses anymore.
+        cancellations.retain(|token| !token.is_unique() && !token.is_cancelled());
+        cancellations.push(token.clone());
+        token
+    }
+
+    /// Call `callback` with the component, for instance to read its model from a signal handler
+    /// like the `view!` macro does for a return value using `self.model`.
+    ///
+    /// Nothing is called and `None` is returned when the component cannot be borrowed: before it is
+    /// initialized, after it is destroyed, and while it is updating, i.e. when the signal is emitted
+    /// from [`update()`](trait.Update.html#tymethod.update) of this same component.
+    pub fn with_state<CALLBACK, RETURN>(&self, callback: CALLBACK) -> Option<RETURN>
+        where CALLBACK: FnOnce(&UPDATE) -> RETURN,
+    {
+        let state = self.state.borrow().upgrade()?;
+        let state = state.try_borrow().ok()?;
+        Some(callback(&state))
+    }
+
+    /// Get a handle holding the stream of the component, its main context and a
+    /// `CancellationToken` cancelled when the component is destroyed.
+    /// The main context is the one the stream dispatches its messages from, or the thread-default
+    /// one for a detached stream.
+    /// Unlike `Relm`, it doesn't depend on the widget type, so it is easier to store in helpers.
+    pub fn handle(&self) -> RelmHandle<UPDATE::Msg> {
+        RelmHandle {
+            cancellation: self.cancellation(),
+            context: self.stream.context().unwrap_or_else(MainContext::ref_thread_default),
+            stream: self.stream.clone(),
+        }
+    }
+
     /// Get the event stream of this stream.
     /// This is used internally by the library.
     pub fn stream(&self) -> &StreamHandle<UPDATE::Msg> {
         &self.stream
     }
+
+    /// Emit `msg` and dispatch it to [`update()`](trait.Update.html#tymethod.update) right away,
+    /// along with the other messages waiting in the stream.
+    /// When called from `update()`, `msg` is only queued, like with `emit()`, and is dispatched
+    /// after `update()` returns.
+    ///
+    /// ## Panics
+    /// Panics if the `EventStream` was dropped.
+    pub fn emit_now(&self, msg: UPDATE::Msg) {
+        self.stream.emit(msg);
+        let _ = self.stream.dispatch_pending();
+    }
+
+    /// Spawn `future` on the thread-default main context and emit the message returned by `map`
+    /// when it completes.
+    /// The output is discarded if the stream was dropped in the meantime.
+    ///
+    /// ## Panics
+    /// Panics if the thread-default main context is owned by another thread.
+    pub fn spawn_local<F, MAP>(&self, future: F, map: MAP)
+        where F: Future + 'static,
+              MAP: FnOnce(F::Output) -> UPDATE::Msg + 'static,
+    {
+        let stream = self.stream.clone();
+        MainContext::ref_thread_default().spawn_local(async move {
+            let output = future.await;
+            let _ = stream.try_emit(map(output));
+        });
+    }
+}
+
+/// Handle to a component, returned by [`Relm::handle()`](struct.Relm.html#method.handle), that is
+/// only parameterized by its message type.
+///
+/// It can be given to the functions taking a stream, like [`interval()`](fn.interval.html) or
+/// [`connect_async()`](fn.connect_async.html).
+pub struct RelmHandle<MSG> {
+    cancellation: CancellationToken,
+    context: MainContext,
+    stream: StreamHandle<MSG>,
+}
+
+impl<MSG> AsRef<StreamHandle<MSG>> for RelmHandle<MSG> {
+    fn as_ref(&self) -> &StreamHandle<MSG> {
+        &self.stream
+    }
+}
+
+impl<MSG> Clone for RelmHandle<MSG> {
+    fn clone(&self) -> Self {
+        RelmHandle {
+            cancellation: self.cancellation.clone(),
+            context: self.context.clone(),
+            stream: self.stream.clone(),
+        }
+    }
+}
+
+impl<MSG> RelmHandle<MSG> {
+    /// Get the token cancelled when the component is destroyed.
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Get the main context of the component.
+    pub fn context(&self) -> &MainContext {
+        &self.context
+    }
+
+    /// Get the event stream of the component.
+    pub fn stream(&self) -> &StreamHandle<MSG> {
+        &self.stream
+    }
 }
 
 /// Trait for a basic (non-widget) component.
@@ -81,16 +210,41 @@ pub trait Update
     /// The type of the parameter of the model() function used to initialize the model.
     type ModelParam: Sized;
     /// The type of the messages sent to the [`update()`](trait.Update.html#tymethod.update) method.
-    type Msg;
+    type Msg: 'static;
 
     /// Create the initial model.
     fn model(relm: &Relm<Self>, param: Self::ModelParam) -> Self::Model;
 
+    /// Method called right before the component is destroyed, i.e. when its stream is closed or
+    /// dropped.
+    /// This is the place to cancel the `Channel`s and timers created by the component.
+    ///
+    /// When the component destroys itself from [`update()`](trait.Update.html#tymethod.update),
+    /// e.g. a dialog closing its own window, this method is called right after `update()` returns,
+    /// so after the subscriptions were stopped.
+    fn on_destroy(&mut self) {
+    }
+
     /// Connect the subscriptions.
     /// Subscriptions are `Future`/`Stream` that are spawn when the object is created.
+    ///
+    /// Unlike with [`subscribe()`](trait.Update.html#method.subscribe), what is started here is not
+    /// stopped automatically when the component is destroyed.
     fn subscriptions(&mut self, _relm: &Relm<Self>) {
     }
 
+    /// Get the long-lived sources of messages of the component, like timers, channels or file
+    /// monitors.
+    /// They are started after the view is initialized and stopped automatically when the
+    /// component is destroyed.
+    ///
+    /// Unlike [`subscriptions()`](trait.Update.html#method.subscriptions), which starts the
+    /// sources itself and has to stop them itself, this method only returns
+    /// [`Subscription`](struct.Subscription.html)s, whose setup and teardown are run by relm.
+    fn subscribe(_relm: &Relm<Self>) -> Vec<Subscription<Self::Msg>> {
+        vec![]
+    }
+
     /// Method called when a message is received from an event.
     fn update(&mut self, event: Self::Msg);
 }
@@ -111,6 +265,65 @@ pub trait UpdateNew: Update {
 pub trait DisplayVariant {
     /// Formats the current variant of the enum.
     fn display_variant(&self) -> &'static str;
+
+    /// Formats the message with `Debug`, if it implements it.
+    ///
+    /// `#[derive(Msg)]` implements this method for the enums implementing `Debug`.
+    fn debug_message(&self) -> Option<String> {
+        None
+    }
+}
+
+/*
+ * Wrapper used by #[derive(Msg)] to format a message with Debug only if it implements it, without
+ * specialization: the method call (&&DebugMessage(msg)).debug_message() resolves to the impl on
+ * &DebugMessage when MSG: Debug and falls back to the impl on DebugMessage by auto-deref.
+ */
+#[doc(hidden)]
+pub struct DebugMessage<'a, MSG>(pub &'a MSG);
+
+#[doc(hidden)]
+pub trait DebuggableMessage {
+    fn debug_message(&self) -> Option<String>;
+}
+
+impl<'a, MSG: Debug> DebuggableMessage for &DebugMessage<'a, MSG> {
+    fn debug_message(&self) -> Option<String> {
+        Some(format!("{:?}", self.0))
+    }
+}
+
+#[doc(hidden)]
+pub trait UndebuggableMessage {
+    fn debug_message(&self) -> Option<String>;
+}
+
+impl<'a, MSG> UndebuggableMessage for DebugMessage<'a, MSG> {
+    fn debug_message(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Introspection of the variants of a message enum.
+///
+/// `#[derive(Msg)]` implements this trait, along with an inherent `variant_name()` method
+/// returning the name of the current variant, which does not require the variants' parameters to
+/// implement `Debug`.
+pub trait MessageVariant {
+    /// Get the index of the current variant, in declaration order.
+    fn variant_index(&self) -> usize;
+
+    /// Get the number of variants of the enum.
+    fn variant_count() -> usize
+        where Self: Sized;
+
+    /// Check if the current variant is saved by the recorder of the `serde` feature.
+    ///
+    /// `#[derive(Msg)]` returns `false` for the variants having the `#[msg(skip_record)]`
+    /// attribute.
+    fn is_recorded(&self) -> bool {
+        true
+    }
 }
 
 impl DisplayVariant for () {
@@ -121,29 +334,68 @@ impl DisplayVariant for () {
 
 /// Create a bare component, i.e. a component only implementing the Update trait, not the Widget
 /// trait.
+/// Its messages are dispatched by the main loop of the default main context, which doesn't require
+/// GTK to be initialized.
 pub fn execute<UPDATE>(model_param: UPDATE::ModelParam) -> EventStream<UPDATE::Msg>
 where UPDATE: Update + UpdateNew + 'static
 {
-    let stream = EventStream::new();
+    let stream = EventStream::with_context(&MainContext::default());
+    stream.set_name(std::any::type_name::<UPDATE>());
 
     let relm = Relm::new(&stream);
     let model = UPDATE::model(&relm, model_param);
     let component = UPDATE::new(&relm, model);
 
-    init_component::<UPDATE>(&stream, component, &relm);
+    let _ = init_component::<UPDATE>(&stream, component, &relm);
     stream
 }
 
 /// Initialize a component by creating its subscriptions and dispatching the messages from the
 /// stream.
+/// The component is owned by the stream, so only a weak reference to it is returned.
 pub fn init_component<UPDATE>(stream: &EventStream<UPDATE::Msg>, mut component: UPDATE, relm: &Relm<UPDATE>)
+    -> Weak<RefCell<UPDATE>>
     where UPDATE: Update + 'static,
           UPDATE::Msg: DisplayVariant + 'static,
 {
+    #[cfg(feature = "trace")]
+    stream.set_tracer(StreamTracer::new(std::any::type_name::<UPDATE>()));
     component.subscriptions(relm);
+    let teardowns: Vec<_> = UPDATE::subscribe(relm).into_iter()
+        .map(|subscription| subscription.start(relm.stream()))
+        .collect();
+    let component = Rc::new(RefCell::new(component));
+    // Set when the stream is closed while the component is borrowed, to call on_destroy() once it
+    // is released.
+    let destroy_pending = Rc::new(Cell::new(false));
+    {
+        let component = component.clone();
+        let cancellations = relm.cancellations.clone();
+        let destroy_pending = destroy_pending.clone();
+        stream.on_close(move || {
+            match component.try_borrow_mut() {
+                Ok(mut component) => component.on_destroy(),
+                // The component is borrowed if it destroys itself in update().
+                Err(_) => destroy_pending.set(true),
+            }
+            for teardown in teardowns {
+                teardown();
+            }
+            for token in cancellations.borrow_mut().drain(..) {
+                token.cancel();
+            }
+        });
+    }
+    let state = Rc::downgrade(&component);
+    *relm.state.borrow_mut() = state.clone();
     stream.set_callback(move |event| {
-        update_component(&mut component, event);
+        let mut component = component.borrow_mut();
+        update_component(&mut *component, event);
+        if destroy_pending.replace(false) {
+            component.on_destroy();
+        }
     });
+    state
 }
 
 fn update_component<COMPONENT>(component: &mut COMPONENT, event: COMPONENT::Msg)
@@ -0,0 +1,132 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::fmt::Debug;
+
+use crate::state::DisplayVariant;
+
+/// A state machine changing its state according to a table of transitions, to which the
+/// [`update()`](trait.Update.html#tymethod.update) method of a component can delegate.
+///
+/// A transition is selected by the current state and the variant of the message, as returned by
+/// [`DisplayVariant::display_variant()`](trait.DisplayVariant.html#tymethod.display_variant).
+/// The messages without a transition for the current state are sent to the illegal transition
+/// handler, which logs a warning by default.
+///
+/// ```ignore
+/// let machine = StateMachine::new(State::Idle)
+///     .transition(State::Idle, "Load", State::Loading)
+///     .transition_with(State::Loading, "Loaded", move |msg| {
+///         stream.emit(Msg::Show(...));
+///         State::Loaded
+///     })
+///     .on_illegal(move |state, msg| error_stream.emit(Msg::Error(format!("{:?}", state))));
+/// ```
+///
+/// With the `trace` feature, the transitions are traced with the states before and after them.
+pub struct StateMachine<STATE, MSG> {
+    on_illegal: IllegalHandler<STATE, MSG>,
+    state: STATE,
+    transitions: Vec<Transition<STATE, MSG>>,
+}
+
+type IllegalHandler<STATE, MSG> = Box<dyn FnMut(&STATE, MSG)>;
+
+struct Transition<STATE, MSG> {
+    from: STATE,
+    handler: Box<dyn FnMut(MSG) -> STATE>,
+    variant: &'static str,
+}
+
+impl<STATE, MSG> StateMachine<STATE, MSG>
+where STATE: Clone + Debug + PartialEq + 'static,
+      MSG: DisplayVariant + 'static,
+{
+    /// Create a state machine in the `initial` state, without transitions.
+    pub fn new(initial: STATE) -> Self {
+        StateMachine {
+            on_illegal: Box::new(|state, msg: MSG| {
+                log::warn!("Illegal transition from the state {:?} on message {}", state, msg.display_variant())
+            }),
+            state: initial,
+            transitions: vec![],
+        }
+    }
+
+    /// Go from the state `from` to the state `to` when receiving a message of the variant
+    /// named `variant`.
+    pub fn transition(self, from: STATE, variant: &'static str, to: STATE) -> Self {
+        self.transition_with(from, variant, move |_| to.clone())
+    }
+
+    /// When receiving a message of the variant named `variant` in the state `from`, call
+    /// `handler`, which can do side effects, like emitting messages, and returns the new state.
+    pub fn transition_with<F>(mut self, from: STATE, variant: &'static str, handler: F) -> Self
+    where F: FnMut(MSG) -> STATE + 'static,
+    {
+        self.transitions.push(Transition {
+            from,
+            handler: Box::new(handler),
+            variant,
+        });
+        self
+    }
+
+    /// Call `handler` with the current state and the message when there is no transition for
+    /// it, instead of logging a warning.
+    /// Use `|_, _| ()` to ignore these messages.
+    pub fn on_illegal<F>(mut self, handler: F) -> Self
+    where F: FnMut(&STATE, MSG) + 'static,
+    {
+        self.on_illegal = Box::new(handler);
+        self
+    }
+
+    /// Get the current state.
+    pub fn state(&self) -> &STATE {
+        &self.state
+    }
+
+    /// Do the transition for `msg` from the current state.
+    /// Return `false` if there was no such transition, after calling the illegal transition
+    /// handler.
+    pub fn handle(&mut self, msg: MSG) -> bool {
+        let variant = msg.display_variant();
+        let state = &self.state;
+        match self.transitions.iter_mut().find(|transition| transition.variant == variant && transition.from == *state) {
+            Some(transition) => {
+                // The message is moved to the handler, so it is formatted before.
+                #[cfg(feature = "trace")]
+                let message = msg.debug_message();
+                let next_state = (transition.handler)(msg);
+                #[cfg(feature = "trace")]
+                crate::trace::trace_transition(variant, message.as_deref(), &self.state, &next_state);
+                self.state = next_state;
+                true
+            },
+            None => {
+                (self.on_illegal)(&self.state, msg);
+                false
+            },
+        }
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use glib::{Cast, IsA, ObjectExt};
+use gtk::{ContainerExt, CssProviderExt, StyleContextExt, WidgetExt};
+
+const CSS_PROVIDER_KEY: &str = "relm-css-provider";
+const STYLE_CLASSES_KEY: &str = "relm-style-classes";
+
+/// Set the style classes of `widget`, which is what the `style_classes` property of the `view!`
+/// macro does.
+///
+/// The classes previously set by this function that are not in `classes` are removed, while the
+/// other classes of the widget are kept.
+pub fn set_style_classes<WIDGET, CLASSES, CLASS>(widget: &WIDGET, classes: CLASSES)
+    where WIDGET: IsA<gtk::Widget>,
+          CLASSES: IntoIterator<Item=CLASS>,
+          CLASS: AsRef<str>,
+{
+    let widget = widget.as_ref();
+    let style_context = widget.get_style_context();
+    let classes: Vec<String> = classes.into_iter()
+        .map(|class| class.as_ref().to_string())
+        .collect();
+    // The data under this key is always set by this function, with this type.
+    let previous_classes = unsafe { widget.get_data::<Vec<String>>(STYLE_CLASSES_KEY) }
+        .cloned()
+        .unwrap_or_default();
+    for class in previous_classes.iter().filter(|class| !classes.contains(class)) {
+        style_context.remove_class(class);
+    }
+    for class in &classes {
+        style_context.add_class(class);
+    }
+    unsafe {
+        widget.set_data(STYLE_CLASSES_KEY, classes);
+    }
+}
+
+/// Style `widget` and its descendants with the stylesheet `css`, which is what the `css` property
+/// of the `view!` macro does.
+///
+/// Unlike a provider added for the whole screen, the stylesheet only applies to the widgets that
+/// are in `widget` when this function is called. Calling it again replaces the previous stylesheet.
+///
+/// ## Panics
+/// Panics if `css` cannot be parsed.
+pub fn set_css<WIDGET: IsA<gtk::Widget>>(widget: &WIDGET, css: &str) {
+    let widget = widget.as_ref();
+    let provider = gtk::CssProvider::new();
+    if let Err(error) = provider.load_from_data(css.as_bytes()) {
+        panic!("Invalid CSS: {}", error);
+    }
+    // The data under this key is always set by this function, with this type.
+    let previous_provider = unsafe { widget.get_data::<gtk::CssProvider>(CSS_PROVIDER_KEY) }.cloned();
+    add_provider(widget, previous_provider.as_ref(), &provider);
+    unsafe {
+        widget.set_data(CSS_PROVIDER_KEY, provider);
+    }
+}
+
+fn add_provider(widget: &gtk::Widget, previous_provider: Option<&gtk::CssProvider>, provider: &gtk::CssProvider) {
+    let style_context = widget.get_style_context();
+    if let Some(previous_provider) = previous_provider {
+        style_context.remove_provider(previous_provider);
+    }
+    style_context.add_provider(provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+    if let Some(container) = widget.downcast_ref::<gtk::Container>() {
+        for child in container.get_children() {
+            add_provider(&child, previous_provider, provider);
+        }
+    }
+}
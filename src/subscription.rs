@@ -0,0 +1,137 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+use std::cell::RefCell;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+
+use gio::{File, FileMonitorEvent};
+#[cfg(unix)]
+use glib::IOCondition;
+
+use crate::core::{Channel, Sender, StreamHandle};
+use crate::monitor::{connect_monitor, create_monitor};
+use crate::sources::interval_cancellable;
+#[cfg(unix)]
+use crate::sources::{io_watch, unix_signal};
+
+type Setup<MSG> = Box<dyn FnOnce(&StreamHandle<MSG>) -> Teardown>;
+
+type Teardown = Box<dyn FnOnce()>;
+
+/// A long-lived source of messages of a component, returned by
+/// [`Update::subscribe()`](trait.Update.html#method.subscribe).
+///
+/// It is started after the view of the component is initialized and stopped when the component is
+/// destroyed, so that no message is emitted after the window is closed.
+pub struct Subscription<MSG> {
+    setup: Setup<MSG>,
+}
+
+impl<MSG: 'static> Subscription<MSG> {
+    /// Create a subscription from a `setup` function, which receives the stream of the component
+    /// and returns the function that stops emitting messages to it.
+    pub fn new<SETUP, TEARDOWN>(setup: SETUP) -> Self
+    where SETUP: FnOnce(&StreamHandle<MSG>) -> TEARDOWN + 'static,
+          TEARDOWN: FnOnce() + 'static,
+    {
+        Subscription {
+            setup: Box::new(move |stream| Box::new(setup(stream))),
+        }
+    }
+
+    /// Create a channel whose values are converted to messages by `map`.
+    /// The `Sender` can be given to another thread.
+    pub fn channel<T, MAP>(map: MAP) -> (Self, Sender<T>)
+    where MAP: Fn(T) -> MSG + 'static,
+          T: 'static,
+    {
+        // The stream is only known when the subscription is started.
+        let target: Rc<RefCell<Option<StreamHandle<MSG>>>> = Rc::new(RefCell::new(None));
+        let (channel, sender) = {
+            let target = target.clone();
+            Channel::new(move |value| {
+                if let Some(ref stream) = *target.borrow() {
+                    let _ = stream.try_emit(map(value));
+                }
+            })
+        };
+        let subscription = Subscription::new(move |stream| {
+            *target.borrow_mut() = Some(stream.clone());
+            move || channel.close()
+        });
+        (subscription, sender)
+    }
+
+    /// Emit the message returned by `constructor` every time `file`, or a file of the directory
+    /// `file`, changes.
+    /// See [`file_monitor()`](fn.file_monitor.html).
+    pub fn file_monitor<F>(file: &File, rate_limit: u32, constructor: F) -> Result<Self, glib::Error>
+    where F: Fn(FileMonitorEvent, Option<File>) -> MSG + 'static,
+    {
+        let monitor = create_monitor(file, rate_limit)?;
+        Ok(Subscription::new(move |stream| {
+            let handle = connect_monitor(monitor, stream, constructor);
+            move || handle.cancel()
+        }))
+    }
+
+    /// Emit the message returned by `constructor` every `duration` ms.
+    pub fn interval<F>(duration: u32, constructor: F) -> Self
+    where F: Fn() -> MSG + 'static,
+    {
+        Subscription::new(move |stream| {
+            let handle = interval_cancellable(stream, duration, constructor);
+            move || handle.cancel()
+        })
+    }
+
+    /// Emit the message returned by `constructor` every time the file descriptor `fd` satisfies
+    /// `condition`.
+    /// See [`io_watch()`](fn.io_watch.html).
+    #[cfg(unix)]
+    pub fn io_watch<F>(fd: RawFd, condition: IOCondition, constructor: F) -> Self
+    where F: Fn(IOCondition) -> MSG + 'static,
+    {
+        Subscription::new(move |stream| {
+            let handle = io_watch(fd, condition, stream, constructor);
+            move || handle.cancel()
+        })
+    }
+
+    /// Emit `msg` every time the process receives the unix signal `signum`.
+    /// See [`unix_signal()`](fn.unix_signal.html).
+    #[cfg(unix)]
+    pub fn unix_signal(signum: i32, msg: MSG) -> Self
+    where MSG: Clone,
+    {
+        Subscription::new(move |stream| {
+            let handle = unix_signal(signum, stream, msg);
+            move || handle.cancel()
+        })
+    }
+
+    pub(crate) fn start(self, stream: &StreamHandle<MSG>) -> Teardown {
+        (self.setup)(stream)
+    }
+}
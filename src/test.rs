@@ -0,0 +1,67 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Unit test the `update()` method of components without a main loop and without initializing
+//! GTK.
+//!
+//! The component is created with a stream that is not attached to any main context (see
+//! [`EventStream::new_detached()`](../struct.EventStream.html#method.new_detached)), so that the
+//! messages it emits are collected instead of being dispatched:
+//!
+//! ```ignore
+//! let (mut counter, stream) = relm::test::new_component::<Counter>(());
+//! let emitted = relm::test::drive_update(&mut counter, &stream, vec![Increment, Increment]);
+//! assert_eq!(counter.model.value, 2);
+//! assert_eq!(emitted, vec![Changed(1), Changed(2)]);
+//! ```
+
+use crate::core::EventStream;
+use crate::state::{Relm, Update, UpdateNew};
+
+/// Create a component with a detached stream, which is returned along with it.
+/// The subscriptions of the component are not started.
+pub fn new_component<UPDATE>(model_param: UPDATE::ModelParam) -> (UPDATE, EventStream<UPDATE::Msg>)
+where UPDATE: Update + UpdateNew,
+{
+    let stream = EventStream::new_detached();
+    stream.set_name(std::any::type_name::<UPDATE>());
+    let relm = Relm::new(&stream);
+    let model = UPDATE::model(&relm, model_param);
+    let component = UPDATE::new(&relm, model);
+    (component, stream)
+}
+
+/// Send each message of `msgs` to the `update()` method of `component` and return the messages
+/// emitted on `stream` since the last call, in order, including those emitted while creating
+/// the component.
+/// The emitted messages are not sent back to `update()`: they stay for the test to check them.
+pub fn drive_update<UPDATE, MSGS>(component: &mut UPDATE, stream: &EventStream<UPDATE::Msg>, msgs: MSGS)
+    -> Vec<UPDATE::Msg>
+where UPDATE: Update,
+      MSGS: IntoIterator<Item=UPDATE::Msg>,
+{
+    let mut emitted = stream.drain_pending();
+    for msg in msgs {
+        component.update(msg);
+        emitted.extend(stream.drain_pending());
+    }
+    emitted
+}
@@ -0,0 +1,156 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Tracing of the messages emitted and dispatched by the components, enabled by the `trace`
+//! feature.
+//!
+//! Every message is logged at the trace level with the component name, the message formatted
+//! with `Debug` when the message type implements it (otherwise, its variant name) and a sequence
+//! number, and is sent to the tracer set by [`set_tracer()`](fn.set_tracer.html).
+//! So are the transitions of the [`StateMachine`s](struct.StateMachine.html), along with the
+//! states before and after them.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::state::DisplayVariant;
+
+/// The step of the life of a message that is traced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceKind {
+    /// The message was emitted on the stream of the component.
+    Emit,
+    /// The message is about to be sent to the `update()` method of the component.
+    Dispatch,
+    /// The message made a [`StateMachine`](../struct.StateMachine.html) change its state.
+    Transition,
+}
+
+/// A traced message, sent to the tracer set by [`set_tracer()`](fn.set_tracer.html).
+#[derive(Debug)]
+pub struct TraceEvent<'a> {
+    /// Number incremented for every traced event, so that the events can be ordered.
+    pub sequence: u64,
+    /// Whether the message was emitted, dispatched or made a state machine change its state.
+    pub kind: TraceKind,
+    /// The type name of the component, or of the states for a transition.
+    pub component: &'a str,
+    /// The name of the variant of the message.
+    pub variant: &'static str,
+    /// The message formatted with `Debug`, when the message type implements it.
+    pub message: Option<&'a str>,
+    /// The states before and after a transition, formatted with `Debug`.
+    pub transition: Option<(&'a str, &'a str)>,
+}
+
+type Tracer = Rc<dyn Fn(TraceEvent)>;
+
+thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = RefCell::new(None);
+}
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Call `tracer` for every message emitted or dispatched by the components of this thread,
+/// replacing the previous tracer.
+pub fn set_tracer<F: Fn(TraceEvent) + 'static>(tracer: F) {
+    TRACER.with(|current| *current.borrow_mut() = Some(Rc::new(tracer)));
+}
+
+/// Remove the tracer set by [`set_tracer()`](fn.set_tracer.html).
+/// The messages are still logged.
+pub fn remove_tracer() {
+    TRACER.with(|current| *current.borrow_mut() = None);
+}
+
+type Describe<MSG> = fn(&MSG) -> (&'static str, Option<String>);
+
+// Set on the stream of a component to trace its messages.
+pub(crate) struct StreamTracer<MSG> {
+    component: &'static str,
+    describe: Describe<MSG>,
+}
+
+impl<MSG> Clone for StreamTracer<MSG> {
+    fn clone(&self) -> Self {
+        StreamTracer {
+            component: self.component,
+            describe: self.describe,
+        }
+    }
+}
+
+impl<MSG: DisplayVariant> StreamTracer<MSG> {
+    pub(crate) fn new(component: &'static str) -> Self {
+        StreamTracer {
+            component,
+            describe: describe::<MSG>,
+        }
+    }
+}
+
+impl<MSG> StreamTracer<MSG> {
+    pub(crate) fn trace(&self, kind: TraceKind, msg: &MSG) {
+        let (variant, message) = (self.describe)(msg);
+        send(TraceEvent {
+            sequence: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            kind,
+            component: self.component,
+            variant,
+            message: message.as_deref(),
+            transition: None,
+        });
+    }
+}
+
+pub(crate) fn trace_transition<STATE: Debug>(variant: &'static str, message: Option<&str>, from: &STATE, to: &STATE) {
+    let from = format!("{:?}", from);
+    let to = format!("{:?}", to);
+    send(TraceEvent {
+        sequence: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        kind: TraceKind::Transition,
+        component: std::any::type_name::<STATE>(),
+        variant,
+        message,
+        transition: Some((&from, &to)),
+    });
+}
+
+// Log the event and send it to the tracer.
+fn send(event: TraceEvent) {
+    let message = event.message.unwrap_or(event.variant);
+    match event.transition {
+        Some((from, to)) =>
+            log::trace!("#{} {:?} {}: {} -> {} on {}", event.sequence, event.kind, event.component, from, to, message),
+        None => log::trace!("#{} {:?} {}: {}", event.sequence, event.kind, event.component, message),
+    }
+    // Clone the tracer so that it can call set_tracer().
+    let tracer = TRACER.with(|tracer| tracer.borrow().clone());
+    if let Some(tracer) = tracer {
+        tracer(event);
+    }
+}
+
+fn describe<MSG: DisplayVariant>(msg: &MSG) -> (&'static str, Option<String>) {
+    (msg.display_variant(), msg.debug_message())
+}
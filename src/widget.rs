@@ -21,7 +21,7 @@
 
 use glib::{IsA, Object};
 
-use super::{Relm, run};
+use super::{Error, Relm, run, run_with_args};
 use crate::state::Update;
 
 /// Trait to implement to manage widget's events.
@@ -60,12 +60,31 @@ pub trait Widget
     fn root(&self) -> Self::Root;
 
     /// Create the window from this widget and start the main loop.
-    fn run(model_param: Self::ModelParam) -> Result<(), glib::BoolError>
+    /// The model parameter is created with `Default::default()`, so that widgets taking no
+    /// parameter, i.e. `ModelParam = ()`, can be started with `Win::run()`.
+    fn run() -> Result<(), Error>
+        where Self: 'static,
+              Self::ModelParam: Default,
+    {
+        run::<Self>(Default::default())
+    }
+
+    /// Like [`run()`](trait.Widget.html#method.run), but with the parameter given to the
+    /// [`model()`](trait.Update.html#tymethod.model) method, e.g. `Win::run_with(Config { .. })`.
+    fn run_with(model_param: Self::ModelParam) -> Result<(), Error>
         where Self: 'static,
     {
         run::<Self>(model_param)
     }
 
+    /// Like [`run()`](trait.Widget.html#method.run), but initialize GTK with the command-line
+    /// `args`, including the program name.
+    fn run_with_args(model_param: Self::ModelParam, args: &[String]) -> Result<(), Error>
+        where Self: 'static,
+    {
+        run_with_args::<Self>(model_param, args)
+    }
+
     /// Create the initial view.
     fn view(relm: &Relm<Self>, model: Self::Model) -> Self;
 }
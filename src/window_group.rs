@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use glib::IsA;
+use gtk::WidgetExt;
+
+use crate::core::StreamHandle;
+use crate::state::DisplayVariant;
+use crate::widget::Widget;
+use crate::init;
+
+#[derive(Default)]
+struct Windows {
+    close_callbacks: Vec<Rc<dyn Fn(usize)>>,
+    components: HashMap<usize, Box<dyn Any>>,
+    next_id: usize,
+}
+
+/// Group of top-level relm windows created at runtime, like one window per opened document.
+///
+/// The group keeps the components of its windows alive and removes them when their window is
+/// destroyed, for instance when its `delete_event` handler returns `Inhibit(false)`.
+/// Cloning the group gives another handle to the same windows.
+#[derive(Clone, Default)]
+pub struct WindowGroup {
+    windows: Rc<RefCell<Windows>>,
+}
+
+impl WindowGroup {
+    /// Create an empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of windows of the group that were not destroyed yet.
+    pub fn active_windows(&self) -> usize {
+        self.windows.borrow().components.len()
+    }
+
+    /// Call `callback` with the number of remaining windows every time a window of the group is
+    /// destroyed, which can be used to quit the application when the last window is closed.
+    pub fn connect_window_closed<CALLBACK: Fn(usize) + 'static>(&self, callback: CALLBACK) {
+        self.windows.borrow_mut().close_callbacks.push(Rc::new(callback));
+    }
+
+    /// Create a new top-level window in the group and return the stream of its component.
+    pub fn spawn_window<WIDGET>(&self, model_param: WIDGET::ModelParam)
+        -> Result<StreamHandle<WIDGET::Msg>, glib::BoolError>
+        where WIDGET: Widget + 'static,
+              WIDGET::Msg: DisplayVariant + 'static,
+              WIDGET::Root: IsA<gtk::Widget>,
+    {
+        let component = init::<WIDGET>(model_param)?;
+        let stream = component.stream();
+        let root = component.widget().clone();
+        let id = {
+            let mut windows = self.windows.borrow_mut();
+            let id = windows.next_id;
+            windows.next_id += 1;
+            let _ = windows.components.insert(id, Box::new(component));
+            id
+        };
+
+        // Only keep weak references in the handler since the window is owned by the group.
+        let windows = Rc::downgrade(&self.windows);
+        let _ = root.connect_destroy(move |_| {
+            if let Some(windows) = windows.upgrade() {
+                // The component is dropped after the group is released, so that its on_destroy()
+                // can use the group.
+                let component = windows.borrow_mut().components.remove(&id);
+                if component.is_some() {
+                    drop(component);
+                    let (remaining, callbacks) = {
+                        let windows = windows.borrow();
+                        (windows.components.len(), windows.close_callbacks.clone())
+                    };
+                    for callback in callbacks {
+                        callback(remaining);
+                    }
+                }
+            }
+        });
+        Ok(stream)
+    }
+}